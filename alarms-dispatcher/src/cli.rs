@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+
+use chain_comms::cli::{self, Args};
+
+use crate::error::Application;
+
+pub(crate) const DEFAULT_CONFIG_PATH: &str = "alarms-dispatcher.toml";
+
+/// Usage of the service.
+pub(crate) const USAGE: &str = "\
+Usage: alarms-dispatcher [--config <FILE>] [COMMAND]
+
+Options:
+    --config <FILE>        Configuration file to use. Defaults to
+                           \"alarms-dispatcher.toml\".
+    -h, --help             Print this message.
+    -V, --version          Print the version.
+
+Commands:
+    run                    Dispatch alarms, as configured. Default command.
+    check-config           Validate the configuration file and exit.
+    version                Print the version.
+    help                   Print this message.
+
+Exit codes:
+    0                      Success.
+    1                      Failure while running.
+    2                      Invalid command-line arguments.
+    3                      Invalid or unreadable configuration.";
+
+#[derive(Debug, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct Cli {
+    pub config_path: PathBuf,
+    pub command: Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Command {
+    Run,
+    CheckConfig,
+    Version,
+    Help,
+}
+
+pub(crate) fn parse<I>(args: I) -> Result<Cli, Application>
+where
+    I: IntoIterator<Item = String>,
+{
+    let Args {
+        config_path,
+        command,
+        rest,
+    }: Args = cli::parse(args, DEFAULT_CONFIG_PATH)
+        .map_err(|error: String| invalid_arguments(&error))?;
+
+    let command: Command = match command.as_deref().unwrap_or("run") {
+        "run" => Command::Run,
+        "check-config" => Command::CheckConfig,
+        "version" => Command::Version,
+        "help" => Command::Help,
+        command => {
+            return Err(invalid_arguments(&format!(
+                "Unknown command \"{command}\"!"
+            )));
+        },
+    };
+
+    if let Some(arg) = rest.first() {
+        return Err(invalid_arguments(&format!(
+            "Unexpected argument \"{arg}\"!"
+        )));
+    }
+
+    Ok(Cli {
+        config_path,
+        command,
+    })
+}
+
+fn invalid_arguments(error: &str) -> Application {
+    Application::InvalidArguments(format!("{error}\n\n{USAGE}"))
+}
+
+#[cfg(test)]
+fn test_parse(args: &[&str]) -> Result<Cli, Application> {
+    parse(args.iter().copied().map(String::from))
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_commands() {
+    assert_eq!(
+        test_parse(&[]).unwrap(),
+        Cli {
+            config_path: PathBuf::from(DEFAULT_CONFIG_PATH),
+            command: Command::Run,
+        }
+    );
+
+    assert_eq!(
+        test_parse(&["--config", "instance-2.toml", "check-config"]).unwrap(),
+        Cli {
+            config_path: PathBuf::from("instance-2.toml"),
+            command: Command::CheckConfig,
+        }
+    );
+
+    assert_eq!(
+        test_parse(&["--config=instance-3.toml"])
+            .unwrap()
+            .config_path,
+        PathBuf::from("instance-3.toml")
+    );
+
+    for (args, command) in [
+        (&["run"][..], Command::Run),
+        (&["version"], Command::Version),
+        (&["-V"], Command::Version),
+        (&["--version"], Command::Version),
+        (&["help"], Command::Help),
+        (&["-h"], Command::Help),
+        (&["--help"], Command::Help),
+    ] {
+        assert_eq!(test_parse(args).unwrap().command, command, "{args:?}");
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_invalid_arguments() {
+    for args in [
+        &["dispatch"][..],
+        &["run", "--now"],
+        &["check-config", "extra"],
+        &["--config"],
+        &["--config", "a.toml", "--config", "b.toml"],
+        &["--unknown"],
+    ] {
+        assert!(
+            matches!(test_parse(args), Err(Application::InvalidArguments(_))),
+            "{args:?}"
+        );
+    }
+}
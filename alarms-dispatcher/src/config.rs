@@ -1,12 +1,11 @@
-use std::{
-    env::{var, VarError},
-    num::{NonZeroU32, NonZeroU64},
-};
+use std::num::{NonZeroU32, NonZeroU64};
 
 use serde::{Deserialize, Serialize};
 
 use broadcast::config::Config as BroadcastConfig;
-use chain_comms::config::Node as NodeConfig;
+use chain_comms::config::{
+    env_var, error::Interpolation, from_str_or_value, Node as NodeConfig,
+};
 
 #[derive(Debug, Deserialize)]
 #[must_use]
@@ -26,26 +25,17 @@ impl AsRef<NodeConfig> for Config {
 }
 
 impl TryFrom<File> for Config {
-    type Error = VarError;
+    type Error = Interpolation;
 
     fn try_from(value: File) -> Result<Self, Self::Error> {
-        match var("OVERRIDE_ADMIN_CONTRACT").map(String::into_boxed_str) {
-            Ok(admin_contract) => Ok(Self {
-                admin_contract,
-                broadcast: value.broadcast,
-                node: value.node,
-                time_alarms: value.time_alarms,
-                market_price_oracle: value.market_price_oracle,
-            }),
-            Err(VarError::NotPresent) => Ok(Self {
-                admin_contract: value.admin_contract,
-                broadcast: value.broadcast,
-                node: value.node,
-                time_alarms: value.time_alarms,
-                market_price_oracle: value.market_price_oracle,
-            }),
-            Err(error @ VarError::NotUnicode { .. }) => Err(error),
-        }
+        Ok(Self {
+            admin_contract: env_var("OVERRIDE_ADMIN_CONTRACT")?
+                .map_or(value.admin_contract, String::into_boxed_str),
+            broadcast: value.broadcast,
+            node: value.node,
+            time_alarms: value.time_alarms,
+            market_price_oracle: value.market_price_oracle,
+        })
     }
 }
 
@@ -53,7 +43,9 @@ impl TryFrom<File> for Config {
 #[must_use]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub(crate) struct AlarmsConfig {
+    #[serde(deserialize_with = "from_str_or_value")]
     pub max_alarms_group: NonZeroU32,
+    #[serde(deserialize_with = "from_str_or_value")]
     pub gas_limit_per_alarm: NonZeroU64,
 }
 
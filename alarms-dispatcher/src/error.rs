@@ -1,7 +1,10 @@
 use semver::Version;
 use thiserror::Error as ThisError;
 
-use chain_comms::reexport::cosmrs::proto::prost::EncodeError;
+use chain_comms::{
+    cli::Exit, reexport::cosmrs::proto::prost::EncodeError,
+    rpc_setup::error::Error as RpcSetupError,
+};
 
 #[derive(Debug, ThisError)]
 pub enum Application {
@@ -10,7 +13,7 @@ pub enum Application {
         #[from] tracing::dispatcher::SetGlobalDefaultError,
     ),
     #[error("Setting up RPC environment failed! Cause: {0}")]
-    RpcSetup(#[from] chain_comms::rpc_setup::error::Error),
+    RpcSetup(#[from] RpcSetupError),
     #[error("Failed to query admin contract! Cause: {0}")]
     QueryAdminContract(#[from] platform::error::Error),
     #[error("Failed to serialize version query message as JSON! Cause: {0}")]
@@ -29,6 +32,20 @@ pub enum Application {
     },
     #[error("Alarms dispatcher loop exited unexpectedly! Cause: {0}")]
     DispatchAlarms(#[from] DispatchAlarms),
+    #[error("Invalid command-line arguments! {0}")]
+    InvalidArguments(String),
+}
+
+impl Application {
+    pub const fn exit(&self) -> Exit {
+        match self {
+            Self::InvalidArguments(_) => Exit::Usage,
+            Self::RpcSetup(RpcSetupError::Configuration(_)) => {
+                Exit::Configuration
+            },
+            _ => Exit::Failure,
+        }
+    }
 }
 
 pub type AppResult<T> = Result<T, Application>;
@@ -62,3 +79,32 @@ pub enum CommitDispatchTx {
     #[error("Failed to deserialize response data! Cause: {0}")]
     DeserializeTxData(#[from] chain_comms::decode::error::Error),
 }
+
+#[cfg(test)]
+#[test]
+fn test_exit_codes() {
+    assert_eq!(
+        Application::InvalidArguments(String::new()).exit(),
+        Exit::Usage
+    );
+
+    assert_eq!(
+        Application::RpcSetup(RpcSetupError::Configuration(
+            chain_comms::config::error::Error::FileRead(
+                std::io::ErrorKind::NotFound.into()
+            )
+        ))
+        .exit(),
+        Exit::Configuration
+    );
+
+    assert_eq!(
+        Application::IncompatibleContractVersion {
+            contract: "oracle",
+            compatible: "^0.5.3".parse().unwrap(),
+            actual: Version::new(0, 4, 0),
+        }
+        .exit(),
+        Exit::Failure
+    );
+}
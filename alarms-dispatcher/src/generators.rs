@@ -11,8 +11,8 @@ use tracing::{error, info, warn};
 
 use broadcast::{
     generators::{
-        CommitError, CommitErrorType, CommitResultReceiver, CommitResultSender,
-        SpawnResult, TxRequest, TxRequestSender,
+        CommitError, CommitErrorType, CommitOutcome, CommitResultReceiver,
+        CommitResultSender, SpawnResult, TxRequest, TxRequestSender,
     },
     mode::Blocking,
 };
@@ -472,7 +472,7 @@ async fn receive_back_tx_hash(
     contract_address: &Arc<str>,
     contract_type: &str,
 ) -> Result<Option<TxHash>, ChannelClosedError> {
-    if let Some(result) = result_receiver.recv().await {
+    if let Some(CommitOutcome { result, .. }) = result_receiver.recv().await {
         match result {
             Ok(hash) => return Ok(Some(hash)),
             Err(CommitError {
@@ -5,7 +5,7 @@
     clippy::significant_drop_tightening
 )]
 
-use std::io;
+use std::{io, path::Path, process::ExitCode};
 
 use semver::{
     BuildMetadata as SemVerBuildMetadata, Comparator as SemVerComparator,
@@ -21,16 +21,22 @@ use tracing_subscriber::fmt::writer::MakeWriterExt as _;
 
 use crate::generators::TasksConfig;
 use chain_comms::{
+    cli::Exit,
     client::Client as NodeClient,
     interact::query,
-    rpc_setup::{prepare_rpc, RpcSetup},
+    rpc_setup::{error::Error as RpcSetupError, prepare_rpc, RpcSetup},
     signing_key::DEFAULT_COSMOS_HD_PATH,
 };
 
 use self::{
-    config::Config, error::AppResult, generators::Contract, messages::QueryMsg,
+    cli::{Cli, Command},
+    config::Config,
+    error::AppResult,
+    generators::Contract,
+    messages::QueryMsg,
 };
 
+mod cli;
 mod config;
 mod error;
 mod generators;
@@ -55,7 +61,61 @@ pub const TIME_ALARMS_COMPATIBLE_VERSION: SemVerComparator = SemVerComparator {
 pub const MAX_CONSEQUENT_ERRORS_COUNT: usize = 5;
 
 #[tokio::main]
-async fn main() -> AppResult<()> {
+async fn main() -> ExitCode {
+    let result: AppResult<()> = match cli::parse(std::env::args().skip(1)) {
+        Ok(Cli {
+            config_path,
+            command,
+        }) => run_command(&config_path, command).await,
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(()) => Exit::Success.into(),
+        Err(error) => {
+            eprintln!("{error}");
+
+            error.exit().into()
+        },
+    }
+}
+
+#[allow(clippy::future_not_send)]
+async fn run_command(config_path: &Path, command: Command) -> AppResult<()> {
+    match command {
+        Command::Run => run(config_path).await,
+        Command::CheckConfig => {
+            chain_comms::config::read::<Config, _>(config_path)
+                .await
+                .map(|_: Config| {
+                    println!(
+                        "Configuration file \"{}\" is valid.",
+                        config_path.display()
+                    );
+                })
+                .map_err(|error: chain_comms::config::error::Error| {
+                    RpcSetupError::from(error).into()
+                })
+        },
+        Command::Version => {
+            println!(
+                "alarms-dispatcher {version}, built on {built_on}, compatible with oracle {ORACLE_COMPATIBLE_VERSION} and time alarms {TIME_ALARMS_COMPATIBLE_VERSION}",
+                version = env!("CARGO_PKG_VERSION"),
+                built_on = env!("BUILD_START_TIME_DATE", "No build time provided!"),
+            );
+
+            Ok(())
+        },
+        Command::Help => {
+            println!("{}", cli::USAGE);
+
+            Ok(())
+        },
+    }
+}
+
+#[allow(clippy::future_not_send)]
+async fn run(config_path: &Path) -> AppResult<()> {
     let (log_writer, log_guard): (NonBlocking, non_blocking::WorkerGuard) =
         NonBlocking::new(rolling::hourly("./logs/", "dispatcher"));
 
@@ -66,7 +126,7 @@ async fn main() -> AppResult<()> {
         env!("BUILD_START_TIME_DATE", "No build time provided!")
     ));
 
-    let result: AppResult<()> = app_main().await;
+    let result: AppResult<()> = app_main(config_path).await;
 
     if let Err(error) = &result {
         error!(error = ?error, "{}", error);
@@ -78,16 +138,14 @@ async fn main() -> AppResult<()> {
 }
 
 #[allow(clippy::future_not_send)]
-async fn app_main() -> AppResult<()> {
-    let rpc_setup: RpcSetup<Config> = prepare_rpc::<Config, _>(
-        "alarms-dispatcher.toml",
-        DEFAULT_COSMOS_HD_PATH,
-    )
-    .await
-    .inspect(|_| info!("Connected to RPC successfully."))
-    .inspect_err(|error| {
-        error!(?error, "Failed to connect to RPC! Cause: {error}");
-    })?;
+async fn app_main(config_path: &Path) -> AppResult<()> {
+    let rpc_setup: RpcSetup<Config> =
+        prepare_rpc::<Config, _>(config_path, DEFAULT_COSMOS_HD_PATH)
+            .await
+            .inspect(|_| info!("Connected to RPC successfully."))
+            .inspect_err(|error| {
+                error!(?error, "Failed to connect to RPC! Cause: {error}");
+            })?;
 
     let contracts =
         fetch_contracts(&rpc_setup.node_client, &rpc_setup.config).await?;
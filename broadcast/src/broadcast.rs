@@ -8,7 +8,9 @@ use tokio::{
 use chain_comms::{client::Client as NodeClient, interact::commit};
 
 use crate::{
-    generators::{CommitError, CommitErrorType, CommitResultSender},
+    generators::{
+        CommitError, CommitErrorType, CommitOutcome, CommitResultSender,
+    },
     log, mode,
     preprocess::TxRequest,
     ApiAndConfiguration,
@@ -40,6 +42,7 @@ pub(crate) async fn sleep_and_broadcast_tx<Impl: mode::Impl>(
         api_and_configuration,
         tx_result_senders,
         tx_request.sender_id,
+        tx_request.request_id,
         tx_request.signed_tx_bytes,
     )
     .await
@@ -79,6 +82,7 @@ async fn broadcast_and_send_back_tx_hash<Impl: mode::Impl>(
     }: &mut ApiAndConfiguration,
     tx_result_senders: &BTreeMap<usize, CommitResultSender>,
     sender_id: usize,
+    request_id: u64,
     signed_tx_bytes: Vec<u8>,
 ) -> Result<ProcessingOutput, Vec<u8>> {
     const VERIFICATION_FAILED_CODE: u32 = 4;
@@ -114,6 +118,7 @@ async fn broadcast_and_send_back_tx_hash<Impl: mode::Impl>(
             poll_time,
             tx_result_senders,
             sender_id,
+            request_id,
             tx_response,
         ),
         SendBackTxHashResult::ChannelClosed
@@ -133,6 +138,7 @@ fn send_back_tx_hash(
     poll_time: Duration,
     tx_result_senders: &BTreeMap<usize, CommitResultSender>,
     sender_id: usize,
+    request_id: u64,
     tx_response: commit::Response,
 ) -> SendBackTxHashResult {
     let tx_hash = tx_response.tx_hash.clone();
@@ -140,17 +146,20 @@ fn send_back_tx_hash(
     let channel_closed = if let Some(sender) = tx_result_senders.get(&sender_id)
     {
         if sender
-            .send(if tx_response.code.is_ok() {
-                Ok(tx_response.tx_hash)
-            } else {
-                Err(CommitError {
-                    r#type: if tx_response.code.value() == 32 {
-                        CommitErrorType::InvalidAccountSequence
-                    } else {
-                        CommitErrorType::Unknown
-                    },
-                    tx_response,
-                })
+            .send(CommitOutcome {
+                request_id,
+                result: if tx_response.code.is_ok() {
+                    Ok(tx_response.tx_hash)
+                } else {
+                    Err(CommitError {
+                        r#type: if tx_response.code.value() == 32 {
+                            CommitErrorType::InvalidAccountSequence
+                        } else {
+                            CommitErrorType::Unknown
+                        },
+                        tx_response,
+                    })
+                },
             })
             .is_ok()
         {
@@ -59,6 +59,7 @@ where
                 fallback_gas_limit,
                 hard_gas_limit,
                 expiration,
+                request_id,
             }) => {
                 if matches!(Impl::filter(&expiration), FilterResult::NotExpired)
                 {
@@ -67,6 +68,7 @@ where
                         fallback_gas_limit,
                         hard_gas_limit,
                         expiration,
+                        request_id,
                     });
 
                     match requests_cache.entry(sender_id) {
@@ -13,6 +13,7 @@ where
     pub(crate) fallback_gas_limit: NonZeroU64,
     pub(crate) hard_gas_limit: NonZeroU64,
     pub(crate) expiration: Impl::Expiration,
+    pub(crate) request_id: u64,
 }
 
 pub type TxRequests<Impl> = BTreeMap<usize, Cell<Option<TxRequest<Impl>>>>;
@@ -2,6 +2,8 @@ use std::time::Duration;
 
 use serde::{de::Deserializer, Deserialize};
 
+use chain_comms::config::from_str_or_value;
+
 #[derive(Debug, Deserialize)]
 pub struct Config {
     #[serde(
@@ -27,5 +29,5 @@ fn deserialize_duration_in_seconds<'de, D>(
 where
     D: Deserializer<'de>,
 {
-    u64::deserialize(deserializer).map(Duration::from_secs)
+    from_str_or_value::<u64, D>(deserializer).map(Duration::from_secs)
 }
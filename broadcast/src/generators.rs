@@ -1,8 +1,11 @@
-use std::{collections::BTreeMap, convert::Infallible, num::NonZeroU64};
+use std::{
+    collections::BTreeMap, convert::Infallible, future::Future,
+    num::NonZeroU64, pin::Pin,
+};
 
 use tokio::{
     sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
-    task::JoinSet,
+    task::{AbortHandle, JoinError, JoinSet},
     time::Instant,
 };
 
@@ -31,14 +34,48 @@ pub struct CommitError {
 
 pub type CommitResult = Result<TxHash, CommitError>;
 
-pub type CommitResultSender = UnboundedSender<CommitResult>;
+/// Result of committing a request, along with the identifier which the
+/// request was sent with.
+pub struct CommitOutcome {
+    pub request_id: u64,
+    pub result: CommitResult,
+}
+
+pub type CommitResultSender = UnboundedSender<CommitOutcome>;
+
+pub type CommitResultReceiver = UnboundedReceiver<CommitOutcome>;
 
-pub type CommitResultReceiver = UnboundedReceiver<CommitResult>;
+pub type TxGenerator = Pin<Box<dyn Future<Output = Infallible> + Send>>;
+
+/// Change to the set of generators, applied while broadcasting.
+pub enum GeneratorsUpdate {
+    /// Spawns a generator, which sends its requests with the given sender ID.
+    Spawn {
+        sender_id: usize,
+        tx_result_sender: CommitResultSender,
+        generator: TxGenerator,
+    },
+    /// Stops the generator with the given sender ID, dropping its pending
+    /// request, if any.
+    Stop { sender_id: usize },
+}
+
+pub type GeneratorsUpdateSender = UnboundedSender<GeneratorsUpdate>;
+
+pub type GeneratorsUpdateReceiver = UnboundedReceiver<GeneratorsUpdate>;
+
+#[must_use]
+#[inline]
+pub fn new_updates_channel(
+) -> (GeneratorsUpdateSender, GeneratorsUpdateReceiver) {
+    unbounded_channel()
+}
 
 #[must_use]
 pub struct SpawnResult {
     pub(crate) tx_generators_set: JoinSet<Infallible>,
     pub(crate) tx_result_senders: BTreeMap<usize, CommitResultSender>,
+    pub(crate) updates: Option<GeneratorsUpdates>,
 }
 
 impl SpawnResult {
@@ -49,10 +86,43 @@ impl SpawnResult {
         Self {
             tx_generators_set,
             tx_result_senders,
+            updates: None,
+        }
+    }
+
+    /// Waits for the next generator to stop, returning the reason it stopped
+    /// for, or `None` when none are left running.
+    pub async fn join_next_generator(&mut self) -> Option<JoinError> {
+        self.tx_generators_set
+            .join_next()
+            .await
+            .and_then(Result::err)
+    }
+
+    /// Allows generators to be spawned and stopped while broadcasting.
+    ///
+    /// The abort handles of the already spawned generators are keyed by their
+    /// sender IDs, so that they can be stopped as well.
+    pub fn with_updates(
+        self,
+        abort_handles: BTreeMap<usize, AbortHandle>,
+        updates_receiver: GeneratorsUpdateReceiver,
+    ) -> Self {
+        Self {
+            updates: Some(GeneratorsUpdates {
+                abort_handles,
+                receiver: updates_receiver,
+            }),
+            ..self
         }
     }
 }
 
+pub(crate) struct GeneratorsUpdates {
+    pub(crate) abort_handles: BTreeMap<usize, AbortHandle>,
+    pub(crate) receiver: GeneratorsUpdateReceiver,
+}
+
 #[must_use]
 pub struct TxRequest<Impl: mode::Impl> {
     pub(crate) sender_id: usize,
@@ -60,6 +130,26 @@ pub struct TxRequest<Impl: mode::Impl> {
     pub(crate) fallback_gas_limit: NonZeroU64,
     pub(crate) hard_gas_limit: NonZeroU64,
     pub(crate) expiration: Impl::Expiration,
+    pub(crate) request_id: u64,
+}
+
+impl<Impl: mode::Impl> TxRequest<Impl> {
+    /// Sets the identifier which the outcome of committing the request is
+    /// sent back with, allowing it to be told apart from the outcomes of
+    /// other requests of the same sender.
+    pub fn with_request_id(self, request_id: u64) -> Self {
+        Self { request_id, ..self }
+    }
+
+    #[must_use]
+    pub fn messages(&self) -> &[ProtobufAny] {
+        &self.messages
+    }
+
+    #[must_use]
+    pub const fn hard_gas_limit(&self) -> NonZeroU64 {
+        self.hard_gas_limit
+    }
 }
 
 impl TxRequest<Blocking> {
@@ -75,6 +165,7 @@ impl TxRequest<Blocking> {
             fallback_gas_limit,
             hard_gas_limit,
             expiration: (),
+            request_id: 0,
         }
     }
 }
@@ -93,8 +184,66 @@ impl TxRequest<NonBlocking> {
             fallback_gas_limit,
             hard_gas_limit,
             expiration,
+            request_id: 0,
         }
     }
 }
 
 pub type TxRequestSender<Impl> = UnboundedSender<TxRequest<Impl>>;
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_with_updates() {
+    let mut tx_generators_set: JoinSet<Infallible> = JoinSet::new();
+
+    let abort_handles: BTreeMap<usize, AbortHandle> =
+        BTreeMap::from([(3, tx_generators_set.spawn(std::future::pending()))]);
+
+    let spawn_result: SpawnResult = SpawnResult::new(
+        tx_generators_set,
+        BTreeMap::from([(3, new_results_channel().0)]),
+    );
+
+    assert!(spawn_result.updates.is_none());
+
+    let (updates_sender, updates_receiver): (
+        GeneratorsUpdateSender,
+        GeneratorsUpdateReceiver,
+    ) = new_updates_channel();
+
+    let SpawnResult {
+        tx_generators_set,
+        tx_result_senders,
+        updates,
+    }: SpawnResult = spawn_result.with_updates(abort_handles, updates_receiver);
+
+    assert_eq!(tx_generators_set.len(), 1);
+
+    assert!(tx_result_senders.contains_key(&3));
+
+    let mut updates: GeneratorsUpdates = updates.unwrap();
+
+    assert!(updates.abort_handles.contains_key(&3));
+
+    _ = updates_sender.send(GeneratorsUpdate::Stop { sender_id: 3 });
+
+    assert!(matches!(
+        updates.receiver.try_recv(),
+        Ok(GeneratorsUpdate::Stop { sender_id: 3 })
+    ));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_join_next_generator() {
+    let mut tx_generators_set: JoinSet<Infallible> = JoinSet::new();
+
+    tx_generators_set.spawn(async { panic!("Generator failure!") });
+
+    let mut spawn_result: SpawnResult =
+        SpawnResult::new(tx_generators_set, BTreeMap::new());
+
+    assert!(spawn_result.join_next_generator().await.unwrap().is_panic());
+
+    assert!(spawn_result.join_next_generator().await.is_none());
+}
@@ -8,7 +8,7 @@
 use std::{
     collections::btree_map::BTreeMap,
     convert::Infallible,
-    future::{poll_fn, Future},
+    future::{pending, poll_fn, Future},
     pin::pin,
     task::Poll,
     time::Duration,
@@ -17,7 +17,7 @@ use std::{
 use tokio::{
     select,
     sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
-    task::JoinSet,
+    task::{AbortHandle, JoinSet},
     time::{sleep, timeout, Instant},
 };
 use tracing::{error, info};
@@ -43,7 +43,10 @@ use self::{
         ProcessingOutput as BroadcastProcessingOutput,
     },
     config::Config,
-    generators::{CommitResultSender, SpawnResult, TxRequest, TxRequestSender},
+    generators::{
+        CommitResultSender, GeneratorsUpdate, GeneratorsUpdates, SpawnResult,
+        TxRequest, TxRequestSender,
+    },
     mode::FilterResult,
 };
 
@@ -73,10 +76,7 @@ where
         UnboundedReceiver<TxRequest<Impl>>,
     ) = unbounded_channel();
 
-    let SpawnResult {
-        mut tx_generators_set,
-        tx_result_senders,
-    }: SpawnResult = spawn_generators(tx_sender)?;
+    let mut generators: SpawnResult = spawn_generators(tx_sender)?;
 
     let mut signal = pin!(tokio::signal::ctrl_c());
 
@@ -114,12 +114,11 @@ where
             node_client,
             node_config,
             tx_receiver,
-            &mut tx_generators_set,
-            tx_result_senders,
+            &mut generators,
         ) => {}
     }
 
-    tx_generators_set.shutdown().await;
+    generators.tx_generators_set.shutdown().await;
 
     Ok(())
 }
@@ -171,8 +170,7 @@ async fn processing_loop<Impl>(
     node_client: NodeClient,
     node_config: NodeConfig,
     mut tx_receiver: UnboundedReceiver<TxRequest<Impl>>,
-    tx_generators_set: &mut JoinSet<Infallible>,
-    mut tx_result_senders: BTreeMap<usize, CommitResultSender>,
+    generators: &mut SpawnResult,
 ) where
     Impl: mode::Impl,
 {
@@ -195,11 +193,15 @@ async fn processing_loop<Impl>(
     let mut sequence_mismatch_streak_first_timestamp = None;
 
     loop {
-        try_join_generator_task(tx_generators_set).await;
+        try_join_generator_task(&mut generators.tx_generators_set).await;
 
         if matches!(
-            cache::purge_and_update(&mut tx_receiver, &mut requests_cache)
-                .await,
+            update_generators_and_cache(
+                &mut tx_receiver,
+                &mut requests_cache,
+                generators
+            )
+            .await,
             Err(cache::ChannelClosed {})
         ) {
             info!("All generator threads stopped. Exiting.");
@@ -241,7 +243,7 @@ async fn processing_loop<Impl>(
                 &mut api_and_configuration,
                 config.between_tx_margin_time,
                 tx_request,
-                &tx_result_senders,
+                &generators.tx_result_senders,
                 last_signing_timestamp,
             )
             .await;
@@ -268,7 +270,7 @@ async fn processing_loop<Impl>(
                     }
 
                     if let Some(ref sender_id) = channel_closed {
-                        _ = tx_result_senders.remove(sender_id);
+                        _ = generators.tx_result_senders.remove(sender_id);
 
                         _ = requests_cache.remove(sender_id);
                     }
@@ -283,6 +285,96 @@ async fn processing_loop<Impl>(
     }
 }
 
+/// Applies the updates of the generators as they are received, until the
+/// cache is updated with the received requests.
+async fn update_generators_and_cache<Impl>(
+    tx_receiver: &mut UnboundedReceiver<TxRequest<Impl>>,
+    requests_cache: &mut cache::TxRequests<Impl>,
+    SpawnResult {
+        tx_generators_set,
+        tx_result_senders,
+        updates,
+    }: &mut SpawnResult,
+) -> Result<(), cache::ChannelClosed>
+where
+    Impl: mode::Impl,
+{
+    loop {
+        select! {
+            biased;
+            update = next_generators_update(updates) => {
+                if let Some(GeneratorsUpdates { abort_handles, .. }) = updates {
+                    update_generators(
+                        update,
+                        tx_generators_set,
+                        abort_handles,
+                        tx_result_senders,
+                        requests_cache,
+                    );
+                }
+            },
+            result = cache::purge_and_update(tx_receiver, requests_cache) => {
+                break result;
+            },
+        }
+    }
+}
+
+/// Waits for the next update of the generators. Never resolves when updates
+/// aren't allowed or when their channel is closed.
+async fn next_generators_update(
+    updates: &mut Option<GeneratorsUpdates>,
+) -> GeneratorsUpdate {
+    if let Some(GeneratorsUpdates { receiver, .. }) = updates {
+        if let Some(update) = receiver.recv().await {
+            return update;
+        }
+
+        *updates = None;
+    }
+
+    pending().await
+}
+
+fn update_generators<Impl>(
+    update: GeneratorsUpdate,
+    tx_generators_set: &mut JoinSet<Infallible>,
+    abort_handles: &mut BTreeMap<usize, AbortHandle>,
+    tx_result_senders: &mut BTreeMap<usize, CommitResultSender>,
+    requests_cache: &mut cache::TxRequests<Impl>,
+) where
+    Impl: mode::Impl,
+{
+    match update {
+        GeneratorsUpdate::Spawn {
+            sender_id,
+            tx_result_sender,
+            generator,
+        } => {
+            if let Some(abort_handle) = abort_handles
+                .insert(sender_id, tx_generators_set.spawn(generator))
+            {
+                abort_handle.abort();
+            }
+
+            _ = tx_result_senders.insert(sender_id, tx_result_sender);
+
+            info!(sender_id, "Generator task spawned.");
+        },
+        GeneratorsUpdate::Stop { sender_id } => {
+            if let Some(abort_handle) = abort_handles.remove(&sender_id) {
+                abort_handle.abort();
+            }
+
+            _ = tx_result_senders.remove(&sender_id);
+
+            _ = requests_cache.remove(&sender_id);
+
+            info!(sender_id, "Generator task stopped.");
+        },
+    }
+}
+
 async fn handle_mempool_error(
     api_and_configuration: &mut ApiAndConfiguration,
     sequence_mismatch_streak_first_timestamp: &mut Option<Instant>,
@@ -333,15 +425,118 @@ async fn try_join_generator_task(tx_generators_set: &mut JoinSet<Infallible>) {
         })
         .await
     {
-        error!(
-            "Generator task {}!",
-            if error.is_panic() {
-                "panicked"
-            } else if error.is_cancelled() {
-                "was cancelled"
-            } else {
-                unreachable!()
-            }
-        );
+        if error.is_panic() {
+            error!("Generator task panicked!");
+        } else if error.is_cancelled() {
+            // Generators are cancelled only when stopped through an update.
+            info!("Generator task was cancelled.");
+        } else {
+            unreachable!()
+        }
     }
 }
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_update_generators() {
+    fn spawn(sender_id: usize) -> GeneratorsUpdate {
+        GeneratorsUpdate::Spawn {
+            sender_id,
+            tx_result_sender: generators::new_results_channel().0,
+            generator: Box::pin(pending()),
+        }
+    }
+
+    let mut tx_generators_set: JoinSet<Infallible> = JoinSet::new();
+
+    let mut abort_handles: BTreeMap<usize, AbortHandle> = BTreeMap::new();
+
+    let mut tx_result_senders: BTreeMap<usize, CommitResultSender> =
+        BTreeMap::new();
+
+    let mut requests_cache: cache::TxRequests<mode::Blocking> = BTreeMap::new();
+
+    let mut update =
+        |update: GeneratorsUpdate,
+         requests_cache: &mut cache::TxRequests<mode::Blocking>| {
+            update_generators(
+                update,
+                &mut tx_generators_set,
+                &mut abort_handles,
+                &mut tx_result_senders,
+                requests_cache,
+            );
+        };
+
+    update(spawn(0), &mut requests_cache);
+
+    update(spawn(1), &mut requests_cache);
+
+    requests_cache.insert(
+        1,
+        std::cell::Cell::new(Some(cache::TxRequest {
+            messages: Vec::new(),
+            fallback_gas_limit: std::num::NonZeroU64::MIN,
+            hard_gas_limit: std::num::NonZeroU64::MIN,
+            expiration: (),
+            request_id: 0,
+        })),
+    );
+
+    // Spawning with the ID of a running generator replaces it.
+    update(spawn(0), &mut requests_cache);
+
+    update(GeneratorsUpdate::Stop { sender_id: 1 }, &mut requests_cache);
+
+    // Stopping a generator which isn't running has no effect.
+    update(GeneratorsUpdate::Stop { sender_id: 2 }, &mut requests_cache);
+
+    assert!(abort_handles.keys().eq([&0]));
+
+    assert!(tx_result_senders.keys().eq([&0]));
+
+    assert!(requests_cache.is_empty());
+
+    for _ in 0..2 {
+        assert!(tx_generators_set
+            .join_next()
+            .await
+            .unwrap()
+            .unwrap_err()
+            .is_cancelled());
+    }
+
+    assert_eq!(tx_generators_set.len(), 1);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_next_generators_update() {
+    let (updates_sender, receiver): (
+        generators::GeneratorsUpdateSender,
+        generators::GeneratorsUpdateReceiver,
+    ) = generators::new_updates_channel();
+
+    let mut updates: Option<GeneratorsUpdates> = Some(GeneratorsUpdates {
+        abort_handles: BTreeMap::new(),
+        receiver,
+    });
+
+    _ = updates_sender.send(GeneratorsUpdate::Stop { sender_id: 0 });
+
+    drop(updates_sender);
+
+    assert!(matches!(
+        next_generators_update(&mut updates).await,
+        GeneratorsUpdate::Stop { sender_id: 0 }
+    ));
+
+    // Once the channel is closed, updates are no longer allowed.
+    assert!(
+        timeout(Duration::ZERO, next_generators_update(&mut updates))
+            .await
+            .is_err()
+    );
+
+    assert!(updates.is_none());
+}
@@ -34,6 +34,7 @@ where
                     hard_gas_limit,
                     fallback_gas_limit,
                     expiration,
+                    request_id,
                 },
             ..
         }: cache::GetNextResult<Impl> =
@@ -56,6 +57,7 @@ where
             sender_id,
             signed_tx_bytes,
             expiration,
+            request_id,
         });
     }
 }
@@ -64,6 +66,7 @@ pub(crate) struct TxRequest<Impl: mode::Impl> {
     pub(crate) sender_id: usize,
     pub(crate) signed_tx_bytes: Vec<u8>,
     pub(crate) expiration: Impl::Expiration,
+    pub(crate) request_id: u64,
 }
 
 #[inline]
@@ -0,0 +1,116 @@
+use std::{path::PathBuf, process::ExitCode};
+
+/// Exit statuses shared by all services, so that scripts can tell apart
+/// mistakes in their invocation from failures of the service itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    Success = 0,
+    /// The service or command failed while running.
+    Failure = 1,
+    /// The command-line arguments are invalid.
+    Usage = 2,
+    /// The configuration couldn't be read or is invalid.
+    Configuration = 3,
+}
+
+impl From<Exit> for ExitCode {
+    fn from(exit: Exit) -> Self {
+        Self::from(exit as u8)
+    }
+}
+
+/// Options common to all services, along with the selected subcommand and
+/// the arguments following it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct Args {
+    pub config_path: PathBuf,
+    /// Subcommand, or `None` when only options are given. `-h`/`--help` and
+    /// `-V`/`--version` select `help` and `version` respectively.
+    pub command: Option<String>,
+    pub rest: Vec<String>,
+}
+
+/// Parses the options preceding the subcommand.
+///
+/// The configuration file is selected with `--config <PATH>` or
+/// `--config=<PATH>`, defaulting to the given path.
+pub fn parse<I>(args: I, default_config_path: &str) -> Result<Args, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+
+    let mut config_path: Option<PathBuf> = None;
+
+    let command: Option<String> = loop {
+        let Some(arg) = args.next() else {
+            break None;
+        };
+
+        let value: String = match arg.as_str() {
+            "-h" | "--help" => break Some(String::from("help")),
+            "-V" | "--version" => break Some(String::from("version")),
+            "--config" => args.next().ok_or_else(|| {
+                String::from("Option \"--config\" requires a value!")
+            })?,
+            _ => {
+                if let Some(value) = arg.strip_prefix("--config=") {
+                    value.to_string()
+                } else if arg.starts_with('-') {
+                    return Err(format!("Unknown option \"{arg}\"!"));
+                } else {
+                    break Some(arg);
+                }
+            },
+        };
+
+        if config_path.replace(value.into()).is_some() {
+            return Err(String::from(
+                "Option \"--config\" can be given only once!",
+            ));
+        }
+    };
+
+    Ok(Args {
+        config_path: config_path
+            .unwrap_or_else(|| PathBuf::from(default_config_path)),
+        command,
+        rest: args.collect(),
+    })
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse() {
+    let parse = |args: &[&str]| {
+        parse(args.iter().copied().map(String::from), "service.toml")
+    };
+
+    assert_eq!(
+        parse(&["--config", "other.toml", "prices", "--provider", "osmosis"]),
+        Ok(Args {
+            config_path: PathBuf::from("other.toml"),
+            command: Some(String::from("prices")),
+            rest: vec![String::from("--provider"), String::from("osmosis")],
+        })
+    );
+
+    assert_eq!(
+        parse(&["--config=other.toml"]),
+        Ok(Args {
+            config_path: PathBuf::from("other.toml"),
+            command: None,
+            rest: Vec::new(),
+        })
+    );
+
+    assert_eq!(
+        parse(&["--version"]).map(|args: Args| args.command),
+        Ok(Some(String::from("version")))
+    );
+
+    assert!(parse(&["--config"]).is_err());
+    assert!(parse(&["--config", "a.toml", "--config", "b.toml"]).is_err());
+    assert!(parse(&["--verbose", "run"]).is_err());
+}
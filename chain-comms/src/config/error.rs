@@ -1,11 +1,44 @@
+use std::path::PathBuf;
+
 use thiserror::Error as ThisError;
 
 #[derive(Debug, ThisError)]
 pub enum Error {
     #[error("Failed to read contents of configuration file! Cause: {0}")]
     FileRead(#[from] std::io::Error),
-    #[error("Failed to parse configuration! Cause: {0}")]
+    #[error("Failed to parse configuration file as TOML! Cause: {0}")]
+    Syntax(#[from] toml_edit::TomlError),
+    #[error("Failed to interpolate value of \"{key}\"! Cause: {error}")]
+    Interpolate {
+        key: Box<str>,
+        #[source]
+        error: Interpolation,
+    },
+    #[error("Configuration is invalid! Cause: {0}")]
     Parse(#[from] toml::de::Error),
 }
 
+#[derive(Debug, ThisError)]
+pub enum Interpolation {
+    #[error("Placeholder \"${{{0}}}\" is invalid! Expected either \"${{VAR}}\" or \"${{VAR:-default}}\".")]
+    InvalidPlaceholder(Box<str>),
+    #[error("Placeholder starting with \"${{{0}\" is not terminated!")]
+    UnterminatedPlaceholder(Box<str>),
+    #[error("Neither environment variable \"{0}\", nor \"{0}_FILE\", is set!")]
+    NotSet(Box<str>),
+    #[error("Both environment variables \"{0}\" and \"{0}_FILE\" are set!")]
+    BothSet(Box<str>),
+    #[error(
+        "Value for environment variable \"{0}\" contains invalid unicode data."
+    )]
+    NotUnicode(Box<str>),
+    #[error("Failed to read file \"{path}\", set through environment variable \"{variable}\"! Cause: {error}")]
+    ReadFile {
+        variable: Box<str>,
+        path: PathBuf,
+        #[source]
+        error: std::io::Error,
+    },
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
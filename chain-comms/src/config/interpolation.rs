@@ -0,0 +1,285 @@
+use std::{
+    env::{var, VarError},
+    fs::read_to_string,
+};
+
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+use super::error::{Error, Interpolation};
+
+/// Replaces the `${VAR}` and `${VAR:-default}` placeholders in the string
+/// values of the document with the values of the environment variables.
+///
+/// A variable which isn't set is read out of the file at the path set in
+/// `VAR_FILE`, when it is. The default is used when neither is set, or when
+/// the value is empty. `$$` stands for a literal `$`.
+///
+/// Interpolated values are left as strings. Numeric and boolean fields parse
+/// them when deserialized, through [`super::from_str_or_value`].
+pub(super) fn document(document: &mut DocumentMut) -> Result<(), Error> {
+    table(document.as_table_mut(), "", &env_var)
+}
+
+fn table<F>(table: &mut Table, path: &str, lookup: &F) -> Result<(), Error>
+where
+    F: Fn(&str) -> Result<Option<String>, Interpolation>,
+{
+    table
+        .iter_mut()
+        .try_for_each(|(key, node)| item(node, &join_key(path, &key), lookup))
+}
+
+fn item<F>(node: &mut Item, path: &str, lookup: &F) -> Result<(), Error>
+where
+    F: Fn(&str) -> Result<Option<String>, Interpolation>,
+{
+    match node {
+        Item::None => Ok(()),
+        Item::Value(node) => value(node, path, lookup),
+        Item::Table(node) => table(node, path, lookup),
+        Item::ArrayOfTables(node) => {
+            node.iter_mut().enumerate().try_for_each(|(index, node)| {
+                table(node, &format!("{path}[{index}]"), lookup)
+            })
+        },
+    }
+}
+
+fn value<F>(node: &mut Value, path: &str, lookup: &F) -> Result<(), Error>
+where
+    F: Fn(&str) -> Result<Option<String>, Interpolation>,
+{
+    match node {
+        Value::String(formatted) => {
+            let text: &str = formatted.value();
+
+            let interpolated: String =
+                string(text, lookup).map_err(|error: Interpolation| {
+                    Error::Interpolate {
+                        key: path.into(),
+                        error,
+                    }
+                })?;
+
+            if interpolated != text {
+                let mut interpolated: Value = Value::from(interpolated);
+
+                *interpolated.decor_mut() = node.decor().clone();
+
+                *node = interpolated;
+            }
+
+            Ok(())
+        },
+        Value::Array(node) => {
+            node.iter_mut().enumerate().try_for_each(|(index, node)| {
+                value(node, &format!("{path}[{index}]"), lookup)
+            })
+        },
+        Value::InlineTable(node) => {
+            node.iter_mut().try_for_each(|(key, node)| {
+                value(node, &join_key(path, &key), lookup)
+            })
+        },
+        Value::Integer(_)
+        | Value::Float(_)
+        | Value::Boolean(_)
+        | Value::Datetime(_) => Ok(()),
+    }
+}
+
+fn join_key(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.into()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn string<F>(text: &str, lookup: &F) -> Result<String, Interpolation>
+where
+    F: Fn(&str) -> Result<Option<String>, Interpolation>,
+{
+    let mut interpolated: String = String::with_capacity(text.len());
+
+    let mut rest: &str = text;
+
+    while let Some(index) = rest.find('$') {
+        interpolated.push_str(&rest[..index]);
+
+        rest = &rest[index + 1..];
+
+        if let Some(after) = rest.strip_prefix('$') {
+            interpolated.push('$');
+
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('{') {
+            let end: usize = after.find('}').ok_or_else(|| {
+                Interpolation::UnterminatedPlaceholder(after.into())
+            })?;
+
+            interpolated.push_str(&placeholder(&after[..end], lookup)?);
+
+            rest = &after[end + 1..];
+        } else {
+            interpolated.push('$');
+        }
+    }
+
+    interpolated.push_str(rest);
+
+    Ok(interpolated)
+}
+
+fn placeholder<F>(
+    placeholder: &str,
+    lookup: &F,
+) -> Result<String, Interpolation>
+where
+    F: Fn(&str) -> Result<Option<String>, Interpolation>,
+{
+    let (name, default): (&str, Option<&str>) = placeholder
+        .split_once(":-")
+        .map_or((placeholder, None), |(name, default): (&str, &str)| {
+            (name, Some(default))
+        });
+
+    if !is_valid_name(name) {
+        return Err(Interpolation::InvalidPlaceholder(placeholder.into()));
+    }
+
+    match (lookup(name)?, default) {
+        (Some(value), None) => Ok(value),
+        (Some(value), Some(_)) if !value.is_empty() => Ok(value),
+        (_, Some(default)) => Ok(default.into()),
+        (None, None) => Err(Interpolation::NotSet(name.into())),
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    name.starts_with(|character: char| {
+        character.is_ascii_alphabetic() || character == '_'
+    }) && name.chars().all(|character: char| {
+        character.is_ascii_alphanumeric() || character == '_'
+    })
+}
+
+/// Reads the environment variable or, when it isn't set, the file at the path
+/// set in the one suffixed with `_FILE`, without its trailing line break.
+///
+/// Used for the placeholders, as well as for the settings which are read
+/// directly from the environment.
+pub fn env_var(name: &str) -> Result<Option<String>, Interpolation> {
+    let file_variable: String = format!("{name}_FILE");
+
+    match (read_var(name)?, read_var(&file_variable)?) {
+        (Some(_), Some(_)) => Err(Interpolation::BothSet(name.into())),
+        (Some(value), None) => Ok(Some(value)),
+        (None, Some(path)) => read_to_string(&path)
+            .map(|contents: String| {
+                Some(contents.trim_end_matches(['\n', '\r']).into())
+            })
+            .map_err(|error: std::io::Error| Interpolation::ReadFile {
+                variable: file_variable.into(),
+                path: path.into(),
+                error,
+            }),
+        (None, None) => Ok(None),
+    }
+}
+
+fn read_var(name: &str) -> Result<Option<String>, Interpolation> {
+    match var(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(VarError::NotPresent) => Ok(None),
+        Err(VarError::NotUnicode(_)) => {
+            Err(Interpolation::NotUnicode(name.into()))
+        },
+    }
+}
+
+#[cfg(test)]
+fn test_lookup(name: &str) -> Result<Option<String>, Interpolation> {
+    match name {
+        "GRPC_URI" => Ok(Some("https://grpc.nolus.network:443".into())),
+        "TICK_TIME" => Ok(Some("60".into())),
+        "EMPTY" => Ok(Some(String::new())),
+        "NOT_UNICODE" => Err(Interpolation::NotUnicode(name.into())),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn test_string() {
+    assert_eq!(
+        string("${GRPC_URI}/path", &test_lookup).unwrap(),
+        "https://grpc.nolus.network:443/path"
+    );
+
+    assert_eq!(
+        string("${MISSING:-default} ${EMPTY:-fallback}", &test_lookup).unwrap(),
+        "default fallback"
+    );
+
+    assert_eq!(string("$$5 and $6", &test_lookup).unwrap(), "$5 and $6");
+
+    assert!(matches!(
+        string("${MISSING}", &test_lookup),
+        Err(Interpolation::NotSet(name)) if &*name == "MISSING"
+    ));
+
+    assert!(matches!(
+        string("${NOT_UNICODE:-default}", &test_lookup),
+        Err(Interpolation::NotUnicode(_))
+    ));
+
+    assert!(matches!(
+        string("${1NVALID}", &test_lookup),
+        Err(Interpolation::InvalidPlaceholder(_))
+    ));
+
+    assert!(matches!(
+        string("${GRPC_URI", &test_lookup),
+        Err(Interpolation::UnterminatedPlaceholder(_))
+    ));
+}
+
+#[cfg(test)]
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn test_document() {
+    let mut document: DocumentMut = r#"
+tick_time = "${TICK_TIME}"
+label = "every ${TICK_TIME} seconds"
+
+[node]
+grpc_uri = "${GRPC_URI}" # Comment is kept.
+hosts = ["${GRPC_URI}", { port = "${PORT:-443}" }]
+"#
+    .parse()
+    .unwrap();
+
+    table(document.as_table_mut(), "", &test_lookup).unwrap();
+
+    assert_eq!(
+        document.to_string(),
+        r#"
+tick_time = "60"
+label = "every 60 seconds"
+
+[node]
+grpc_uri = "https://grpc.nolus.network:443" # Comment is kept.
+hosts = ["https://grpc.nolus.network:443", { port = "443" }]
+"#
+    );
+
+    let mut document: DocumentMut =
+        "[node]\nfee_denom = \"${FEE_DENOM}\"".parse().unwrap();
+
+    assert!(matches!(
+        table(document.as_table_mut(), "", &test_lookup),
+        Err(Error::Interpolate { key, .. }) if &*key == "node.fee_denom"
+    ));
+}
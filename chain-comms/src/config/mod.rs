@@ -1,6 +1,6 @@
 use std::{
-    env::{var, VarError},
     error::Error as StdError,
+    fmt::Display,
     num::{NonZeroU64, NonZeroUsize},
     path::Path,
     str::FromStr,
@@ -12,10 +12,14 @@ use serde::{
     Deserialize, Deserializer, Serialize,
 };
 use tokio::fs::read_to_string;
+use toml_edit::DocumentMut;
 
-use self::error::Result as ModuleResult;
+use self::error::{Interpolation, Result as ModuleResult};
+
+pub use self::interpolation::env_var;
 
 pub mod error;
+mod interpolation;
 
 #[derive(Debug, Clone, Deserialize)]
 #[must_use]
@@ -104,36 +108,103 @@ where
     T::Err: StdError,
     D: Deserializer<'de>,
 {
-    match var(var_name) {
-        Ok(value) => T::from_str(&value).map(Some).map_err(D::Error::custom),
-        Err(VarError::NotPresent) => Ok(None),
-        Err(VarError::NotUnicode(_)) => Err(D::Error::custom(format!(
-            r#"Value for environment variable "{var_name}" contains invalid unicode data."#,
-        ))),
+    env_var(var_name)
+        .map_err(|error: Interpolation| D::Error::custom(error))?
+        .map(|value: String| T::from_str(&value).map_err(D::Error::custom))
+        .transpose()
+}
+
+/// Deserializes the value either as is or parsed out of a string, as the
+/// interpolated values, e.g. `"${TICK_TIME:-60}"`, are left as strings.
+pub fn from_str_or_value<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Deserialize<'de> + FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    match toml::Value::deserialize(deserializer)? {
+        toml::Value::String(value) => {
+            T::from_str(&value).map_err(D::Error::custom)
+        },
+        value => T::deserialize(value).map_err(D::Error::custom),
+    }
+}
+
+/// Deserializes the optional value as by [`from_str_or_value`]. Missing values
+/// have to be defaulted separately.
+pub fn some_from_str_or_value<'de, T, D>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de> + FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    from_str_or_value(deserializer).map(Some)
+}
+
+/// Value deserialized as by [`from_str_or_value`], for the places where the
+/// deserializing function can't be set, e.g. the values of maps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct StrOrValue<T>(pub T);
+
+impl<'de, T> Deserialize<'de> for StrOrValue<T>
+where
+    T: Deserialize<'de> + FromStr,
+    T::Err: Display,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        from_str_or_value(deserializer).map(Self)
     }
 }
 
+/// Reads the configuration file, replacing the `${VAR}` and `${VAR:-default}`
+/// placeholders in its string values with the values of the environment
+/// variables or of the files set in `VAR_FILE`.
 pub async fn read<C, P>(path: P) -> ModuleResult<C>
 where
     C: DeserializeOwned + AsRef<Node> + Send,
     P: AsRef<Path> + Send,
 {
-    toml::from_str(&read_to_string(path).await?).map_err(Into::into)
+    parse(&read_to_string(path).await?)
+}
+
+/// Parses the configuration, replacing the placeholders in its string values
+/// the same way as [`read`] does.
+pub fn parse<C>(contents: &str) -> ModuleResult<C>
+where
+    C: DeserializeOwned,
+{
+    let mut document: DocumentMut = contents.parse()?;
+
+    interpolation::document(&mut document)?;
+
+    toml::from_str(&document.to_string()).map_err(Into::into)
 }
 
 #[derive(Debug, Clone, Deserialize)]
 #[must_use]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 struct File {
-    #[serde(default)]
+    #[serde(default, deserialize_with = "some_from_str_or_value")]
     http2_concurrency_limit: Option<NonZeroUsize>,
     address_prefix: Box<str>,
     fee_denom: Denom,
+    #[serde(deserialize_with = "from_str_or_value")]
     gas_adjustment_numerator: NonZeroU64,
+    #[serde(deserialize_with = "from_str_or_value")]
     gas_adjustment_denominator: NonZeroU64,
+    #[serde(deserialize_with = "from_str_or_value")]
     gas_price_numerator: NonZeroU64,
+    #[serde(deserialize_with = "from_str_or_value")]
     gas_price_denominator: NonZeroU64,
+    #[serde(deserialize_with = "from_str_or_value")]
     fee_adjustment_numerator: NonZeroU64,
+    #[serde(deserialize_with = "from_str_or_value")]
     fee_adjustment_denominator: NonZeroU64,
 }
 
@@ -160,3 +231,47 @@ struct CoinDTO {
     amount: String,
     denom: String,
 }
+
+#[cfg(test)]
+#[test]
+fn test_from_str_or_value() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Test {
+        #[serde(deserialize_with = "from_str_or_value")]
+        limit: NonZeroU64,
+        #[serde(default, deserialize_with = "some_from_str_or_value")]
+        concurrency: Option<NonZeroUsize>,
+    }
+
+    assert_eq!(
+        toml::from_str::<Test>("limit = 1_000\nconcurrency = \"10\"").unwrap(),
+        Test {
+            limit: NonZeroU64::new(1_000).unwrap(),
+            concurrency: NonZeroUsize::new(10),
+        }
+    );
+
+    assert_eq!(
+        toml::from_str::<Test>("limit = \"1000\"").unwrap(),
+        Test {
+            limit: NonZeroU64::new(1_000).unwrap(),
+            concurrency: None,
+        }
+    );
+
+    assert!(toml::from_str::<Test>("limit = \"0\"").is_err());
+
+    assert!(toml::from_str::<Test>("limit = \"many\"").is_err());
+
+    assert!(toml::from_str::<Test>("limit = true").is_err());
+
+    assert_eq!(
+        toml::from_str::<std::collections::BTreeMap<String, StrOrValue<u8>>>(
+            "a = 1\nb = \"2\""
+        )
+        .unwrap()
+        .into_values()
+        .collect::<Vec<StrOrValue<u8>>>(),
+        [StrOrValue(1), StrOrValue(2)]
+    );
+}
@@ -21,6 +21,12 @@ pub enum AccountData {
     DeserializeAccountData(#[from] prost::DecodeError),
 }
 
+#[derive(Debug, ThisError)]
+pub enum Bank {
+    #[error("RPC error occurred while querying bank module! Cause: {0}")]
+    Rpc(#[from] tonic::Status),
+}
+
 #[derive(Debug, ThisError)]
 pub enum Raw {
     #[error("Connection failure occurred! Cause: {0}")]
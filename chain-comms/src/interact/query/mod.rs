@@ -5,6 +5,10 @@ use cosmrs::{
                 query_client::QueryClient as AuthQueryClient, BaseAccount,
                 QueryAccountRequest, QueryAccountResponse,
             },
+            bank::v1beta1::{
+                query_client::QueryClient as BankQueryClient, Metadata,
+                QueryDenomMetadataRequest, QuerySupplyOfRequest,
+            },
             base::tendermint::v1beta1::{
                 service_client::ServiceClient as TendermintServiceClient,
                 GetNodeInfoRequest,
@@ -23,12 +27,13 @@ use serde::de::DeserializeOwned;
 use tonic::{
     client::Grpc as GrpcClient, codec::ProstCodec,
     codegen::http::uri::PathAndQuery, transport::Channel as TonicChannel,
-    IntoRequest as _, Response as TonicResponse,
+    Code as TonicCode, IntoRequest as _, Response as TonicResponse,
 };
 use tracing::debug;
 
 use self::error::{
-    AccountData as AccountError, Raw as RawError, Wasm as WasmError,
+    AccountData as AccountError, Bank as BankError, Raw as RawError,
+    Wasm as WasmError,
 };
 
 pub mod error;
@@ -121,3 +126,37 @@ where
                 .map_err(From::from)
         })
 }
+
+/// Returns the bank module's metadata of the given denomination, or `None`
+/// when no metadata is registered for it.
+pub async fn denom_metadata(
+    query_client: &mut BankQueryClient<TonicChannel>,
+    denom: String,
+) -> Result<Option<Metadata>, BankError> {
+    match query_client
+        .denom_metadata(QueryDenomMetadataRequest { denom })
+        .await
+    {
+        Ok(response) => Ok(response.into_inner().metadata),
+        Err(status) if status.code() == TonicCode::NotFound => Ok(None),
+        Err(status) => Err(BankError::Rpc(status)),
+    }
+}
+
+/// Returns the total supply of the given denomination, which is zero for
+/// denominations that don't exist.
+pub async fn supply_of(
+    query_client: &mut BankQueryClient<TonicChannel>,
+    denom: String,
+) -> Result<String, BankError> {
+    query_client
+        .supply_of(QuerySupplyOfRequest { denom })
+        .await
+        .map(|response| {
+            response
+                .into_inner()
+                .amount
+                .map_or_else(|| String::from("0"), |coin| coin.amount)
+        })
+        .map_err(BankError::Rpc)
+}
@@ -7,6 +7,7 @@
 
 pub mod account;
 pub mod build_tx;
+pub mod cli;
 pub mod client;
 pub mod config;
 pub mod decode;
@@ -6,8 +6,8 @@ pub enum Error {
         "Couldn't read secret mnemonic from the standard input! Cause: {0}"
     )]
     ReadingMnemonic(#[from] tokio::io::Error),
-    #[error("Couldn't read secret mnemonic from environment variable because it can't be encoded as valid UTF-8!")]
-    NonUnicodeMnemonic,
+    #[error("Couldn't read secret mnemonic from the environment! Cause: {0}")]
+    EnvironmentMnemonic(#[from] crate::config::error::Interpolation),
     #[error("Invalid mnemonic passed or is not in English! Cause: {0}")]
     ParsingMnemonic(cosmrs::bip32::Error),
     #[error("Couldn't parse derivation path! Cause: {0}")]
@@ -1,11 +1,11 @@
-use std::env::{var, VarError};
-
 use cosmrs::{
     bip32::{Language, Mnemonic},
     crypto::secp256k1::SigningKey,
 };
 use tokio::io::{AsyncBufReadExt, BufReader};
 
+use crate::config::env_var;
+
 use self::error::{Error, Result};
 
 pub mod error;
@@ -16,21 +16,20 @@ pub async fn signing_key(
     derivation_path: &str,
     password: &str,
 ) -> Result<SigningKey> {
-    let secret: String = match var("SIGNING_KEY_MNEMONIC") {
-        Ok(secret) => secret,
-        Err(VarError::NotPresent) => {
-            println!("Enter dispatcher's account secret: ");
+    let secret: String = if let Some(secret) = env_var("SIGNING_KEY_MNEMONIC")?
+    {
+        secret
+    } else {
+        println!("Enter dispatcher's account secret: ");
 
-            let mut secret = String::new();
+        let mut secret = String::new();
 
-            // Returns number of read bytes, which is meaningless for current case.
-            let _ = BufReader::new(tokio::io::stdin())
-                .read_line(&mut secret)
-                .await?;
+        // Returns number of read bytes, which is meaningless for current case.
+        let _ = BufReader::new(tokio::io::stdin())
+            .read_line(&mut secret)
+            .await?;
 
-            secret
-        },
-        Err(VarError::NotUnicode(_)) => return Err(Error::NonUnicodeMnemonic),
+        secret
     };
 
     SigningKey::derive_from_path(
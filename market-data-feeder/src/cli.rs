@@ -0,0 +1,197 @@
+use std::path::PathBuf;
+
+use chain_comms::cli::{self, Args};
+
+use crate::{
+    dry_run,
+    error::Application,
+    history::query::{self as history_query, Filter, Format},
+};
+
+pub(crate) const DEFAULT_CONFIG_PATH: &str = "market-data-feeder.toml";
+
+/// Usage of the service.
+pub(crate) const USAGE: &str = "\
+Usage: market-data-feeder [--config <FILE>] [COMMAND]
+
+Options:
+    --config <FILE>        Configuration file to use. Defaults to
+                           \"market-data-feeder.toml\".
+    -h, --help             Print this message.
+    -V, --version          Print the version.
+
+Commands:
+    run                    Feed prices, as configured. Default command.
+    dry-run                Fetch and check prices without broadcasting the
+                           feeding messages. See \"dry-run --help\".
+    check-config           Validate the configuration file and exit.
+    prices --provider <ID> Fetch and print the prices of the given provider
+                           or aggregate once.
+    history                Query the history file. See \"history --help\".
+    version                Print the version.
+    help                   Print this message.
+
+Exit codes:
+    0                      Success.
+    1                      Failure while running.
+    2                      Invalid command-line arguments.
+    3                      Invalid or unreadable configuration.";
+
+#[derive(Debug, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct Cli {
+    pub config_path: PathBuf,
+    pub command: Command,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Command {
+    Run,
+    DryRun(dry_run::Options),
+    CheckConfig,
+    Prices {
+        provider_id: Box<str>,
+    },
+    History(PathBuf, Filter, Format),
+    Version,
+    /// Prints the given usage.
+    Help(&'static str),
+}
+
+pub(crate) fn parse<I>(args: I) -> Result<Cli, Application>
+where
+    I: IntoIterator<Item = String>,
+{
+    let Args {
+        config_path,
+        command,
+        rest,
+    }: Args = cli::parse(args, DEFAULT_CONFIG_PATH)
+        .map_err(|error: String| invalid_arguments(&error))?;
+
+    let asks_for_help: bool = rest
+        .iter()
+        .any(|arg: &String| matches!(arg.as_str(), "-h" | "--help"));
+
+    let command: Command = match command.as_deref().unwrap_or("run") {
+        "run" => no_arguments(Command::Run, &rest)?,
+        "dry-run" if asks_for_help => Command::Help(dry_run::USAGE),
+        "dry-run" => Command::DryRun(dry_run::parse_args(rest)?),
+        "check-config" => no_arguments(Command::CheckConfig, &rest)?,
+        "prices" => parse_prices(rest)?,
+        "history" if asks_for_help => Command::Help(history_query::USAGE),
+        "history" => {
+            let (path, filter, format): (PathBuf, Filter, Format) =
+                history_query::parse_args(rest)?;
+
+            Command::History(path, filter, format)
+        },
+        "version" => no_arguments(Command::Version, &rest)?,
+        "help" => Command::Help(USAGE),
+        command => {
+            return Err(invalid_arguments(&format!(
+                "Unknown command \"{command}\"!"
+            )));
+        },
+    };
+
+    Ok(Cli {
+        config_path,
+        command,
+    })
+}
+
+fn parse_prices(rest: Vec<String>) -> Result<Command, Application> {
+    match <[String; 2]>::try_from(rest) {
+        Ok([option, provider_id]) if option == "--provider" => {
+            Ok(Command::Prices {
+                provider_id: provider_id.into(),
+            })
+        },
+        _ => Err(invalid_arguments(
+            "Command \"prices\" expects exactly one \"--provider <ID>\" option!",
+        )),
+    }
+}
+
+fn no_arguments(
+    command: Command,
+    rest: &[String],
+) -> Result<Command, Application> {
+    rest.first().map_or(Ok(command), |arg: &String| {
+        Err(invalid_arguments(&format!(
+            "Unexpected argument \"{arg}\"!"
+        )))
+    })
+}
+
+fn invalid_arguments(error: &str) -> Application {
+    Application::InvalidArguments(format!("{error}\n\n{USAGE}"))
+}
+
+#[cfg(test)]
+fn test_parse(args: &[&str]) -> Result<Cli, Application> {
+    parse(args.iter().copied().map(String::from))
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_commands() {
+    assert_eq!(
+        test_parse(&[]).unwrap(),
+        Cli {
+            config_path: PathBuf::from(DEFAULT_CONFIG_PATH),
+            command: Command::Run,
+        }
+    );
+
+    assert_eq!(
+        test_parse(&[
+            "--config",
+            "instance-2.toml",
+            "prices",
+            "--provider",
+            "osmosis"
+        ])
+        .unwrap(),
+        Cli {
+            config_path: PathBuf::from("instance-2.toml"),
+            command: Command::Prices {
+                provider_id: "osmosis".into(),
+            },
+        }
+    );
+
+    assert_eq!(
+        test_parse(&["dry-run", "--simulate"]).unwrap().command,
+        Command::DryRun(dry_run::Options {
+            output: None,
+            simulate: true,
+        })
+    );
+
+    assert_eq!(
+        test_parse(&["history", "--help"]).unwrap().command,
+        Command::Help(history_query::USAGE)
+    );
+
+    assert_eq!(test_parse(&["-V"]).unwrap().command, Command::Version);
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_invalid_arguments() {
+    for args in [
+        &["feed"][..],
+        &["run", "--now"],
+        &["prices"],
+        &["prices", "--provider"],
+        &["prices", "--source", "osmosis"],
+        &["check-config", "extra"],
+    ] {
+        assert!(
+            matches!(test_parse(args), Err(Application::InvalidArguments(_))),
+            "{args:?}"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    collections::btree_map::{BTreeMap, Entry as BTreeMapEntry},
+    num::NonZeroU32,
+    sync::Arc,
+};
+
+use serde::de::{Deserializer, Error as DeserializeError};
+
+use super::{
+    get_oracle, providers::map_comparison_provider_option, raw,
+    str_pool::StrPool, Aggregate, AggregateSource, Provider,
+    ProviderWithComparison,
+};
+
+pub(super) fn reconstruct<'r, 'de, D>(
+    raw_aggregates: BTreeMap<String, raw::Aggregate>,
+    str_pool: &'r mut StrPool,
+    oracles: &'r BTreeMap<Arc<str>, Arc<str>>,
+    providers: &'r BTreeMap<Box<str>, ProviderWithComparison>,
+) -> Result<BTreeMap<Box<str>, Aggregate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut aggregates: BTreeMap<Box<str>, Aggregate> = BTreeMap::new();
+
+    for (
+        raw_id,
+        raw::Aggregate {
+            oracle_id,
+            method,
+            min_sources,
+            sources: raw_sources,
+            comparison,
+            feeding,
+            jump_guard,
+            cross_rates,
+        },
+    ) in raw_aggregates
+    {
+        let id: Box<str> = raw_id.into_boxed_str();
+
+        if providers.contains_key(&id) {
+            return Err(DeserializeError::custom(format_args!(
+                "Aggregate with ID \"{id}\" shares its ID with a provider!",
+            )));
+        }
+
+        if raw_sources.len() < min_sources.get() {
+            return Err(DeserializeError::custom(format_args!(
+                "Aggregate with ID \"{id}\" requires at least {min_sources} sources, but has only {sources_count}!",
+                sources_count = raw_sources.len(),
+            )));
+        }
+
+        let oracle_id: Arc<str> = str_pool.get_or_insert(oracle_id);
+        let oracle_address: Arc<str> = get_oracle::<D>(oracles, &oracle_id)?;
+
+        let sources: BTreeMap<Box<str>, AggregateSource> = raw_sources
+            .into_iter()
+            .map(
+                |(source_id, raw::AggregateSource { name, weight, misc }): (
+                    String,
+                    raw::AggregateSource,
+                )| {
+                    (
+                        format!("{id}_{source_id}").into_boxed_str(),
+                        AggregateSource {
+                            provider: Provider {
+                                name: str_pool.get_or_insert(name),
+                                oracle_id: oracle_id.clone(),
+                                oracle_address: oracle_address.clone(),
+                                misc,
+                            },
+                            weight: weight.unwrap_or(NonZeroU32::MIN),
+                        },
+                    )
+                },
+            )
+            .collect();
+
+        let aggregate: Aggregate = Aggregate {
+            oracle_address,
+            method,
+            min_sources,
+            sources,
+            comparison: map_comparison_provider_option::<D>(
+                comparison, &id, str_pool,
+            )?,
+            feeding: feeding.map(From::from),
+            jump_guard: jump_guard.map(From::from),
+            cross_rates: cross_rates.map(From::from),
+        };
+
+        match aggregates.entry(id) {
+            BTreeMapEntry::Vacant(entry) => entry.insert(aggregate),
+            BTreeMapEntry::Occupied(entry) => {
+                return Err(DeserializeError::custom(format_args!(
+                    "Aggregate with ID \"{id}\" already exists!",
+                    id = entry.key()
+                )));
+            },
+        };
+    }
+
+    Ok(aggregates)
+}
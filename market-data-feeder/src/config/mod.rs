@@ -1,7 +1,7 @@
 use std::{
     collections::BTreeMap,
-    env::{self, var},
-    num::NonZeroU64,
+    num::{NonZeroU32, NonZeroU64, NonZeroUsize},
+    path::PathBuf,
     sync::Arc,
     time::Duration,
 };
@@ -11,12 +11,18 @@ use serde::{
     Deserialize,
 };
 use thiserror::Error as ThisError;
+use tracing::warn;
 
 use broadcast::config::Config as BroadcastConfig;
-use chain_comms::config::{read_from_env, Node as NodeConfig};
+use chain_comms::config::{
+    env_var, error::Interpolation, read_from_env, Node as NodeConfig,
+};
+
+use crate::deviation::{BasisPoints, DeviationLimits};
 
 use self::str_pool::StrPool;
 
+mod aggregates;
 mod comparison_providers;
 mod providers;
 mod raw;
@@ -29,16 +35,23 @@ pub(crate) type SymbolUnsized = str;
 
 pub(crate) type Currencies = BTreeMap<Ticker, SymbolAndDecimalPlaces>;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AssetKind {
+    Native,
+    Cw20,
+}
+
 #[derive(Debug, Deserialize)]
 #[must_use]
-#[serde(rename_all = "snake_case", deny_unknown_fields)]
+#[serde(try_from = "raw::SymbolAndDecimalPlaces")]
 pub(crate) struct SymbolAndDecimalPlaces {
-    #[serde(deserialize_with = "deserialize_arc_str")]
     denom: Arc<SymbolUnsized>,
     decimal_places: u8,
+    kind: AssetKind,
 }
 
 impl SymbolAndDecimalPlaces {
+    /// Native denomination or, for CW20 tokens, the token contract's address.
     pub const fn denom(&self) -> &Arc<SymbolUnsized> {
         &self.denom
     }
@@ -46,6 +59,43 @@ impl SymbolAndDecimalPlaces {
     pub const fn decimal_places(&self) -> u8 {
         self.decimal_places
     }
+
+    pub const fn kind(&self) -> AssetKind {
+        self.kind
+    }
+}
+
+impl TryFrom<raw::SymbolAndDecimalPlaces> for SymbolAndDecimalPlaces {
+    type Error = &'static str;
+
+    fn try_from(
+        raw::SymbolAndDecimalPlaces {
+            denom,
+            cw20_contract,
+            decimal_places,
+        }: raw::SymbolAndDecimalPlaces,
+    ) -> Result<Self, Self::Error> {
+        let (denom, kind): (String, AssetKind) = match (denom, cw20_contract) {
+            (Some(denom), None) => (denom, AssetKind::Native),
+            (None, Some(cw20_contract)) => (cw20_contract, AssetKind::Cw20),
+            (None, None) => {
+                return Err(
+                    r#"Either "denom" or "cw20_contract" has to be set!"#,
+                );
+            },
+            (Some(_), Some(_)) => {
+                return Err(
+                    r#"Only one of "denom" and "cw20_contract" can be set!"#,
+                );
+            },
+        };
+
+        Ok(Self {
+            denom: denom.into(),
+            decimal_places,
+            kind,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -57,7 +107,20 @@ pub(crate) struct Config {
     pub node: NodeConfig,
     pub oracles: BTreeMap<Arc<str>, Arc<str>>,
     pub providers: BTreeMap<Box<str>, ProviderWithComparison>,
+    pub aggregates: BTreeMap<Box<str>, Aggregate>,
     pub comparison_providers: BTreeMap<Arc<str>, ComparisonProvider>,
+    pub history: Option<History>,
+    /// Whether to refuse starting when not all of the pairs requested by the
+    /// oracles are priced.
+    pub strict_coverage: bool,
+}
+
+/// Location of the append-only file recording what is fetched and fed.
+#[derive(Debug, Deserialize)]
+#[must_use]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) struct History {
+    pub path: PathBuf,
 }
 
 impl<'de> Deserialize<'de> for Config {
@@ -73,7 +136,10 @@ impl<'de> Deserialize<'de> for Config {
             node,
             oracles: raw_oracles,
             providers: raw_providers,
+            aggregates: raw_aggregates,
             comparison_providers: raw_comparison_providers,
+            history,
+            strict_coverage,
         }: raw::Config = raw::Config::deserialize(deserializer)?;
 
         let time_before_feeding: Duration =
@@ -101,7 +167,19 @@ impl<'de> Deserialize<'de> for Config {
             )?;
 
         let providers: BTreeMap<Box<str>, ProviderWithComparison> =
-            providers::reconstruct::<D>(raw_providers, str_pool, &oracles)?;
+            providers::reconstruct::<D>(
+                raw_providers,
+                &mut str_pool,
+                &oracles,
+            )?;
+
+        let aggregates: BTreeMap<Box<str>, Aggregate> =
+            aggregates::reconstruct::<D>(
+                raw_aggregates,
+                &mut str_pool,
+                &oracles,
+                &providers,
+            )?;
 
         Ok(Self {
             hard_gas_limit,
@@ -110,7 +188,10 @@ impl<'de> Deserialize<'de> for Config {
             node,
             oracles,
             providers,
+            aggregates,
             comparison_providers,
+            history,
+            strict_coverage,
         })
     }
 }
@@ -155,29 +236,78 @@ pub(crate) trait ProviderConfig: Sync + Send {
 pub(crate) trait ProviderConfigExt<const COMPARISON: bool>:
     ProviderConfig
 {
-    fn fetch_from_env(id: &str, name: &str) -> Result<String, EnvError>;
+    /// Name of the environment variable holding the provider's field.
+    fn env_name(id: &str, name: &str) -> String;
+
+    fn fetch_from_env(id: &str, name: &str) -> Result<String, EnvError> {
+        fetch_from_env(&Self::env_name(id, name))
+    }
+
+    /// Takes the string field out of the configuration or, when it isn't set
+    /// there, falls back to the deprecated environment variable.
+    fn take_or_fetch_from_env(
+        &mut self,
+        id: &str,
+        name: &'static str,
+    ) -> Result<String, FieldError> {
+        self.misc_mut().remove(name).map_or_else(
+            || {
+                let env_name: String = Self::env_name(id, name);
+
+                warn!(
+                    "Field \"{name}\" of provider with ID: {id} is read from \
+                    the environment variable \"{env_name}\", which is \
+                    deprecated! Set it in the configuration instead, e.g. as \
+                    \"${{{env_name}}}\"."
+                );
+
+                fetch_from_env(&env_name).map_err(FieldError::Env)
+            },
+            |value: toml::Value| {
+                value.try_into().map_err(|error: toml::de::Error| {
+                    FieldError::Deserialize(name, error)
+                })
+            },
+        )
+    }
 }
 
 impl<T> ProviderConfigExt<true> for T
 where
     T: ProviderConfig + ?Sized,
 {
-    fn fetch_from_env(id: &str, name: &str) -> Result<String, EnvError> {
-        let name: String = format!(
+    fn env_name(id: &str, name: &str) -> String {
+        format!(
             "COMPARISON_PROVIDER_{id}_{field}",
             id = id.to_ascii_uppercase(),
             field = name.to_ascii_uppercase()
-        );
-
-        var(&name).map_err(|error: env::VarError| EnvError(name, error))
+        )
     }
 }
 
+/// Reads the environment variable or, when it isn't set, the file at the path
+/// set in the one suffixed with `_FILE`.
+fn fetch_from_env(name: &str) -> Result<String, EnvError> {
+    env_var(name)
+        .and_then(|value: Option<String>| {
+            value.ok_or_else(|| Interpolation::NotSet(name.into()))
+        })
+        .map_err(EnvError)
+}
+
 #[derive(Debug, ThisError)]
-#[error("Variable name: \"{0}\". Cause: {1}")]
-pub(crate) struct EnvError(String, env::VarError);
+#[error(transparent)]
+pub(crate) struct EnvError(Interpolation);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, ThisError)]
+pub(crate) enum FieldError {
+    #[error("Failed to deserialize field \"{0}\"! Cause: {1}")]
+    Deserialize(&'static str, toml::de::Error),
+    #[error("Failed to fetch field from environment variables! Cause: {0}")]
+    Env(EnvError),
+}
+
+#[derive(Debug, Clone, PartialEq)]
 #[must_use]
 pub(crate) struct Provider {
     name: Arc<str>,
@@ -213,41 +343,209 @@ impl ProviderConfig for Provider {
 }
 
 impl ProviderConfigExt<false> for Provider {
-    fn fetch_from_env(id: &str, name: &str) -> Result<String, EnvError> {
-        let name: String = format!(
+    fn env_name(id: &str, name: &str) -> String {
+        format!(
             "PROVIDER_{id}_{field}",
             id = id.to_ascii_uppercase(),
             field = name.to_ascii_uppercase()
-        );
-
-        var(&name).map_err(|error: env::VarError| EnvError(name, error))
+        )
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 #[must_use]
 pub(crate) struct ProviderWithComparison {
     pub provider: Provider,
-    pub comparison: Option<ComparisonProviderIdAndMaxDeviation>,
+    pub comparison: Option<ComparisonProviderIdsAndMaxDeviation>,
+    pub feeding: Option<FeedingPolicy>,
+    pub jump_guard: Option<JumpGuard>,
+    pub cross_rates: Option<CrossRates>,
 }
 
-#[derive(Debug, Clone)]
+/// Derivation of the pairs requested by the oracle, which are not fetched
+/// directly, from the other prices fetched on the same tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[must_use]
-pub(crate) struct ComparisonProviderIdAndMaxDeviation {
-    pub provider_id: Arc<str>,
-    pub max_deviation_exclusive: u64,
+pub(crate) struct CrossRates {
+    /// Maximum count of fetched prices chained to derive a single pair.
+    pub max_hops: NonZeroUsize,
 }
 
-#[derive(Debug, Clone)]
+impl From<raw::CrossRates> for CrossRates {
+    fn from(raw::CrossRates { max_hops }: raw::CrossRates) -> Self {
+        const DEFAULT_MAX_HOPS: NonZeroUsize = match NonZeroUsize::new(2) {
+            Some(max_hops) => max_hops,
+            None => unreachable!(),
+        };
+
+        Self {
+            max_hops: max_hops.unwrap_or(DEFAULT_MAX_HOPS),
+        }
+    }
+}
+
+/// Limits of the change of a pair's price since it was last fed, beyond which
+/// the new price is refused until an operator accepts it through the override
+/// file.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub(crate) struct JumpGuard {
+    pub limits: DeviationLimits,
+    pub override_file: Option<PathBuf>,
+}
+
+impl From<raw::JumpGuard> for JumpGuard {
+    fn from(
+        raw::JumpGuard {
+            max_jump_bps,
+            ticker_max_jump_bps,
+            pair_max_jump_bps,
+            override_file,
+        }: raw::JumpGuard,
+    ) -> Self {
+        Self {
+            limits: DeviationLimits::new(
+                max_jump_bps,
+                ticker_max_jump_bps,
+                pair_max_jump_bps,
+            ),
+            override_file,
+        }
+    }
+}
+
+/// Policy under which prices are fed only when they have moved enough since
+/// they were last fed, or when they were last fed long enough ago.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct FeedingPolicy {
+    pub deviation: BasisPoints,
+    pub heartbeat: Duration,
+}
+
+impl From<raw::FeedingPolicy> for FeedingPolicy {
+    fn from(
+        raw::FeedingPolicy {
+            deviation_bps,
+            heartbeat_seconds,
+        }: raw::FeedingPolicy,
+    ) -> Self {
+        Self {
+            deviation: deviation_bps,
+            heartbeat: Duration::from_secs(heartbeat_seconds.get()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub(crate) struct ComparisonProviderIdsAndMaxDeviation {
+    pub provider_ids: Box<[Arc<str>]>,
+    pub quorum: NonZeroUsize,
+    pub deviation_limits: DeviationLimits,
+    pub continuous: Option<ContinuousComparison>,
+}
+
+/// Comparison guard check performed while feeding, in addition to the one
+/// performed before it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct ContinuousComparison {
+    pub every_ticks: NonZeroU64,
+    pub on_failure: ComparisonFailureAction,
+}
+
+/// Group of providers feeding a single oracle with prices aggregated out of
+/// the ones returned by each of them.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub(crate) struct Aggregate {
+    pub oracle_address: Arc<str>,
+    pub method: AggregationMethod,
+    pub min_sources: NonZeroUsize,
+    pub sources: BTreeMap<Box<str>, AggregateSource>,
+    pub comparison: Option<ComparisonProviderIdsAndMaxDeviation>,
+    pub feeding: Option<FeedingPolicy>,
+    pub jump_guard: Option<JumpGuard>,
+    pub cross_rates: Option<CrossRates>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub(crate) struct AggregateSource {
+    pub provider: Provider,
+    pub weight: NonZeroU32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum AggregationMethod {
+    /// Median of the prices returned by the sources.
+    #[default]
+    Median,
+    /// Mean of the prices returned by the sources, weighted by the sources'
+    /// configured weights, e.g. their pools' liquidity.
+    WeightedMean,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ComparisonFailureAction {
+    /// Feeds only the pairs which passed the check.
+    Skip,
+    /// Feeds none of the pairs until the check passes again.
+    #[default]
+    Hold,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 #[repr(transparent)]
 #[must_use]
 pub(crate) struct ComparisonProvider {
     pub provider: Provider,
 }
 
-fn deserialize_arc_str<'de, D>(deserializer: D) -> Result<Arc<str>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    String::deserialize(deserializer).map(Into::into)
+#[cfg(test)]
+#[test]
+fn test_take_or_fetch_from_env() {
+    let mut provider: Provider = Provider {
+        name: "osmosis".into(),
+        oracle_id: "osmosis".into(),
+        oracle_address: "nolus1oracle".into(),
+        misc: BTreeMap::from([
+            ("grpc_uri".into(), "https://grpc.osmosis.zone".into()),
+            ("lcd_url".into(), 443.into()),
+        ]),
+    };
+
+    assert_eq!(
+        ProviderConfigExt::<false>::take_or_fetch_from_env(
+            &mut provider,
+            "osmosis_test_only",
+            "grpc_uri"
+        )
+        .unwrap(),
+        "https://grpc.osmosis.zone"
+    );
+
+    assert!(!provider.misc.contains_key("grpc_uri"));
+
+    assert!(matches!(
+        ProviderConfigExt::<false>::take_or_fetch_from_env(
+            &mut provider,
+            "osmosis_test_only",
+            "lcd_url"
+        ),
+        Err(FieldError::Deserialize("lcd_url", _))
+    ));
+
+    assert!(matches!(
+        ProviderConfigExt::<false>::take_or_fetch_from_env(
+            &mut provider,
+            "osmosis_test_only",
+            "grpc_uri"
+        ),
+        Err(FieldError::Env(EnvError(Interpolation::NotSet(name))))
+            if &*name == "PROVIDER_OSMOSIS_TEST_ONLY_GRPC_URI"
+    ));
 }
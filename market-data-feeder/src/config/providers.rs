@@ -1,18 +1,24 @@
 use std::{
-    collections::btree_map::{BTreeMap, Entry as BTreeMapEntry},
+    collections::{
+        btree_map::{BTreeMap, Entry as BTreeMapEntry},
+        BTreeSet,
+    },
+    num::NonZeroUsize,
     sync::Arc,
 };
 
 use serde::de::{Deserializer, Error as DeserializeError};
 
+use crate::deviation::{BasisPoints, DeviationLimits};
+
 use super::{
-    get_oracle, raw, str_pool::StrPool, ComparisonProviderIdAndMaxDeviation,
-    Provider, ProviderConfigExt, ProviderWithComparison,
+    get_oracle, raw, str_pool::StrPool, ComparisonProviderIdsAndMaxDeviation,
+    ContinuousComparison, Provider, ProviderConfigExt, ProviderWithComparison,
 };
 
 pub(super) fn reconstruct<'r, 'de, D>(
     raw_providers: BTreeMap<String, raw::ProviderWithComparison>,
-    mut str_pool: StrPool,
+    str_pool: &'r mut StrPool,
     oracles: &'r BTreeMap<Arc<str>, Arc<str>>,
 ) -> Result<BTreeMap<Box<str>, ProviderWithComparison>, D::Error>
 where
@@ -31,6 +37,9 @@ where
                     misc,
                 },
             comparison,
+            feeding,
+            jump_guard,
+            cross_rates,
         },
     ) in raw_providers
     {
@@ -47,10 +56,11 @@ where
                 misc,
             },
             comparison: map_comparison_provider_option::<D>(
-                comparison,
-                &id,
-                &mut str_pool,
+                comparison, &id, str_pool,
             )?,
+            feeding: feeding.map(From::from),
+            jump_guard: jump_guard.map(From::from),
+            cross_rates: cross_rates.map(From::from),
         };
 
         match providers.entry(id) {
@@ -67,27 +77,131 @@ where
     Ok(providers)
 }
 
-fn map_comparison_provider_option<'de, D>(
-    comparison: Option<raw::ComparisonProviderIdAndMaxDeviation>,
+pub(super) fn map_comparison_provider_option<'de, D>(
+    comparison: Option<raw::ComparisonProviderIdsAndMaxDeviation>,
     id: &str,
     str_pool: &mut StrPool,
-) -> Result<Option<ComparisonProviderIdAndMaxDeviation>, D::Error>
+) -> Result<Option<ComparisonProviderIdsAndMaxDeviation>, D::Error>
 where
     D: Deserializer<'de>,
 {
     comparison
-        .map(|raw::ComparisonProviderIdAndMaxDeviation { provider_id }: raw::ComparisonProviderIdAndMaxDeviation| {
-            <Provider as ProviderConfigExt<false>>::fetch_from_env(id, "max_deviation")
-                .map_err(DeserializeError::custom)
-                .and_then(|value: String| {
-                    value.parse().map_err(DeserializeError::custom)
-                })
-                .map(|max_deviation_exclusive: u64| {
-                    ComparisonProviderIdAndMaxDeviation {
-                        provider_id: str_pool.get_or_insert(provider_id),
-                        max_deviation_exclusive,
-                    }
-                })
+        .map(|comparison: raw::ComparisonProviderIdsAndMaxDeviation| {
+            map_comparison_provider::<D>(comparison, id, str_pool)
         })
         .transpose()
 }
+
+fn map_comparison_provider<'de, D>(
+    raw::ComparisonProviderIdsAndMaxDeviation {
+        provider_id,
+        provider_ids,
+        quorum,
+        max_deviation_bps,
+        ticker_max_deviation_bps,
+        pair_max_deviation_bps,
+        check_every_ticks,
+        on_failure,
+    }: raw::ComparisonProviderIdsAndMaxDeviation,
+    id: &str,
+    str_pool: &mut StrPool,
+) -> Result<ComparisonProviderIdsAndMaxDeviation, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if provider_id.is_some() && !provider_ids.is_empty() {
+        return Err(DeserializeError::custom(format_args!(
+            "Provider with ID \"{id}\" has both \"provider_id\" and \"provider_ids\" set in its comparison configuration!",
+        )));
+    }
+
+    let mut unique_ids: BTreeSet<Arc<str>> = BTreeSet::new();
+
+    let provider_ids: Box<[Arc<str>]> = provider_id
+        .into_iter()
+        .chain(provider_ids)
+        .map(|provider_id: String| {
+            let provider_id: Arc<str> = str_pool.get_or_insert(provider_id);
+
+            if unique_ids.insert(provider_id.clone()) {
+                Ok(provider_id)
+            } else {
+                Err(DeserializeError::custom(format_args!(
+                    "Comparison provider with ID \"{provider_id}\" is listed more than once for provider with ID \"{id}\"!",
+                )))
+            }
+        })
+        .collect::<Result<_, D::Error>>()?;
+
+    let Some(providers_count): Option<NonZeroUsize> =
+        NonZeroUsize::new(provider_ids.len())
+    else {
+        return Err(DeserializeError::custom(format_args!(
+            "Provider with ID \"{id}\" has no comparison providers listed in its comparison configuration!",
+        )));
+    };
+
+    let quorum: NonZeroUsize = quorum.unwrap_or(providers_count);
+
+    if providers_count < quorum {
+        return Err(DeserializeError::custom(format_args!(
+            "Comparison quorum of provider with ID \"{id}\" is {quorum}, which is greater than the count of its comparison providers, {providers_count}!",
+        )));
+    }
+
+    let continuous: Option<ContinuousComparison> = match (
+        check_every_ticks,
+        on_failure,
+    ) {
+        (Some(every_ticks), on_failure) => Some(ContinuousComparison {
+            every_ticks,
+            on_failure: on_failure.unwrap_or_default(),
+        }),
+        (None, None) => None,
+        (None, Some(_)) => {
+            return Err(DeserializeError::custom(format_args!(
+                "Provider with ID \"{id}\" has \"on_failure\" set in its comparison configuration, but not \"check_every_ticks\"!",
+            )));
+        },
+    };
+
+    let default_max_deviation: BasisPoints =
+        if let Some(max_deviation_bps) = max_deviation_bps {
+            max_deviation_bps
+        } else {
+            default_max_deviation_from_env::<D>(id)?
+        };
+
+    Ok(ComparisonProviderIdsAndMaxDeviation {
+        provider_ids,
+        quorum,
+        deviation_limits: DeviationLimits::new(
+            default_max_deviation,
+            ticker_max_deviation_bps,
+            pair_max_deviation_bps,
+        ),
+        continuous,
+    })
+}
+
+/// Reads the legacy, percent based, maximum deviation from the environment
+/// and converts it into basis points.
+fn default_max_deviation_from_env<'de, D>(
+    id: &str,
+) -> Result<BasisPoints, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    <Provider as ProviderConfigExt<false>>::fetch_from_env(id, "max_deviation")
+        .map_err(DeserializeError::custom)
+        .and_then(|value: String| {
+            value.parse().map_err(DeserializeError::custom)
+        })
+        .and_then(|max_deviation_percent: u64| {
+            max_deviation_percent.checked_mul(100).ok_or_else(|| {
+                DeserializeError::custom(format_args!(
+                    "Maximum deviation of provider with ID \"{id}\" is too big!",
+                ))
+            })
+        })
+}
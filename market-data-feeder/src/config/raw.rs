@@ -1,9 +1,29 @@
-use std::{collections::BTreeMap, num::NonZeroU64};
+use std::{
+    collections::BTreeMap,
+    num::{NonZeroU32, NonZeroU64, NonZeroUsize},
+    path::PathBuf,
+};
 
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 use broadcast::config::Config as BroadcastConfig;
-use chain_comms::config::Node as NodeConfig;
+use chain_comms::config::{
+    from_str_or_value, some_from_str_or_value, Node as NodeConfig, StrOrValue,
+};
+
+use crate::deviation::BasisPoints;
+
+use super::{AggregationMethod, ComparisonFailureAction, Ticker};
+
+#[derive(Debug, Deserialize)]
+#[must_use]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub(super) struct SymbolAndDecimalPlaces {
+    pub denom: Option<String>,
+    pub cw20_contract: Option<String>,
+    #[serde(deserialize_with = "from_str_or_value")]
+    pub decimal_places: u8,
+}
 
 #[derive(Debug, Clone, Deserialize)]
 #[must_use]
@@ -21,14 +41,88 @@ pub(super) struct Provider {
 pub(super) struct ProviderWithComparison {
     #[serde(flatten)]
     pub provider: Provider,
-    pub comparison: Option<ComparisonProviderIdAndMaxDeviation>,
+    pub comparison: Option<ComparisonProviderIdsAndMaxDeviation>,
+    pub feeding: Option<FeedingPolicy>,
+    pub jump_guard: Option<JumpGuard>,
+    pub cross_rates: Option<CrossRates>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[must_use]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub(super) struct CrossRates {
+    #[serde(default, deserialize_with = "some_from_str_or_value")]
+    pub max_hops: Option<NonZeroUsize>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[must_use]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub(super) struct JumpGuard {
+    #[serde(deserialize_with = "from_str_or_value")]
+    pub max_jump_bps: BasisPoints,
+    #[serde(default, deserialize_with = "ticker_limits")]
+    pub ticker_max_jump_bps: BTreeMap<Ticker, BasisPoints>,
+    #[serde(default, deserialize_with = "pair_limits")]
+    pub pair_max_jump_bps: BTreeMap<Ticker, BTreeMap<Ticker, BasisPoints>>,
+    pub override_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[must_use]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub(super) struct FeedingPolicy {
+    #[serde(deserialize_with = "from_str_or_value")]
+    pub deviation_bps: BasisPoints,
+    #[serde(deserialize_with = "from_str_or_value")]
+    pub heartbeat_seconds: NonZeroU64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[must_use]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub(super) struct ComparisonProviderIdsAndMaxDeviation {
+    pub provider_id: Option<String>,
+    #[serde(default)]
+    pub provider_ids: Vec<String>,
+    #[serde(default, deserialize_with = "some_from_str_or_value")]
+    pub quorum: Option<NonZeroUsize>,
+    #[serde(default, deserialize_with = "some_from_str_or_value")]
+    pub max_deviation_bps: Option<BasisPoints>,
+    #[serde(default, deserialize_with = "ticker_limits")]
+    pub ticker_max_deviation_bps: BTreeMap<Ticker, BasisPoints>,
+    #[serde(default, deserialize_with = "pair_limits")]
+    pub pair_max_deviation_bps: BTreeMap<Ticker, BTreeMap<Ticker, BasisPoints>>,
+    #[serde(default, deserialize_with = "some_from_str_or_value")]
+    pub check_every_ticks: Option<NonZeroU64>,
+    pub on_failure: Option<ComparisonFailureAction>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 #[must_use]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
-pub(super) struct ComparisonProviderIdAndMaxDeviation {
-    pub provider_id: String,
+pub(super) struct Aggregate {
+    pub oracle_id: String,
+    #[serde(default)]
+    pub method: AggregationMethod,
+    #[serde(deserialize_with = "from_str_or_value")]
+    pub min_sources: NonZeroUsize,
+    pub sources: BTreeMap<String, AggregateSource>,
+    pub comparison: Option<ComparisonProviderIdsAndMaxDeviation>,
+    pub feeding: Option<FeedingPolicy>,
+    pub jump_guard: Option<JumpGuard>,
+    pub cross_rates: Option<CrossRates>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[must_use]
+#[serde(rename_all = "snake_case")]
+pub(super) struct AggregateSource {
+    pub name: String,
+    #[serde(default, deserialize_with = "some_from_str_or_value")]
+    pub weight: Option<NonZeroU32>,
+    #[serde(flatten)]
+    pub misc: BTreeMap<String, toml::Value>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -43,10 +137,145 @@ pub(super) struct ComparisonProvider {
 #[must_use]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub(super) struct Config {
+    #[serde(deserialize_with = "from_str_or_value")]
     pub hard_gas_limit: NonZeroU64,
     pub broadcast: BroadcastConfig,
     pub node: NodeConfig,
     pub oracles: BTreeMap<String, String>,
     pub providers: BTreeMap<String, ProviderWithComparison>,
+    #[serde(default)]
+    pub aggregates: BTreeMap<String, Aggregate>,
     pub comparison_providers: BTreeMap<String, ComparisonProvider>,
+    pub history: Option<super::History>,
+    #[serde(default, deserialize_with = "from_str_or_value")]
+    pub strict_coverage: bool,
+}
+
+/// Deserializes the limits of tickers, each as by [`from_str_or_value`].
+fn ticker_limits<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<Ticker, BasisPoints>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    BTreeMap::<Ticker, StrOrValue<BasisPoints>>::deserialize(deserializer).map(
+        |limits: BTreeMap<Ticker, StrOrValue<BasisPoints>>| {
+            limits
+                .into_iter()
+                .map(
+                    |(ticker, StrOrValue(limit)): (
+                        Ticker,
+                        StrOrValue<BasisPoints>,
+                    )| (ticker, limit),
+                )
+                .collect()
+        },
+    )
+}
+
+/// Deserializes the limits of pairs, keyed by their base and then by their
+/// quote ticker, each as by [`from_str_or_value`].
+fn pair_limits<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<Ticker, BTreeMap<Ticker, BasisPoints>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    BTreeMap::<Ticker, Limits>::deserialize(deserializer).map(
+        |limits: BTreeMap<Ticker, Limits>| {
+            limits
+                .into_iter()
+                .map(|(base, Limits(limits)): (Ticker, Limits)| (base, limits))
+                .collect()
+        },
+    )
+}
+
+/// Limits of tickers, deserialized by [`ticker_limits`].
+struct Limits(BTreeMap<Ticker, BasisPoints>);
+
+impl<'de> Deserialize<'de> for Limits {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        ticker_limits(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn test_interpolated_settings() {
+    let Aggregate {
+        min_sources,
+        sources,
+        comparison,
+        feeding,
+        jump_guard,
+        cross_rates,
+        ..
+    }: Aggregate = chain_comms::config::parse(
+        r#"
+oracle_id = "osmosis"
+min_sources = "${MIN_SOURCES:-2}"
+
+[sources.spot]
+name = "osmosis"
+weight = "${SPOT_WEIGHT:-3}"
+
+[sources.spot.currencies]
+OSMO = { denom = "uosmo", decimal_places = "${OSMO_DECIMAL_PLACES:-6}" }
+
+[sources.twap]
+name = "osmosis_twap"
+
+[comparison]
+provider_ids = ["sanity_check", "binance"]
+quorum = "${QUORUM:-1}"
+max_deviation_bps = "${MAX_DEVIATION_BPS:-500}"
+ticker_max_deviation_bps = { OSMO = "${OSMO_MAX_DEVIATION_BPS:-1000}" }
+pair_max_deviation_bps = { USDC = { USDT = "${STABLE_MAX_DEVIATION_BPS:-25}" } }
+check_every_ticks = "${CHECK_EVERY_TICKS:-10}"
+
+[feeding]
+deviation_bps = "${DEVIATION_BPS:-50}"
+heartbeat_seconds = "${HEARTBEAT_SECONDS:-3600}"
+
+[jump_guard]
+max_jump_bps = "${MAX_JUMP_BPS:-2000}"
+
+[cross_rates]
+max_hops = "${MAX_HOPS:-3}"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(min_sources.get(), 2);
+
+    let spot: &AggregateSource = &sources["spot"];
+
+    assert_eq!(spot.weight, NonZeroU32::new(3));
+
+    let currencies: BTreeMap<Ticker, SymbolAndDecimalPlaces> =
+        spot.misc["currencies"].clone().try_into().unwrap();
+
+    assert_eq!(currencies["OSMO"].decimal_places, 6);
+
+    let comparison: ComparisonProviderIdsAndMaxDeviation = comparison.unwrap();
+
+    assert_eq!(comparison.quorum, NonZeroUsize::new(1));
+    assert_eq!(comparison.max_deviation_bps, Some(500));
+    assert_eq!(comparison.ticker_max_deviation_bps["OSMO"], 1000);
+    assert_eq!(comparison.pair_max_deviation_bps["USDC"]["USDT"], 25);
+    assert_eq!(comparison.check_every_ticks, NonZeroU64::new(10));
+
+    let feeding: FeedingPolicy = feeding.unwrap();
+
+    assert_eq!(feeding.deviation_bps, 50);
+    assert_eq!(feeding.heartbeat_seconds.get(), 3600);
+
+    assert_eq!(jump_guard.unwrap().max_jump_bps, 2000);
+
+    assert_eq!(cross_rates.unwrap().max_hops, NonZeroUsize::new(3));
 }
@@ -1,41 +1,127 @@
 use std::collections::BTreeMap;
 
-use bnum::BUint;
-
 use crate::{
-    config::Ticker,
-    price::{Coin, CoinWithDecimalPlaces, Price},
+    config::{Ticker, TickerUnsized},
+    price::{Coin, CoinWithDecimalPlaces, Price, UInt},
     provider::PriceComparisonGuardError,
 };
 
-/// Capable of storing integers with precision of 320 bits.
-pub(crate) type UInt = BUint<5>;
+/// One hundredth of a percent.
+pub(crate) type BasisPoints = u64;
+
+/// Exclusive upper bounds of the deviation allowed between a price and its
+/// comparison price.
+///
+/// Overrides for a specific pair take precedence over the ones for the
+/// tickers composing it, regardless of the pair's direction. When both of the
+/// tickers have overrides, the greater one is used, as the more volatile
+/// currency determines the pair's volatility.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct DeviationLimits {
+    default: BasisPoints,
+    tickers: BTreeMap<Ticker, BasisPoints>,
+    pairs: BTreeMap<Ticker, BTreeMap<Ticker, BasisPoints>>,
+}
+
+impl DeviationLimits {
+    pub const fn new(
+        default: BasisPoints,
+        tickers: BTreeMap<Ticker, BasisPoints>,
+        pairs: BTreeMap<Ticker, BTreeMap<Ticker, BasisPoints>>,
+    ) -> Self {
+        Self {
+            default,
+            tickers,
+            pairs,
+        }
+    }
+
+    pub fn get(
+        &self,
+        base: &TickerUnsized,
+        quote: &TickerUnsized,
+    ) -> BasisPoints {
+        self.pair(base, quote)
+            .or_else(|| self.pair(quote, base))
+            .or_else(|| {
+                match (self.tickers.get(base), self.tickers.get(quote)) {
+                    (Some(&base), Some(&quote)) => Some(base.max(quote)),
+                    (Some(&limit), None) | (None, Some(&limit)) => Some(limit),
+                    (None, None) => None,
+                }
+            })
+            .unwrap_or(self.default)
+    }
+
+    fn pair(
+        &self,
+        base: &TickerUnsized,
+        quote: &TickerUnsized,
+    ) -> Option<BasisPoints> {
+        self.pairs
+            .get(base)
+            .and_then(|quotes: &BTreeMap<Ticker, BasisPoints>| {
+                quotes.get(quote)
+            })
+            .copied()
+    }
+}
 
+/// Calculates the deviation of `price` from `reference`, in basis points.
+///
+/// Both prices are expected to be of the same pair.
+pub(crate) fn deviation_between(
+    price: &Price<CoinWithDecimalPlaces>,
+    reference: &Price<CoinWithDecimalPlaces>,
+) -> UInt {
+    deviation_bps(
+        price,
+        reference.amount().amount(),
+        reference.amount_quote().amount(),
+    )
+}
+
+fn deviation_bps(
+    price: &Price<CoinWithDecimalPlaces>,
+    comparison_base: u128,
+    comparison_quote: u128,
+) -> UInt {
+    const WHOLE: UInt = UInt::from_digit(10_000);
+
+    /*
+    CP_base    P_base      X
+    ------- = -------- * -----
+    CP_quote   P_quote   10000
+
+        CP_base    P_quote           CP_base * P_quote * 10000
+    X = -------- * ------- * 10000 = -------------------------
+        CP_quote   P_base               CP_quote * P_base
+
+    Deviation = ABS(10000 - X)
+    */
+    let basis_points_of_comparison_price: UInt = (UInt::from(comparison_base)
+        * UInt::from(price.amount_quote().amount())
+        * WHOLE)
+        / (UInt::from(comparison_quote) * UInt::from(price.amount().amount()));
+
+    if basis_points_of_comparison_price < WHOLE {
+        WHOLE - basis_points_of_comparison_price
+    } else {
+        basis_points_of_comparison_price - WHOLE
+    }
+}
+
+/// Compares all of the prices, instead of stopping at the first failing one,
+/// so that the error covers each of the failing pairs.
 pub(crate) fn compare_prices<C>(
     prices: &[Price<CoinWithDecimalPlaces>],
     comparison_prices: &[Price<C>],
-    max_deviation_exclusive: u64,
+    deviation_limits: &DeviationLimits,
 ) -> Result<(), PriceComparisonGuardError>
 where
     C: Coin,
 {
-    const HUNDRED: UInt = UInt::from_digit(100);
-
-    fn to_big_uint(n: u128) -> UInt {
-        // Order is documented to be in Little-Endian.
-        UInt::from_digits([
-            (n & u128::from(u64::MAX))
-                .try_into()
-                .unwrap_or_else(|_| unreachable!()),
-            (n >> u64::BITS)
-                .try_into()
-                .unwrap_or_else(|_| unreachable!()),
-            0,
-            0,
-            0,
-        ])
-    }
-
     let mut map: BTreeMap<Ticker, BTreeMap<Ticker, (u128, u128)>> =
         BTreeMap::new();
 
@@ -62,53 +148,152 @@ where
         }
     }
 
+    let mut failures: Vec<PriceComparisonGuardError> = Vec::new();
+
     for price in prices {
-        let (comparison_base, comparison_quote): (u128, u128) = map
-            .get(price.amount().ticker())
-            .and_then(|map: &BTreeMap<Ticker, (u128, u128)>| {
-                map.get(price.amount_quote().ticker())
-            })
-            .copied()
-            .ok_or_else(|| {
-                PriceComparisonGuardError::MissingComparisonPrice(
-                    price.amount().ticker().to_string(),
-                    price.amount_quote().ticker().to_string(),
-                )
-            })?;
-
-        /*
-        CP_base    P_base     X
-        ------- = -------- * ---
-        CP_quote   P_quote   100
-
-            CP_base    P_quote         CP_base * P_quote * 100
-        X = -------- * ------- * 100 = -----------------------
-            CP_quote   P_base             CP_quote * P_base
-
-        Deviation = ABS(100 - X)
-        */
-        let percentage_of_comparison_price: UInt =
-            (to_big_uint(comparison_base)
-                * to_big_uint(price.amount_quote().amount())
-                * HUNDRED)
-                / (to_big_uint(comparison_quote)
-                    * to_big_uint(price.amount().amount()));
-
-        let deviation_percent: UInt =
-            if percentage_of_comparison_price < HUNDRED {
-                HUNDRED - percentage_of_comparison_price
-            } else {
-                percentage_of_comparison_price - HUNDRED
-            };
-
-        if deviation_percent >= UInt::from_digit(max_deviation_exclusive) {
-            return Err(PriceComparisonGuardError::DeviationTooBig(
+        let Some((comparison_base, comparison_quote)): Option<(u128, u128)> =
+            map.get(price.amount().ticker())
+                .and_then(|map: &BTreeMap<Ticker, (u128, u128)>| {
+                    map.get(price.amount_quote().ticker())
+                })
+                .copied()
+        else {
+            failures.push(PriceComparisonGuardError::MissingComparisonPrice(
+                price.amount().ticker().to_string(),
+                price.amount_quote().ticker().to_string(),
+            ));
+
+            continue;
+        };
+
+        let deviation: UInt =
+            deviation_bps(price, comparison_base, comparison_quote);
+
+        let max_deviation_exclusive: BasisPoints = deviation_limits
+            .get(price.amount().ticker(), price.amount_quote().ticker());
+
+        if deviation >= UInt::from_digit(max_deviation_exclusive) {
+            failures.push(PriceComparisonGuardError::DeviationTooBig(
                 price.amount().ticker().to_string(),
                 price.amount_quote().ticker().to_string(),
-                deviation_percent,
+                deviation,
+                max_deviation_exclusive,
             ));
         }
     }
 
-    Ok(())
+    if failures.len() > 1 {
+        Err(PriceComparisonGuardError::PairsFailed(
+            failures.into_boxed_slice(),
+        ))
+    } else {
+        failures.pop().map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(test)]
+fn test_price(
+    base: &str,
+    quote: &str,
+    amount_quote: u128,
+) -> Price<CoinWithDecimalPlaces> {
+    Price::new(
+        CoinWithDecimalPlaces::new(10_000, base.into(), 6),
+        CoinWithDecimalPlaces::new(amount_quote, quote.into(), 6),
+    )
+}
+
+#[cfg(test)]
+fn test_deviation_limits() -> DeviationLimits {
+    DeviationLimits::new(
+        100,
+        BTreeMap::from([("USDC".into(), 50), ("OSMO".into(), 300)]),
+        BTreeMap::from([(
+            "USDC".into(),
+            BTreeMap::from([("USDT".into(), 10)]),
+        )]),
+    )
+}
+
+#[cfg(test)]
+#[test]
+fn test_deviation_limits_precedence() {
+    let limits: DeviationLimits = test_deviation_limits();
+
+    assert_eq!(limits.get("ATOM", "NLS"), 100);
+    assert_eq!(limits.get("ATOM", "USDC"), 50);
+    assert_eq!(limits.get("OSMO", "USDC"), 300);
+    assert_eq!(limits.get("USDC", "USDT"), 10);
+    assert_eq!(limits.get("USDT", "USDC"), 10);
+}
+
+#[cfg(test)]
+#[test]
+fn test_compare_prices_in_basis_points() {
+    let limits: DeviationLimits = test_deviation_limits();
+
+    let comparison_prices: [Price<CoinWithDecimalPlaces>; 2] = [
+        test_price("USDC", "USDT", 10_000),
+        test_price("ATOM", "NLS", 10_000),
+    ];
+
+    compare_prices(
+        &[
+            test_price("USDC", "USDT", 10_009),
+            test_price("ATOM", "NLS", 10_099),
+        ],
+        &comparison_prices,
+        &limits,
+    )
+    .unwrap();
+
+    assert!(matches!(
+        compare_prices(
+            &[test_price("USDC", "USDT", 10_010)],
+            &comparison_prices,
+            &limits,
+        ),
+        Err(PriceComparisonGuardError::DeviationTooBig(base, quote, deviation, 10))
+            if base == "USDC"
+                && quote == "USDT"
+                && deviation == UInt::from_digit(10)
+    ));
+
+    assert!(matches!(
+        compare_prices(
+            &[test_price("ATOM", "NLS", 9_900)],
+            &comparison_prices,
+            &limits,
+        ),
+        Err(PriceComparisonGuardError::DeviationTooBig(_, _, deviation, 100))
+            if deviation == UInt::from_digit(100)
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_compare_prices_reports_all_failing_pairs() {
+    let Err(error) = compare_prices(
+        &[
+            test_price("USDC", "USDT", 10_010),
+            test_price("ATOM", "NLS", 10_000),
+            test_price("OSMO", "NLS", 10_000),
+        ],
+        &[
+            test_price("USDC", "USDT", 10_000),
+            test_price("ATOM", "NLS", 10_000),
+        ],
+        &test_deviation_limits(),
+    ) else {
+        unreachable!()
+    };
+
+    assert!(matches!(
+        &error,
+        PriceComparisonGuardError::PairsFailed(errors) if errors.len() == 2
+    ));
+
+    assert!(error.fails_pair("USDC", "USDT"));
+    assert!(error.fails_pair("OSMO", "NLS"));
+    assert!(!error.fails_pair("ATOM", "NLS"));
 }
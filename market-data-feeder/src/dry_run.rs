@@ -0,0 +1,336 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+use serde_json::value::RawValue;
+use tokio::{
+    select,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    task::block_in_place,
+};
+use tracing::{error, info, warn};
+
+use broadcast::{
+    generators::{SpawnResult, TxRequest},
+    mode::NonBlocking,
+};
+use chain_comms::{
+    client::Client as NodeClient,
+    config,
+    interact::simulate,
+    reexport::cosmrs::proto::{
+        cosmos::base::abci::v1beta1::GasInfo,
+        cosmwasm::wasm::v1::MsgExecuteContract, Any as ProtobufAny,
+    },
+    rpc_setup::{error::Error as RpcSetupError, prepare_rpc, RpcSetup},
+    signer::Signer,
+    signing_key::DEFAULT_COSMOS_HD_PATH,
+};
+
+use crate::{
+    check_compatibility,
+    config::Config,
+    error as error_mod,
+    result::Result,
+    workers::{self, CheckedProviders, SpawnContext},
+};
+
+/// Options of the `dry-run` subcommand.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct Options {
+    /// File to which the payloads are appended, instead of the standard
+    /// output.
+    pub output: Option<PathBuf>,
+    /// Whether to simulate the would-be transactions. Requires the signing
+    /// key, as simulated transactions have to be signed.
+    pub simulate: bool,
+}
+
+/// Usage of the `dry-run` subcommand.
+pub(crate) const USAGE: &str = "\
+Usage: market-data-feeder dry-run [OPTIONS]
+
+Fetches and checks prices as configured, printing the price feeding messages
+which would have been broadcast, one JSON object per line. Nothing is
+committed to the chain and no signing key is needed, unless simulating.
+
+Options:
+    --output <FILE>        Append the messages to the given file instead.
+    --simulate             Simulate each message and report the gas it would
+                           use. Requires the signing key.";
+
+/// Parses the arguments following the `dry-run` subcommand.
+pub(crate) fn parse_args<I>(args: I) -> Result<Options>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+
+    let mut options: Options = Options::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--simulate" => options.simulate = true,
+            "--output" => {
+                options.output = Some(
+                    args.next()
+                        .ok_or_else(|| {
+                            error_mod::Application::InvalidArguments(format!(
+                                "Option \"{arg}\" requires a value!"
+                            ))
+                        })?
+                        .into(),
+                );
+            },
+            _ => {
+                return Err(error_mod::Application::InvalidArguments(format!(
+                    "Unknown option \"{arg}\"!\n\n{USAGE}"
+                )));
+            },
+        }
+    }
+
+    Ok(options)
+}
+
+/// Single line of the dry run's output.
+#[derive(Serialize)]
+struct Payload<'r> {
+    /// Milliseconds since the Unix epoch.
+    timestamp_ms: u64,
+    oracle: &'r str,
+    /// Execution message as it would have been sent to the oracle.
+    msg: &'r RawValue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    simulation: Option<&'r Simulation>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Simulation {
+    Succeeded { gas_wanted: u64, gas_used: u64 },
+    Failed { error: String },
+}
+
+#[allow(clippy::future_not_send)]
+pub(crate) async fn run(
+    config_path: &Path,
+    Options { output, simulate }: Options,
+) -> Result<()> {
+    let (config, node_client, mut signer): (
+        Config,
+        NodeClient,
+        Option<Signer>,
+    ) = if simulate {
+        let RpcSetup {
+            signer,
+            config,
+            node_client,
+            ..
+        }: RpcSetup<Config> =
+            prepare_rpc(config_path, DEFAULT_COSMOS_HD_PATH).await?;
+
+        (config, node_client, Some(signer))
+    } else {
+        let config: Config = config::read(config_path)
+            .await
+            .map_err(RpcSetupError::from)?;
+
+        let node_client: NodeClient = NodeClient::from_config(config.as_ref())
+            .await
+            .map_err(RpcSetupError::from)?;
+
+        (config, node_client, None)
+    };
+
+    check_compatibility(&config, &mut node_client.wasm_query_client()).await?;
+
+    let providers: CheckedProviders = workers::check_providers(
+        &node_client,
+        config.providers,
+        config.aggregates,
+        config.comparison_providers,
+        config.strict_coverage,
+    )
+    .await?;
+
+    let mut output: Box<dyn Write + Send> = match &output {
+        Some(path) => Box::new(open_output(path)?),
+        None => Box::new(io::stdout()),
+    };
+
+    let (tx_request_sender, mut tx_request_receiver): (
+        UnboundedSender<TxRequest<NonBlocking>>,
+        UnboundedReceiver<TxRequest<NonBlocking>>,
+    ) = unbounded_channel();
+
+    info!("Starting workers in dry run mode...");
+
+    // Kept alive until the end, as dropping it stops the workers.
+    let mut spawn_result: SpawnResult = block_in_place(|| {
+        crate::workers::spawn(SpawnContext {
+            node_client: node_client.clone(),
+            providers,
+            tx_request_sender,
+            signer_address: signer.as_ref().map_or_else(
+                || Arc::from(""),
+                |signer: &Signer| Arc::from(signer.signer_address()),
+            ),
+            hard_gas_limit: config.hard_gas_limit,
+            time_before_feeding: config.time_before_feeding,
+            tick_time: config.broadcast.tick_time,
+            poll_time: config.broadcast.poll_time,
+            history: None,
+            reload_config_path: None,
+        })
+    })?;
+
+    info!("Workers started successfully. Nothing will be broadcast.");
+
+    let result: Result<()> = loop {
+        select! {
+            result = tokio::signal::ctrl_c() => {
+                if let Err(error) = result {
+                    error!(?error, "Error received from Ctrl+C signal handler! Stopping dry run! Error: {error}");
+                } else {
+                    info!("Received Ctrl+C signal. Stopping dry run.");
+                }
+
+                break Ok(());
+            }
+            Some(error) = spawn_result.join_next_generator() => {
+                if error.is_panic() {
+                    error!("Worker task panicked!");
+                } else {
+                    info!("Worker task was cancelled.");
+                }
+            }
+            maybe_tx_request = tx_request_receiver.recv() => {
+                let Some(tx_request) = maybe_tx_request else {
+                    break Err(error_mod::Application::DryRunWorkersStopped);
+                };
+
+                let simulation: Option<Simulation> = if let Some(signer) = &mut signer {
+                    Some(simulate_request(signer, &node_client, &config.node, &tx_request).await)
+                } else {
+                    None
+                };
+
+                for message in tx_request.messages() {
+                    if let Err(error) = write_payload(&mut output, message, simulation.as_ref()) {
+                        error!(?error, "Failed to write dry run payload! Cause: {error}");
+                    }
+                }
+            }
+        }
+    };
+
+    drop(spawn_result);
+
+    output
+        .flush()
+        .map_err(error_mod::Application::DryRunOutput)?;
+
+    result
+}
+
+fn open_output(path: &Path) -> Result<impl Write + Send> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(error_mod::Application::DryRunOutput)
+}
+
+async fn simulate_request(
+    signer: &mut Signer,
+    node_client: &NodeClient,
+    node_config: &config::Node,
+    tx_request: &TxRequest<NonBlocking>,
+) -> Simulation {
+    let result: std::result::Result<GasInfo, simulate::error::Error> =
+        simulate::with_serialized_messages(
+            signer,
+            node_client,
+            node_config,
+            tx_request.hard_gas_limit(),
+            tx_request.messages().to_vec(),
+        )
+        .await;
+
+    match result {
+        Ok(GasInfo {
+            gas_wanted,
+            gas_used,
+        }) => Simulation::Succeeded {
+            gas_wanted,
+            gas_used,
+        },
+        Err(error) => {
+            warn!(?error, "Simulation failed! Cause: {error}");
+
+            Simulation::Failed {
+                error: error.to_string(),
+            }
+        },
+    }
+}
+
+fn write_payload<W>(
+    output: &mut W,
+    message: &ProtobufAny,
+    simulation: Option<&Simulation>,
+) -> io::Result<()>
+where
+    W: Write + ?Sized,
+{
+    let MsgExecuteContract { contract, msg, .. } = message
+        .to_msg()
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+    let msg: &RawValue = serde_json::from_slice(&msg)?;
+
+    serde_json::to_writer(
+        &mut *output,
+        &Payload {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| {
+                    duration.as_millis().try_into().unwrap_or(u64::MAX)
+                }),
+            oracle: &contract,
+            msg,
+            simulation,
+        },
+    )?;
+
+    output.write_all(b"\n")?;
+
+    output.flush()
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_args() {
+    assert_eq!(
+        parse_args(
+            ["--simulate", "--output", "payloads.jsonl"].map(String::from)
+        )
+        .unwrap(),
+        Options {
+            output: Some(PathBuf::from("payloads.jsonl")),
+            simulate: true,
+        }
+    );
+
+    assert_eq!(parse_args([]).unwrap(), Options::default());
+
+    assert!(parse_args(["--output"].map(String::from)).is_err());
+    assert!(parse_args(["--broadcast"].map(String::from)).is_err());
+}
@@ -3,6 +3,8 @@ use std::{error::Error as StdError, sync::Arc};
 use semver::Version;
 use thiserror::Error as ThisError;
 
+use chain_comms::{cli::Exit, rpc_setup::error::Error as RpcSetupError};
+
 use crate::provider::PriceComparisonGuardError;
 
 #[derive(Debug, ThisError)]
@@ -12,7 +14,7 @@ pub(crate) enum Application {
         #[from] tracing::dispatcher::SetGlobalDefaultError,
     ),
     #[error("Setting up RPC environment failed! Cause: {0}")]
-    RpcSetup(#[from] chain_comms::rpc_setup::error::Error),
+    RpcSetup(#[from] RpcSetupError),
     #[error("Failed to serialize version query message as JSON! Cause: {0}")]
     SerializeVersionQueryMessage(#[from] serde_json_wasm::ser::Error),
     #[error("Failed to query contract's version! Cause: {0}")]
@@ -35,6 +37,48 @@ pub(crate) enum Application {
     ),
     #[error("A worker thread has exited due to an error! Cause: {0}")]
     Worker(#[from] Worker),
+    #[error("History store failure! Cause: {0}")]
+    History(#[from] crate::history::Error),
+    #[error("Invalid command-line arguments! {0}")]
+    InvalidArguments(String),
+    #[error("Failed to write dry run output! Cause: {0}")]
+    DryRunOutput(std::io::Error),
+    #[error("All workers have stopped before the dry run was stopped!")]
+    DryRunWorkersStopped,
+    #[error("No provider or aggregate is configured with identifier: {0}")]
+    NoSuchProvider(Box<str>),
+    #[error("Failed to fetch prices! Cause: {0}")]
+    FetchPrices(#[from] crate::provider::ProviderError),
+    #[error("Failed to write prices! Cause: {0}")]
+    WritePrices(std::io::Error),
+    #[error("Failed to check provider \"{0}\" against its chain! Cause: {1}")]
+    CheckProvider(Box<str>, crate::provider::ProviderError),
+    #[error("Configured currencies don't match chains' metadata! Mismatches found: {0}")]
+    DenomMismatches(usize),
+    #[error("Coverage of oracles' pairs is incomplete, while strict coverage is required! Dropped pairs: {0}")]
+    IncompleteCoverage(usize),
+}
+
+impl Application {
+    pub const fn exit(&self) -> Exit {
+        match self {
+            Self::InvalidArguments(_)
+            | Self::NoSuchProvider(_)
+            | Self::History(crate::history::Error::InvalidQuery(_)) => {
+                Exit::Usage
+            },
+            Self::RpcSetup(RpcSetupError::Configuration(_))
+            | Self::UnknownProviderId(_)
+            | Self::UnknownPriceComparisonProviderId(_)
+            | Self::DenomMismatches(_)
+            | Self::IncompleteCoverage(_)
+            | Self::Worker(
+                Worker::InstantiateProvider(..)
+                | Worker::InstantiatePriceComparisonProvider(..),
+            ) => Exit::Configuration,
+            _ => Exit::Failure,
+        }
+    }
 }
 
 #[derive(Debug, ThisError)]
@@ -59,3 +103,26 @@ pub(crate) enum Worker {
         #[from] chain_comms::reexport::cosmrs::proto::prost::EncodeError,
     ),
 }
+
+#[cfg(test)]
+#[test]
+fn test_exit() {
+    assert_eq!(
+        Application::Worker(Worker::InstantiateProvider(
+            "osmosis".into(),
+            Box::new(std::fmt::Error),
+        ))
+        .exit(),
+        Exit::Configuration
+    );
+
+    assert_eq!(
+        Application::Worker(Worker::EmptyPriceList).exit(),
+        Exit::Failure
+    );
+
+    assert_eq!(
+        Application::NoSuchProvider("osmosis".into()).exit(),
+        Exit::Usage
+    );
+}
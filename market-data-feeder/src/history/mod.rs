@@ -0,0 +1,369 @@
+use std::{
+    fs::OpenOptions,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error as ThisError;
+use tokio::{
+    fs::File,
+    io::AsyncWriteExt as _,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+};
+use tracing::error;
+
+use chain_comms::interact::{get_tx_response::Response as TxResponse, TxHash};
+
+use crate::{
+    config::Ticker,
+    price::{Coin as _, CoinWithDecimalPlaces, Price},
+};
+
+pub(crate) mod query;
+
+/// Single line of the history file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[must_use]
+pub(crate) struct Record {
+    /// Milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub provider_id: Box<str>,
+    pub oracle: Box<str>,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum Event {
+    /// Prices returned by the provider on a tick.
+    Fetched { prices: Vec<PriceEntry> },
+    /// Outcome of benchmarking the provider's prices.
+    Compared {
+        passed: bool,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        skipped_pairs: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// Prices sent to be fed to the oracle, recorded once the outcome of
+    /// committing them is known, along with the hash of the transaction when
+    /// it was committed.
+    Fed {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tx_hash: Option<String>,
+        prices: Vec<PriceEntry>,
+    },
+    /// Transaction feeding the last sent prices was committed.
+    Committed { tx_hash: String },
+    /// Transaction feeding the last sent prices failed to be committed.
+    CommitFailed { code: u32, raw_log: String },
+    /// Delivery outcome of a committed transaction, or none when it wasn't
+    /// found before polling stopped.
+    Delivered {
+        tx_hash: String,
+        outcome: Option<TxOutcome>,
+    },
+}
+
+impl Event {
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Fetched { .. } => "fetched",
+            Self::Compared { .. } => "compared",
+            Self::Fed { .. } => "fed",
+            Self::Committed { .. } => "committed",
+            Self::CommitFailed { .. } => "commit_failed",
+            Self::Delivered { .. } => "delivered",
+        }
+    }
+
+    pub fn fetched(prices: &[Price<CoinWithDecimalPlaces>]) -> Self {
+        Self::Fetched {
+            prices: prices.iter().map(PriceEntry::from).collect(),
+        }
+    }
+
+    pub fn fed(
+        prices: &[Price<CoinWithDecimalPlaces>],
+        tx_hash: Option<&TxHash>,
+    ) -> Self {
+        Self::Fed {
+            tx_hash: tx_hash.map(|tx_hash: &TxHash| tx_hash.0.clone()),
+            prices: prices.iter().map(PriceEntry::from).collect(),
+        }
+    }
+
+    pub fn delivered(
+        tx_hash: &TxHash,
+        tx_response: Option<&TxResponse>,
+    ) -> Self {
+        Self::Delivered {
+            tx_hash: tx_hash.0.clone(),
+            outcome: tx_response.map(|tx_response: &TxResponse| TxOutcome {
+                block_height: tx_response.block_height,
+                code: tx_response.code.value(),
+                gas_wanted: tx_response.gas_wanted,
+                gas_used: tx_response.gas_used,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct PriceEntry {
+    pub base: CoinEntry,
+    pub quote: CoinEntry,
+    /// Amount of quote paid for one whole unit of base, rendered with the
+    /// full precision of the amounts, see [`Price::to_decimal_string`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+}
+
+impl PriceEntry {
+    /// Renders the price of the entry, also for records written before the
+    /// rendered price was recorded along with the amounts.
+    pub fn to_decimal_string(&self) -> Option<String> {
+        self.price.clone().or_else(|| {
+            let coin = |entry: &CoinEntry| {
+                entry.amount.parse().ok().map(|amount: u128| {
+                    CoinWithDecimalPlaces::new(
+                        amount,
+                        entry.ticker.clone(),
+                        entry.decimal_places,
+                    )
+                })
+            };
+
+            Price::new(coin(&self.base)?, coin(&self.quote)?)
+                .to_decimal_string()
+        })
+    }
+}
+
+impl From<&Price<CoinWithDecimalPlaces>> for PriceEntry {
+    fn from(price: &Price<CoinWithDecimalPlaces>) -> Self {
+        Self {
+            base: CoinEntry::from(price.amount()),
+            quote: CoinEntry::from(price.amount_quote()),
+            price: price.to_decimal_string(),
+        }
+    }
+}
+
+/// Coin with its amount as a string, as JSON numbers can't represent all
+/// 128-bit integers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct CoinEntry {
+    pub amount: String,
+    pub ticker: Ticker,
+    pub decimal_places: u8,
+}
+
+impl From<&CoinWithDecimalPlaces> for CoinEntry {
+    fn from(coin: &CoinWithDecimalPlaces) -> Self {
+        Self {
+            amount: coin.amount().to_string(),
+            ticker: coin.ticker().to_string(),
+            decimal_places: coin.decimal_places(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct TxOutcome {
+    pub block_height: u64,
+    pub code: u32,
+    pub gas_wanted: u64,
+    pub gas_used: u64,
+}
+
+/// Handle of the append-only history file.
+///
+/// Records are sent to a background task which writes them, one JSON object
+/// per line, so recording never blocks the workers.
+#[derive(Clone)]
+#[must_use]
+pub(crate) struct History {
+    sender: UnboundedSender<Record>,
+}
+
+impl History {
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let file: File = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map(File::from_std)
+            .map_err(|error: io::Error| Error::Open(path.into(), error))?;
+
+        let (sender, receiver): (
+            UnboundedSender<Record>,
+            UnboundedReceiver<Record>,
+        ) = unbounded_channel();
+
+        tokio::spawn(write_records(path.into(), file, receiver));
+
+        Ok(Self { sender })
+    }
+
+    pub fn recorder(&self, provider_id: &str, oracle: &str) -> Recorder {
+        Recorder {
+            sender: self.sender.clone(),
+            provider_id: provider_id.into(),
+            oracle: oracle.into(),
+        }
+    }
+}
+
+/// Records the events of a single provider's task.
+#[must_use]
+pub(crate) struct Recorder {
+    sender: UnboundedSender<Record>,
+    provider_id: Arc<str>,
+    oracle: Arc<str>,
+}
+
+impl Recorder {
+    pub fn record(&self, event: Event) {
+        let record: Record = Record {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| {
+                    duration.as_millis().try_into().unwrap_or(u64::MAX)
+                }),
+            provider_id: self.provider_id.as_ref().into(),
+            oracle: self.oracle.as_ref().into(),
+            event,
+        };
+
+        if self.sender.send(record).is_err() {
+            error!(
+                provider_id = %self.provider_id,
+                "History writer has stopped! Record dropped."
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn test_recorder() -> (Recorder, UnboundedReceiver<Record>) {
+    let (sender, receiver): (
+        UnboundedSender<Record>,
+        UnboundedReceiver<Record>,
+    ) = unbounded_channel();
+
+    (
+        Recorder {
+            sender,
+            provider_id: Arc::from("provider"),
+            oracle: Arc::from("oracle"),
+        },
+        receiver,
+    )
+}
+
+async fn write_records(
+    path: PathBuf,
+    mut file: File,
+    mut receiver: UnboundedReceiver<Record>,
+) {
+    while let Some(record) = receiver.recv().await {
+        let mut line: Vec<u8> = match serde_json::to_vec(&record) {
+            Ok(line) => line,
+            Err(error) => {
+                error!(
+                    error = ?error,
+                    "Failed to serialize history record! Cause: {error}",
+                );
+
+                continue;
+            },
+        };
+
+        line.push(b'\n');
+
+        if let Err(error) = write_line(&mut file, &line).await {
+            error!(
+                error = ?error,
+                "Failed to write history record to \"{path}\"! Cause: {error}",
+                path = path.display(),
+            );
+        }
+    }
+}
+
+async fn write_line(file: &mut File, line: &[u8]) -> io::Result<()> {
+    file.write_all(line).await?;
+
+    file.flush().await
+}
+
+#[derive(Debug, ThisError)]
+pub(crate) enum Error {
+    #[error("Failed to open history file \"{}\"! Cause: {1}", .0.display())]
+    Open(PathBuf, io::Error),
+    #[error("Failed to read history file \"{}\"! Cause: {1}", .0.display())]
+    Read(PathBuf, io::Error),
+    #[error("Failed to parse line {0} of history file! Cause: {1}")]
+    Parse(usize, serde_json::Error),
+    #[error("Failed to write history records! Cause: {0}")]
+    Write(io::Error),
+    #[error("Invalid history query! {0}")]
+    InvalidQuery(String),
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_records_are_appended() {
+    let path: PathBuf = crate::create_unique_temp_file("history");
+
+    let history: History = History::open(&path).unwrap();
+
+    let recorder: Recorder = history.recorder("osmosis", "nolus1oracle");
+
+    let prices: [Price<CoinWithDecimalPlaces>; 1] = [Price::new(
+        CoinWithDecimalPlaces::new(1, "OSMO".into(), 6),
+        CoinWithDecimalPlaces::new(u128::MAX, "USDC".into(), 6),
+    )];
+
+    recorder.record(Event::fetched(&prices));
+    recorder.record(Event::Committed {
+        tx_hash: String::from("HASH"),
+    });
+
+    drop((history, recorder));
+
+    let records: Vec<Record> =
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            loop {
+                let records: Vec<Record> =
+                    query::read(&path, &query::Filter::default())
+                        .await
+                        .unwrap();
+
+                if records.len() == 2 {
+                    break records;
+                }
+
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .unwrap();
+
+    tokio::fs::remove_file(&path).await.unwrap();
+
+    assert_eq!(&*records[0].provider_id, "osmosis");
+    assert_eq!(&*records[0].oracle, "nolus1oracle");
+    assert_eq!(
+        records[0].event,
+        Event::Fetched {
+            prices: vec![PriceEntry::from(&prices[0])],
+        }
+    );
+    assert_eq!(records[1].event.kind(), "committed");
+}
@@ -0,0 +1,403 @@
+use std::{
+    borrow::Cow,
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+};
+
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt as _, BufReader, Lines},
+};
+
+use super::{Error, Record};
+
+/// Criteria which records have to match. Unset criteria match any record.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct Filter {
+    pub provider_id: Option<String>,
+    pub oracle: Option<String>,
+    pub event: Option<String>,
+    pub tx_hash: Option<String>,
+    /// Inclusive lower bound, in milliseconds since the Unix epoch.
+    pub since_ms: Option<u64>,
+    /// Exclusive upper bound, in milliseconds since the Unix epoch.
+    pub until_ms: Option<u64>,
+}
+
+impl Filter {
+    pub fn matches(&self, record: &Record) -> bool {
+        self.provider_id
+            .as_deref()
+            .map_or(true, |provider_id: &str| *record.provider_id == *provider_id)
+            && self
+                .oracle
+                .as_deref()
+                .map_or(true, |oracle: &str| *record.oracle == *oracle)
+            && self
+                .event
+                .as_deref()
+                .map_or(true, |event: &str| record.event.kind() == event)
+            && self.tx_hash.as_deref().map_or(true, |tx_hash: &str| {
+                matches!(
+                    &record.event,
+                    super::Event::Fed { tx_hash: Some(record_tx_hash), .. }
+                        | super::Event::Committed { tx_hash: record_tx_hash }
+                        | super::Event::Delivered { tx_hash: record_tx_hash, .. }
+                        if record_tx_hash == tx_hash
+                )
+            })
+            && self
+                .since_ms
+                .map_or(true, |since_ms: u64| since_ms <= record.timestamp_ms)
+            && self
+                .until_ms
+                .map_or(true, |until_ms: u64| record.timestamp_ms < until_ms)
+    }
+}
+
+/// Output format of the `history` subcommand.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    /// Matching records, one JSON object per line.
+    #[default]
+    Json,
+    /// Prices of the matching `fetched` and `fed` records, one per row.
+    Csv,
+}
+
+impl Format {
+    fn parse(value: &str) -> Result<Self, Error> {
+        match value {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(Error::InvalidQuery(format!(
+                "Option \"--format\" expects either \"json\" or \"csv\"! Got: {value}"
+            ))),
+        }
+    }
+}
+
+/// Reads the records of the history file which match the filter, in the order
+/// they were recorded.
+pub(crate) async fn read(
+    path: &Path,
+    filter: &Filter,
+) -> Result<Vec<Record>, Error> {
+    let mut lines: Lines<BufReader<File>> = File::open(path)
+        .await
+        .map(|file: File| BufReader::new(file).lines())
+        .map_err(|error: io::Error| Error::Open(path.into(), error))?;
+
+    let mut records: Vec<Record> = Vec::new();
+
+    let mut line_number: usize = 0;
+
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|error: io::Error| Error::Read(path.into(), error))?
+    {
+        line_number += 1;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record: Record = serde_json::from_str(&line)
+            .map_err(|error| Error::Parse(line_number, error))?;
+
+        if filter.matches(&record) {
+            records.push(record);
+        }
+    }
+
+    Ok(records)
+}
+
+/// Usage of the `history` subcommand.
+pub(crate) const USAGE: &str = "\
+Usage: market-data-feeder history <FILE> [OPTIONS]
+
+Prints the records of the history file which match all of the given options,
+one JSON object per line, or the prices they contain as CSV.
+
+Options:
+    --provider <ID>        Records of the given provider or aggregate.
+    --oracle <ADDRESS>     Records of the given oracle.
+    --event <KIND>         Records of the given kind: fetched, compared, fed,
+                           committed, commit_failed or delivered.
+    --tx-hash <HASH>       Records of the given transaction.
+    --since <UNIX MS>      Records made at or after the given time.
+    --until <UNIX MS>      Records made before the given time.
+    --format <FORMAT>      Output format: json (default), or csv to print
+                           one row per price of fetched and fed records.";
+
+/// Parses the arguments following the `history` subcommand.
+pub(crate) fn parse_args<I>(args: I) -> Result<(PathBuf, Filter, Format), Error>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+
+    let mut path: Option<PathBuf> = None;
+
+    let mut filter: Filter = Filter::default();
+
+    let mut format: Format = Format::default();
+
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            if path.replace(arg.into()).is_some() {
+                return Err(Error::InvalidQuery(String::from(
+                    "Only one history file can be queried at a time!",
+                )));
+            }
+
+            continue;
+        }
+
+        let value: String = args.next().ok_or_else(|| {
+            Error::InvalidQuery(format!("Option \"{arg}\" requires a value!"))
+        })?;
+
+        match arg.as_str() {
+            "--provider" => filter.provider_id = Some(value),
+            "--oracle" => filter.oracle = Some(value),
+            "--event" => filter.event = Some(value),
+            "--tx-hash" => filter.tx_hash = Some(value),
+            "--since" => filter.since_ms = Some(parse_timestamp(&arg, &value)?),
+            "--until" => filter.until_ms = Some(parse_timestamp(&arg, &value)?),
+            "--format" => format = Format::parse(&value)?,
+            _ => {
+                return Err(Error::InvalidQuery(format!(
+                    "Unknown option \"{arg}\"!\n\n{USAGE}"
+                )));
+            },
+        }
+    }
+
+    path.map(|path: PathBuf| (path, filter, format))
+        .ok_or_else(|| {
+            Error::InvalidQuery(format!("No history file given!\n\n{USAGE}"))
+        })
+}
+
+fn parse_timestamp(option: &str, value: &str) -> Result<u64, Error> {
+    value.parse().map_err(|_| {
+        Error::InvalidQuery(format!(
+            "Option \"{option}\" expects milliseconds since the Unix epoch! Got: {value}"
+        ))
+    })
+}
+
+/// Runs the `history` subcommand, printing the matching records to the
+/// standard output.
+pub(crate) async fn run(
+    path: &Path,
+    filter: &Filter,
+    format: Format,
+) -> Result<(), Error> {
+    let records: Vec<Record> = read(path, filter).await?;
+
+    let mut stdout: io::StdoutLock<'_> = io::stdout().lock();
+
+    match format {
+        Format::Json => {
+            for record in records {
+                serde_json::to_writer(&mut stdout, &record)
+                    .map_err(io::Error::from)
+                    .and_then(|()| stdout.write_all(b"\n"))
+                    .map_err(Error::Write)?;
+            }
+        },
+        Format::Csv => {
+            write_csv(&mut stdout, &records).map_err(Error::Write)?;
+        },
+    }
+
+    stdout.flush().map_err(Error::Write)
+}
+
+const CSV_HEADER: &str =
+    "timestamp_ms,provider_id,oracle,event,base,quote,price\n";
+
+/// Writes the prices of the `fetched` and `fed` records, one per row, with
+/// the price rendered exactly. Other records are skipped.
+fn write_csv<W>(writer: &mut W, records: &[Record]) -> io::Result<()>
+where
+    W: io::Write,
+{
+    writer.write_all(CSV_HEADER.as_bytes())?;
+
+    for record in records {
+        let (super::Event::Fetched { prices }
+        | super::Event::Fed { prices, .. }) = &record.event
+        else {
+            continue;
+        };
+
+        for price in prices {
+            writeln!(
+                writer,
+                "{timestamp_ms},{provider_id},{oracle},{event},{base},{quote},{price}",
+                timestamp_ms = record.timestamp_ms,
+                provider_id = csv_field(&record.provider_id),
+                oracle = csv_field(&record.oracle),
+                event = record.event.kind(),
+                base = csv_field(&price.base.ticker),
+                quote = csv_field(&price.quote.ticker),
+                price = price.to_decimal_string().unwrap_or_default(),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Quotes the field when it contains characters which are special to CSV.
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_args() {
+    let (path, filter, format): (PathBuf, Filter, Format) = parse_args(
+        [
+            "history.jsonl",
+            "--provider",
+            "osmosis",
+            "--event",
+            "fed",
+            "--since",
+            "1700000000000",
+            "--format",
+            "csv",
+        ]
+        .map(String::from),
+    )
+    .unwrap();
+
+    assert_eq!(path, PathBuf::from("history.jsonl"));
+    assert_eq!(format, Format::Csv);
+    assert_eq!(
+        filter,
+        Filter {
+            provider_id: Some(String::from("osmosis")),
+            event: Some(String::from("fed")),
+            since_ms: Some(1_700_000_000_000),
+            ..Filter::default()
+        }
+    );
+
+    assert!(parse_args(["--provider", "osmosis"].map(String::from)).is_err());
+    assert!(parse_args(
+        ["history.jsonl", "--since", "yesterday"].map(String::from)
+    )
+    .is_err());
+    assert!(
+        parse_args(["history.jsonl", "--format", "xml"].map(String::from))
+            .is_err()
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_filter_matches() {
+    let record: Record = Record {
+        timestamp_ms: 1_000,
+        provider_id: "osmosis".into(),
+        oracle: "nolus1oracle".into(),
+        event: super::Event::Delivered {
+            tx_hash: String::from("HASH"),
+            outcome: None,
+        },
+    };
+
+    assert!(Filter::default().matches(&record));
+    assert!(Filter {
+        tx_hash: Some(String::from("HASH")),
+        since_ms: Some(1_000),
+        until_ms: Some(1_001),
+        ..Filter::default()
+    }
+    .matches(&record));
+    assert!(!Filter {
+        event: Some(String::from("fed")),
+        ..Filter::default()
+    }
+    .matches(&record));
+    assert!(!Filter {
+        until_ms: Some(1_000),
+        ..Filter::default()
+    }
+    .matches(&record));
+
+    let fed = |tx_hash: Option<&str>| Record {
+        event: super::Event::Fed {
+            tx_hash: tx_hash.map(String::from),
+            prices: Vec::new(),
+        },
+        ..record.clone()
+    };
+
+    let filter: Filter = Filter {
+        tx_hash: Some(String::from("HASH")),
+        ..Filter::default()
+    };
+
+    assert!(filter.matches(&fed(Some("HASH"))));
+    assert!(!filter.matches(&fed(Some("OTHER"))));
+    assert!(!filter.matches(&fed(None)));
+}
+
+#[cfg(test)]
+#[test]
+fn test_write_csv() {
+    use crate::price::{CoinWithDecimalPlaces, Price};
+
+    let record = |event: super::Event| Record {
+        timestamp_ms: 1_000,
+        provider_id: "osmosis, main".into(),
+        oracle: "nolus1oracle".into(),
+        event,
+    };
+
+    let prices: [Price<CoinWithDecimalPlaces>; 2] = [
+        Price::new(
+            CoinWithDecimalPlaces::new(10_u128.pow(18), "WETH".into(), 18),
+            CoinWithDecimalPlaces::new(3_456_780_000, "USDC".into(), 6),
+        ),
+        Price::new(
+            CoinWithDecimalPlaces::new(3, "OSMO".into(), 6),
+            CoinWithDecimalPlaces::new(2, "USDC".into(), 6),
+        ),
+    ];
+
+    let mut csv: Vec<u8> = Vec::new();
+
+    write_csv(
+        &mut csv,
+        &[
+            record(super::Event::fed(&prices, None)),
+            record(super::Event::Committed {
+                tx_hash: String::from("HASH"),
+            }),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        format!(
+            "{CSV_HEADER}\
+            1000,\"osmosis, main\",nolus1oracle,fed,WETH,USDC,3456.78\n\
+            1000,\"osmosis, main\",nolus1oracle,fed,OSMO,USDC,0.67\n"
+        )
+    );
+}
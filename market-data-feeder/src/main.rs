@@ -5,7 +5,13 @@
     clippy::significant_drop_tightening
 )]
 
-use std::{io, sync::Arc};
+use std::{
+    future::Future,
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::Arc,
+};
 
 use semver::{
     BuildMetadata as SemVerBuildMetadata, Comparator as SemVerComparator,
@@ -22,23 +28,33 @@ use tracing_subscriber::fmt::writer::MakeWriterExt as _;
 
 use broadcast::broadcast;
 use chain_comms::{
+    cli::Exit,
     client::Client as NodeClient,
     interact::query,
     reexport::{
         cosmrs::proto::cosmwasm::wasm::v1::query_client::QueryClient as WasmQueryClient,
         tonic::transport::Channel as TonicChannel,
     },
-    rpc_setup::{prepare_rpc, RpcSetup},
+    rpc_setup::{error::Error as RpcSetupError, prepare_rpc, RpcSetup},
     signing_key::DEFAULT_COSMOS_HD_PATH,
 };
 
 use self::{
-    config::Config, messages::QueryMsg, result::Result, workers::SpawnContext,
+    cli::{Cli, Command},
+    config::{Config, History as HistoryConfig},
+    history::{History, PriceEntry},
+    messages::QueryMsg,
+    price::{CoinWithDecimalPlaces, Price},
+    result::Result,
+    workers::{CheckedProviders, SpawnContext},
 };
 
+mod cli;
 mod config;
 mod deviation;
+mod dry_run;
 mod error;
+mod history;
 mod log;
 mod messages;
 mod price;
@@ -56,7 +72,64 @@ const COMPATIBLE_VERSION: SemVerComparator = SemVerComparator {
 };
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
+    let result: Result<()> = match cli::parse(std::env::args().skip(1)) {
+        Ok(Cli {
+            config_path,
+            command,
+        }) => run_command(&config_path, command).await,
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(()) => Exit::Success.into(),
+        Err(error) => {
+            eprintln!("{error}");
+
+            error.exit().into()
+        },
+    }
+}
+
+#[allow(clippy::future_not_send)]
+async fn run_command(config_path: &Path, command: Command) -> Result<()> {
+    match command {
+        Command::Run => with_logging(app_main(config_path)).await,
+        Command::DryRun(options) => {
+            with_logging(dry_run::run(config_path, options)).await
+        },
+        Command::CheckConfig => check_config(config_path).await,
+        Command::Prices { provider_id } => {
+            print_prices(config_path, &provider_id).await
+        },
+        Command::History(path, filter, format) => {
+            history::query::run(&path, &filter, format)
+                .await
+                .map_err(From::from)
+        },
+        Command::Version => {
+            println!(
+                "market-data-feeder {version}, built on {built_on}, compatible with oracle {COMPATIBLE_VERSION}",
+                version = env!("CARGO_PKG_VERSION"),
+                built_on = env!("BUILD_START_TIME_DATE", "No build time provided!"),
+            );
+
+            Ok(())
+        },
+        Command::Help(usage) => {
+            println!("{usage}");
+
+            Ok(())
+        },
+    }
+}
+
+/// Runs the service with its logs written to the standard output and to the
+/// log files.
+async fn with_logging<F>(service: F) -> Result<()>
+where
+    F: Future<Output = Result<()>>,
+{
     let (log_writer, log_guard): (NonBlocking, non_blocking::WorkerGuard) =
         NonBlocking::new(rolling::hourly("./logs/", "feeder"));
 
@@ -67,7 +140,7 @@ async fn main() -> Result<()> {
         env!("BUILD_START_TIME_DATE", "No build time provided!")
     ));
 
-    let result: Result<()> = app_main().await;
+    let result: Result<()> = service.await;
 
     if let Err(error) = &result {
         error!(error = ?error, "{}", error);
@@ -78,37 +151,106 @@ async fn main() -> Result<()> {
     result
 }
 
+async fn read_config(config_path: &Path) -> Result<Config> {
+    chain_comms::config::read(config_path).await.map_err(
+        |error: chain_comms::config::error::Error| {
+            RpcSetupError::from(error).into()
+        },
+    )
+}
+
+async fn check_config(config_path: &Path) -> Result<()> {
+    let config: Config = read_config(config_path).await?;
+
+    println!(
+        "Configuration file \"{path}\" is valid. Providers: {providers}; aggregates: {aggregates}; comparison providers: {comparison_providers}.",
+        path = config_path.display(),
+        providers = config.providers.len(),
+        aggregates = config.aggregates.len(),
+        comparison_providers = config.comparison_providers.len(),
+    );
+
+    Ok(())
+}
+
+/// Prints the prices of the given provider or aggregate, one JSON object per
+/// line, in the same format as the history file.
+async fn print_prices(config_path: &Path, provider_id: &str) -> Result<()> {
+    let config: Config = read_config(config_path).await?;
+
+    let node_client: NodeClient = NodeClient::from_config(config.as_ref())
+        .await
+        .map_err(RpcSetupError::from)?;
+
+    let prices: Box<[Price<CoinWithDecimalPlaces>]> = workers::fetch_prices(
+        &node_client,
+        config.providers,
+        config.aggregates,
+        provider_id,
+    )
+    .await?;
+
+    let mut stdout: io::StdoutLock<'_> = io::stdout().lock();
+
+    for price in prices.iter() {
+        serde_json::to_writer(&mut stdout, &PriceEntry::from(price))
+            .map_err(io::Error::from)
+            .and_then(|()| stdout.write_all(b"\n"))
+            .map_err(error::Application::WritePrices)?;
+    }
+
+    stdout.flush().map_err(error::Application::WritePrices)
+}
+
 #[allow(clippy::future_not_send)]
-async fn app_main() -> Result<()> {
+async fn app_main(config_path: &Path) -> Result<()> {
     let RpcSetup {
         signer,
         config,
         node_client,
         ..
     }: RpcSetup<Config> =
-        prepare_rpc("market-data-feeder.toml", DEFAULT_COSMOS_HD_PATH).await?;
+        prepare_rpc(config_path, DEFAULT_COSMOS_HD_PATH).await?;
 
     check_compatibility(&config, &mut node_client.wasm_query_client()).await?;
 
+    let providers: CheckedProviders = workers::check_providers(
+        &node_client,
+        config.providers,
+        config.aggregates,
+        config.comparison_providers,
+        config.strict_coverage,
+    )
+    .await?;
+
+    let history: Option<History> = config
+        .history
+        .as_ref()
+        .map(|history: &HistoryConfig| History::open(&history.path))
+        .transpose()?;
+
     let spawn_generators_f = {
         let node_client: NodeClient = node_client.clone();
 
         let signer_address: Arc<str> = Arc::from(signer.signer_address());
 
+        let config_path: PathBuf = config_path.to_path_buf();
+
         move |tx_request_sender| {
             info!("Starting workers...");
 
             block_in_place(move || {
                 workers::spawn(SpawnContext {
                     node_client: node_client.clone(),
-                    providers: config.providers,
-                    price_comparison_providers: config.comparison_providers,
+                    providers,
                     tx_request_sender,
                     signer_address,
                     hard_gas_limit: config.hard_gas_limit,
                     time_before_feeding: config.time_before_feeding,
                     tick_time: config.broadcast.tick_time,
                     poll_time: config.broadcast.poll_time,
+                    history,
+                    reload_config_path: Some(config_path),
                 })
             })
             .map(|spawn_result| {
@@ -179,3 +321,31 @@ async fn check_compatibility(
 
     Ok(())
 }
+
+/// Creates an empty file in the temporary directory, whose name is unique
+/// among the tests, as well as among concurrently running test processes.
+#[cfg(test)]
+fn create_unique_temp_file(prefix: &str) -> std::path::PathBuf {
+    use std::{
+        fs::OpenOptions,
+        io::ErrorKind,
+        path::PathBuf,
+        sync::atomic::{AtomicU64, Ordering},
+    };
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    loop {
+        let path: PathBuf = std::env::temp_dir().join(format!(
+            "market-data-feeder-{prefix}-{pid}-{counter}",
+            pid = std::process::id(),
+            counter = COUNTER.fetch_add(1, Ordering::Relaxed),
+        ));
+
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => break path,
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => {},
+            Err(error) => panic!("{error}"),
+        }
+    }
+}
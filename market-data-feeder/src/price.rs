@@ -1,12 +1,19 @@
-use std::str::FromStr;
+use std::{borrow::Cow, fmt, str::FromStr};
 
 use serde::{
-    de::Error as DeserializeError, Deserialize, Deserializer, Serialize,
+    de::{Error as DeserializeError, Visitor},
+    Deserialize, Deserializer, Serialize,
 };
+use serde_json::value::RawValue;
 use thiserror::Error;
 
+use bnum::BUint;
+
 use crate::config::{Ticker, TickerUnsized};
 
+/// Capable of storing integers with precision of 320 bits.
+pub(crate) type UInt = BUint<5>;
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub(crate) struct Ratio {
     numerator: u128,
@@ -14,6 +21,64 @@ pub(crate) struct Ratio {
 }
 
 impl Ratio {
+    /// Constructs a ratio out of big integers, reducing it by their greatest
+    /// common divisor. Ratios which still don't fit in 128 bits are scaled
+    /// down, both of their parts by the same power of two, with rounding.
+    ///
+    /// Ratios which would be scaled down to zero, or to a zero denominator,
+    /// are rejected instead.
+    pub fn from_big_uints(
+        numerator: UInt,
+        denominator: UInt,
+    ) -> Result<Self, Error> {
+        if denominator == UInt::ZERO {
+            return Err(Error::ZeroDenominator);
+        }
+
+        let gcd: UInt = gcd(numerator, denominator);
+
+        let (numerator, denominator): (UInt, UInt) =
+            (numerator / gcd, denominator / gcd);
+
+        let mut shift: u32 = numerator
+            .bits()
+            .max(denominator.bits())
+            .saturating_sub(u128::BITS);
+
+        loop {
+            if let (Ok(scaled_numerator), Ok(scaled_denominator)) = (
+                u128::try_from(shift_right_rounded(numerator, shift)),
+                u128::try_from(shift_right_rounded(denominator, shift)),
+            ) {
+                break if scaled_denominator == 0 {
+                    Err(Error::NumeratorTooBig)
+                } else if scaled_numerator == 0 && numerator != UInt::ZERO {
+                    Err(Error::DenominatorTooBig)
+                } else {
+                    Ok(Self {
+                        numerator: scaled_numerator,
+                        denominator: scaled_denominator,
+                    })
+                };
+            }
+
+            // Rounding up may need one more bit.
+            shift += 1;
+        }
+    }
+
+    /// Parses a price as found in a JSON document, keeping all of the digits
+    /// of numbers. Strings are unescaped before being parsed.
+    pub fn from_json(value: &RawValue) -> Result<Self, Error> {
+        if value.get().starts_with('"') {
+            serde_json::from_str::<Cow<'_, str>>(value.get())
+                .map_err(Error::Json)
+                .and_then(|value: Cow<'_, str>| Self::from_str(&value))
+        } else {
+            Self::from_str(value.get())
+        }
+    }
+
     pub const fn to_price(
         self,
         base: Ticker,
@@ -48,69 +113,117 @@ impl Ratio {
 }
 
 impl<'de> Deserialize<'de> for Ratio {
+    /// Accepts both strings and numbers. Numbers which are parsed as floating
+    /// point ones by the deserializer are only as precise as their shortest
+    /// representation, so sources which send prices with many significant
+    /// digits should be read through [`Ratio::from_json`] instead.
     #[inline]
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        <&str>::deserialize(deserializer).and_then(|price: &str| {
-            Self::from_str(price).map_err(DeserializeError::custom)
-        })
+        deserializer.deserialize_any(RatioVisitor)
+    }
+}
+
+struct RatioVisitor;
+
+impl Visitor<'_> for RatioVisitor {
+    type Value = Ratio;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a non-negative decimal number or a string of one")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: DeserializeError,
+    {
+        Ratio::from_str(value).map_err(E::custom)
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: DeserializeError,
+    {
+        self.visit_u128(value.into())
+    }
+
+    fn visit_u128<E>(self, value: u128) -> Result<Self::Value, E>
+    where
+        E: DeserializeError,
+    {
+        Ratio::from_big_uints(value.into(), UInt::ONE).map_err(E::custom)
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+    where
+        E: DeserializeError,
+    {
+        self.visit_str(&value.to_string())
     }
 }
 
 impl FromStr for Ratio {
     type Err = Error;
 
+    /// Parses a decimal number with all of its digits, reducing it by the
+    /// greatest common divisor of its numerator and denominator.
+    ///
+    /// Both forms returned by price sources are accepted, plain (`0.00001234`)
+    /// and scientific (`1.234e-05`), optionally with a leading `+`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s
-            .trim_start_matches('0')
-            .trim_end_matches('0')
-            .trim_end_matches('.');
-
-        let point: usize = s.find('.').unwrap_or(s.len());
-
-        match s.len() - point {
-            0 => s
-                .parse()
-                .map(|numerator| Self {
-                    numerator,
-                    denominator: 1,
-                })
-                .map_err(Error::ParseNumerator),
-            exponent @ 1.. => (exponent - 1)
-                .try_into()
-                .map_err(Error::from)
-                .and_then(|exp: u32| {
-                    10_u128.checked_pow(exp).ok_or(Error::ExponentTooBig)
-                })
-                .and_then(|denominator: u128| {
-                    let result = s[point + 1..].trim_start_matches('0').parse();
-
-                    if point == 0 {
-                        result.map(Some)
-                    } else {
-                        result.and_then(|after_decimal: u128| {
-                            s[..point].parse().map(|before_decimal: u128| {
-                                before_decimal
-                                    .checked_mul(denominator)
-                                    .and_then(|before_decimal| {
-                                        before_decimal
-                                            .checked_add(after_decimal)
-                                    })
-                            })
-                        })
-                    }
-                    .map_err(Error::ParseNumerator)
-                    .and_then(|maybe_numerator| {
-                        maybe_numerator.ok_or(Error::NumeratorTooBig)
-                    })
-                    .map(|numerator: u128| Self {
-                        numerator,
-                        denominator,
-                    })
-                }),
+        let s: &str = s.trim();
+
+        let s: &str = s.strip_prefix('+').unwrap_or(s);
+
+        let (mantissa, exponent): (&str, i64) = match s.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => {
+                (mantissa, exponent.parse().map_err(Error::ParseExponent)?)
+            },
+            None => (s, 0),
+        };
+
+        let (integer, fraction): (&str, &str) =
+            mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+        if !integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|digit: u8| digit.is_ascii_digit())
+        {
+            return Err(Error::InvalidNumber(s.into()));
         }
+
+        let fraction: &str = fraction.trim_end_matches('0');
+
+        let mut numerator: UInt =
+            UInt::from_str_radix(&format!("{integer}{fraction}"), 10)?;
+
+        let mut denominator: UInt = UInt::ONE;
+
+        let exponent: i64 = i64::try_from(fraction.len())
+            .ok()
+            .and_then(|fraction_digits: i64| {
+                exponent.checked_sub(fraction_digits)
+            })
+            .ok_or(Error::ExponentTooBig)?;
+
+        let scale: UInt = exponent
+            .unsigned_abs()
+            .try_into()
+            .ok()
+            .and_then(|exponent: u32| UInt::TEN.checked_pow(exponent))
+            .ok_or(Error::ExponentTooBig)?;
+
+        if exponent < 0 {
+            denominator = scale;
+        } else if numerator != UInt::ZERO {
+            numerator =
+                numerator.checked_mul(scale).ok_or(Error::NumeratorTooBig)?;
+        }
+
+        Self::from_big_uints(numerator, denominator)
     }
 }
 
@@ -123,16 +236,51 @@ impl<'r> TryFrom<&'r str> for Ratio {
     }
 }
 
+/// Divides the value by `2^shift`, rounding half up.
+fn shift_right_rounded(value: UInt, shift: u32) -> UInt {
+    if shift == 0 {
+        value
+    } else {
+        let half: UInt = value >> (shift - 1);
+
+        (half >> 1) + (half & UInt::ONE)
+    }
+}
+
+/// Greatest common divisor, which is the other number when either of them is
+/// zero.
+pub(crate) fn gcd(mut a: UInt, mut b: UInt) -> UInt {
+    while b != UInt::ZERO {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Failed to parse numerator! Cause: {0}")]
-    ParseNumerator(#[from] std::num::ParseIntError),
-    #[error("Failed to convert denominator exponent! Cause: {0}")]
-    ConvertInt(#[from] std::num::TryFromIntError),
+    ParseNumerator(bnum::errors::ParseIntError),
+    #[error("Failed to parse ratio! Invalid number: \"{0}\"")]
+    InvalidNumber(String),
+    #[error("Failed to parse exponent! Cause: {0}")]
+    ParseExponent(std::num::ParseIntError),
+    #[error("Failed to parse JSON string! Cause: {0}")]
+    Json(serde_json::Error),
     #[error("Failed to parse ratio! Denominator exponent too big!")]
     ExponentTooBig,
     #[error("Failed to parse ratio! Numerator too big!")]
     NumeratorTooBig,
+    #[error("Failed to parse ratio! Denominator too big!")]
+    DenominatorTooBig,
+    #[error("Failed to construct ratio! Denominator is equal to zero!")]
+    ZeroDenominator,
+}
+
+impl From<bnum::errors::ParseIntError> for Error {
+    fn from(error: bnum::errors::ParseIntError) -> Self {
+        Self::ParseNumerator(error)
+    }
 }
 
 pub trait Coin: Send + 'static {
@@ -256,6 +404,119 @@ where
     }
 }
 
+impl Price<CoinWithDecimalPlaces> {
+    /// Renders the amount of quote paid for one whole unit of base.
+    ///
+    /// The value is computed with integers only. It is rendered exactly when
+    /// its decimal expansion terminates. Otherwise it is rounded half up to
+    /// twice as many fractional digits as its reduced denominator has, which
+    /// tells it apart from any other value with no greater denominator.
+    /// Returns `None` when the base amount is zero or the value doesn't fit in
+    /// [`UInt`].
+    #[must_use]
+    pub fn to_decimal_string(&self) -> Option<String> {
+        let power_of_ten =
+            |exponent: u8| UInt::TEN.checked_pow(exponent.into());
+
+        // quote / 10^quote_dp      quote * 10^base_dp
+        // --------------------- = --------------------
+        // base / 10^base_dp        base * 10^quote_dp
+        let numerator: UInt = UInt::from(self.amount_quote.amount())
+            .checked_mul(power_of_ten(self.amount.decimal_places())?)?;
+
+        let denominator: UInt = UInt::from(self.amount.amount())
+            .checked_mul(power_of_ten(self.amount_quote.decimal_places())?)?;
+
+        if denominator == UInt::ZERO {
+            return None;
+        }
+
+        let gcd: UInt = gcd(numerator, denominator);
+
+        let (numerator, denominator): (UInt, UInt) =
+            (numerator / gcd, denominator / gcd);
+
+        let max_fraction_digits: usize = fraction_digits(denominator)
+            .unwrap_or_else(|| 2 * denominator.to_string().len());
+
+        let mut integer: UInt = numerator / denominator;
+
+        let mut remainder: UInt = numerator % denominator;
+
+        let mut fraction: Vec<u8> = Vec::new();
+
+        while remainder != UInt::ZERO && fraction.len() < max_fraction_digits {
+            remainder = remainder.checked_mul(UInt::TEN)?;
+
+            fraction.push(
+                u8::try_from(remainder / denominator)
+                    .unwrap_or_else(|_| unreachable!()),
+            );
+
+            remainder %= denominator;
+        }
+
+        if remainder.checked_mul(UInt::TWO)? >= denominator {
+            let carried: bool =
+                fraction.iter_mut().rev().all(|digit: &mut u8| {
+                    *digit = (*digit + 1) % 10;
+
+                    *digit == 0
+                });
+
+            if carried {
+                integer = integer.checked_add(UInt::ONE)?;
+            }
+        }
+
+        while fraction.last() == Some(&0) {
+            fraction.pop();
+        }
+
+        let mut digits: String = integer.to_string();
+
+        if !fraction.is_empty() {
+            digits.push('.');
+
+            digits.extend(
+                fraction
+                    .into_iter()
+                    .map(|digit: u8| char::from(b'0' + digit)),
+            );
+        }
+
+        Some(digits)
+    }
+}
+
+/// Base and quote tickers of a price.
+pub(crate) type Pair = (Ticker, Ticker);
+
+pub(crate) fn pair_of(price: &Price<CoinWithDecimalPlaces>) -> Pair {
+    (
+        price.amount().ticker().to_string(),
+        price.amount_quote().ticker().to_string(),
+    )
+}
+
+/// Number of fractional digits of the decimal expansion of any numerator over
+/// the given denominator, when it terminates, i.e. when the denominator has no
+/// prime factors other than two and five.
+fn fraction_digits(mut denominator: UInt) -> Option<usize> {
+    let mut digits: [usize; 2] = [0; 2];
+
+    for (factor, digits) in [UInt::TWO, UInt::FIVE].into_iter().zip(&mut digits)
+    {
+        while denominator % factor == UInt::ZERO {
+            denominator /= factor;
+
+            *digits += 1;
+        }
+    }
+
+    (denominator == UInt::ONE).then(|| digits[0].max(digits[1]))
+}
+
 #[cfg(test)]
 #[test]
 fn test_ratio_less_than_one() {
@@ -264,8 +525,8 @@ fn test_ratio_less_than_one() {
     assert_eq!(
         Ratio::from_str(INPUT).unwrap(),
         Ratio {
-            numerator: 1234,
-            denominator: 10000,
+            numerator: 617,
+            denominator: 5000,
         }
     );
 }
@@ -278,8 +539,8 @@ fn test_ratio_greater_than_one() {
     assert_eq!(
         Ratio::from_str(INPUT).unwrap(),
         Ratio {
-            numerator: 1234,
-            denominator: 1000,
+            numerator: 617,
+            denominator: 500,
         }
     );
 }
@@ -292,8 +553,8 @@ fn test_ratio_greater_than_ten() {
     assert_eq!(
         Ratio::from_str(INPUT).unwrap(),
         Ratio {
-            numerator: 1234,
-            denominator: 100,
+            numerator: 617,
+            denominator: 50,
         }
     );
 }
@@ -306,8 +567,8 @@ fn test_ratio_greater_than_hundred() {
     assert_eq!(
         Ratio::from_str(INPUT).unwrap(),
         Ratio {
-            numerator: 1234,
-            denominator: 10,
+            numerator: 617,
+            denominator: 5,
         }
     );
 }
@@ -339,3 +600,207 @@ fn test_ratio_integer() {
         }
     );
 }
+
+#[cfg(test)]
+#[test]
+fn test_ratio_more_than_38_digits() {
+    const INPUT: &str = "123456789012345678901234567890.1234567890";
+
+    assert_eq!(
+        Ratio::from_str(INPUT).unwrap(),
+        Ratio {
+            numerator: 123_456_789_012_345_678_901_234_567_890_123_456_789,
+            denominator: 1_000_000_000,
+        }
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_ratio_from_big_uints() {
+    let big: UInt = UInt::from(u128::MAX) * UInt::from_digit(6);
+
+    assert_eq!(
+        Ratio::from_big_uints(big, UInt::from_digit(12)).unwrap(),
+        Ratio {
+            numerator: u128::MAX,
+            denominator: 2,
+        }
+    );
+
+    // Scaled down by `2^2`, after which the numerator, rounded up, takes the
+    // whole 128 bits.
+    assert_eq!(
+        Ratio::from_big_uints(
+            UInt::from(u128::MAX) * UInt::from_digit(4),
+            UInt::from_digit(7)
+        )
+        .unwrap(),
+        Ratio {
+            numerator: u128::MAX,
+            denominator: 2,
+        }
+    );
+
+    // Rounding the numerator up to `2^128` takes one more bit.
+    assert_eq!(
+        Ratio::from_big_uints(
+            (UInt::ONE << 129) - UInt::ONE,
+            UInt::from_digit(3)
+        )
+        .unwrap(),
+        Ratio {
+            numerator: 1 << 127,
+            denominator: 1,
+        }
+    );
+
+    assert!(matches!(
+        Ratio::from_big_uints(big * big, UInt::from_digit(7)),
+        Err(Error::NumeratorTooBig)
+    ));
+
+    assert!(matches!(
+        Ratio::from_big_uints(UInt::ONE, big * big),
+        Err(Error::DenominatorTooBig)
+    ));
+
+    assert!(matches!(
+        Ratio::from_big_uints(UInt::ONE, UInt::ZERO),
+        Err(Error::ZeroDenominator)
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_ratio_tiny_value() {
+    const INPUT: &str = "0.000000000000000000000000000000000123";
+
+    assert_eq!(
+        Ratio::from_str(INPUT).unwrap(),
+        Ratio {
+            numerator: 123,
+            denominator: 10_u128.pow(36),
+        }
+    );
+
+    assert!(matches!(
+        Ratio::from_str(&format!("0.{}1", "0".repeat(60))),
+        Err(Error::DenominatorTooBig)
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_ratio_exotic_formats() {
+    for (input, numerator, denominator) in [
+        ("1.234e-05", 617, 50_000_000),
+        ("1.234E-5", 617, 50_000_000),
+        ("+1.234e-05", 617, 50_000_000),
+        ("2.5e+1", 25, 1),
+        ("2.5e1", 25, 1),
+        ("0e10", 0, 1),
+        (".5", 1, 2),
+        ("5.", 5, 1),
+        (" 12.50 ", 25, 2),
+    ] {
+        assert_eq!(
+            Ratio::from_str(input).unwrap(),
+            Ratio {
+                numerator,
+                denominator,
+            },
+            "{input}",
+        );
+    }
+
+    for input in [
+        "", ".", "-1", "1e", "1.2.3", "1e-1.5", "++1", "1e++1", "0x10",
+    ] {
+        assert!(Ratio::from_str(input).is_err(), "{input}");
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_ratio_from_json() {
+    let ratio = |json: &str| {
+        Ratio::from_json(&serde_json::from_str::<Box<RawValue>>(json).unwrap())
+    };
+
+    assert_eq!(
+        ratio("1.234e-05").unwrap(),
+        ratio(r#""1.234e-05""#).unwrap()
+    );
+    assert_eq!(ratio("0.1").unwrap(), ratio(r#""0.1""#).unwrap());
+    assert_eq!(
+        ratio("0.1").unwrap(),
+        serde_json::from_str::<Ratio>("0.1").unwrap()
+    );
+    assert_eq!(
+        ratio("1.234e-05").unwrap(),
+        serde_json::from_str::<Ratio>(r#""1.234e-05""#).unwrap()
+    );
+    assert_eq!(
+        ratio("42").unwrap(),
+        serde_json::from_str::<Ratio>("42").unwrap()
+    );
+    assert!(ratio("null").is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_price_to_decimal_string() {
+    let price = |amount: u128,
+                 decimal_places: u8,
+                 amount_quote: u128,
+                 quote_decimal_places: u8| {
+        Price::new(
+            CoinWithDecimalPlaces::new(amount, "BASE".into(), decimal_places),
+            CoinWithDecimalPlaces::new(
+                amount_quote,
+                "QUOTE".into(),
+                quote_decimal_places,
+            ),
+        )
+    };
+
+    assert_eq!(
+        price(10_u128.pow(18), 18, 3_456_780_000, 6).to_decimal_string(),
+        Some(String::from("3456.78"))
+    );
+    assert_eq!(
+        price(1, 18, u128::MAX, 6).to_decimal_string(),
+        Some((UInt::from(u128::MAX) * UInt::from(10_u128.pow(12))).to_string())
+    );
+    assert_eq!(
+        price(3, 6, 2, 6).to_decimal_string(),
+        Some(String::from("0.67"))
+    );
+    assert_eq!(
+        price(3, 6, 200, 6).to_decimal_string(),
+        Some(String::from("66.67"))
+    );
+    assert_eq!(
+        price(7_000, 6, 1, 6).to_decimal_string(),
+        Some(String::from("0.00014286"))
+    );
+    assert_eq!(
+        price(3, 0, 2_999_999, 6).to_decimal_string(),
+        Some(String::from("0.99999966666667"))
+    );
+    assert_eq!(
+        price(1, 6, 1_234_567, 18).to_decimal_string(),
+        Some(String::from("0.000001234567"))
+    );
+    assert_eq!(
+        price(1, 0, 1, 40).to_decimal_string(),
+        Some(format!("0.{}1", "0".repeat(39)))
+    );
+    assert_eq!(
+        price(2, 0, 5, 0).to_decimal_string(),
+        Some(String::from("2.5"))
+    );
+    assert_eq!(price(0, 6, 1, 6).to_decimal_string(), None);
+    assert_eq!(price(1, 200, 1, 6).to_decimal_string(), None);
+}
@@ -0,0 +1,306 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroUsize,
+};
+
+use thiserror::Error as ThisError;
+use tracing::{debug, info, warn};
+
+use crate::{
+    config::Ticker,
+    price::{pair_of, CoinWithDecimalPlaces, Pair, Price},
+};
+
+/// Pairs requested by the oracle, along with the ones which aren't priced and
+/// the reasons why.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct Coverage {
+    requested: BTreeSet<Pair>,
+    dropped: BTreeMap<Pair, DropReason>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ThisError)]
+pub(crate) enum DropReason {
+    #[error("Currency \"{0}\" is not configured!")]
+    UnconfiguredCurrency(Ticker),
+    #[error(
+        "Covered by {covering} sources, while at least {min} are required!"
+    )]
+    NotEnoughSources { covering: usize, min: NonZeroUsize },
+    #[error("No price was returned for it!")]
+    NotPriced,
+    /// Fetching failed, which is not necessarily caused by the configuration.
+    #[error("Failed to fetch price! Cause: {0}")]
+    FetchFailed(String),
+}
+
+impl DropReason {
+    pub const fn is_fetch_failure(&self) -> bool {
+        matches!(self, Self::FetchFailed(_))
+    }
+}
+
+impl Coverage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn request(&mut self, base: &str, quote: &str) {
+        self.requested.insert((base.into(), quote.into()));
+    }
+
+    /// Marks a requested pair as one which won't be priced.
+    pub fn drop_pair(&mut self, base: &str, quote: &str, reason: DropReason) {
+        self.request(base, quote);
+
+        self.dropped.insert((base.into(), quote.into()), reason);
+    }
+
+    /// Merges the coverages of an aggregate's sources. A pair is covered when
+    /// at least `min_sources` of the sources cover it.
+    ///
+    /// Pairs which would be covered, if it weren't for sources failing to
+    /// fetch their prices, are dropped as failed to be fetched. Sources which
+    /// failed as a whole are given as `failed_sources`.
+    pub fn merge<I>(
+        coverages: I,
+        failed_sources: usize,
+        min_sources: NonZeroUsize,
+    ) -> Self
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        let coverages: Vec<Self> = coverages.into_iter().collect();
+
+        let mut merged: Self = Self::new();
+
+        for pair in coverages
+            .iter()
+            .flat_map(|coverage: &Self| coverage.requested.iter())
+        {
+            if merged.requested.contains(pair) {
+                continue;
+            }
+
+            let covering: usize = coverages
+                .iter()
+                .filter(|coverage: &&Self| coverage.covers(pair))
+                .count();
+
+            let fetch_failures: Vec<&DropReason> = coverages
+                .iter()
+                .filter_map(|coverage: &Self| coverage.dropped.get(pair))
+                .filter(|reason: &&DropReason| reason.is_fetch_failure())
+                .collect();
+
+            if covering + fetch_failures.len() + failed_sources
+                < min_sources.get()
+            {
+                merged.dropped.insert(
+                    pair.clone(),
+                    DropReason::NotEnoughSources {
+                        covering,
+                        min: min_sources,
+                    },
+                );
+            } else if covering < min_sources.get() {
+                merged.dropped.insert(
+                    pair.clone(),
+                    fetch_failures.first().map_or_else(
+                        || {
+                            DropReason::FetchFailed(format!(
+                                "{failed_sources} of the sources failed!"
+                            ))
+                        },
+                        |&reason: &&DropReason| reason.clone(),
+                    ),
+                );
+            }
+
+            merged.requested.insert(pair.clone());
+        }
+
+        merged
+    }
+
+    /// Accounts for the prices returned on a tick. Requested pairs which are
+    /// priced, e.g. ones derived as cross rates, are no longer dropped, while
+    /// the ones which aren't are dropped as not priced.
+    pub fn with_prices(
+        mut self,
+        prices: &[Price<CoinWithDecimalPlaces>],
+    ) -> Self {
+        let priced_pairs: BTreeSet<Pair> = prices.iter().map(pair_of).collect();
+
+        for pair in &self.requested {
+            if priced_pairs.contains(pair) {
+                self.dropped.remove(pair);
+            } else {
+                self.dropped
+                    .entry(pair.clone())
+                    .or_insert(DropReason::NotPriced);
+            }
+        }
+
+        self
+    }
+
+    pub fn requested(&self) -> impl Iterator<Item = &Pair> {
+        self.requested.iter()
+    }
+
+    pub fn dropped(&self) -> impl Iterator<Item = (&Pair, &DropReason)> {
+        self.dropped.iter()
+    }
+
+    /// Logs which of the requested pairs are priced and which are dropped,
+    /// along with the reasons why.
+    pub fn report(&self, provider_id: &str) {
+        let priced: Vec<String> = self
+            .requested
+            .iter()
+            .filter(|pair: &&Pair| !self.dropped.contains_key(*pair))
+            .map(|(base, quote): &Pair| format!("{base}/{quote}"))
+            .collect();
+
+        debug!(
+            %provider_id,
+            requested = ?self.requested,
+            priced = ?priced,
+            "Coverage of oracle's pairs."
+        );
+
+        info!(
+            %provider_id,
+            requested = self.requested.len(),
+            priced = priced.len(),
+            dropped = self.dropped.len(),
+            "Coverage of oracle's pairs."
+        );
+
+        for ((base, quote), reason) in &self.dropped {
+            warn!(
+                %provider_id,
+                "Pair {base}/{quote} requested by oracle is dropped! Reason: {reason}"
+            );
+        }
+    }
+
+    fn covers(&self, pair: &Pair) -> bool {
+        self.requested.contains(pair) && !self.dropped.contains_key(pair)
+    }
+}
+
+#[cfg(test)]
+fn test_price(base: &str, quote: &str) -> Price<CoinWithDecimalPlaces> {
+    Price::new(
+        CoinWithDecimalPlaces::new(1, base.into(), 6),
+        CoinWithDecimalPlaces::new(1, quote.into(), 6),
+    )
+}
+
+#[cfg(test)]
+#[test]
+fn test_with_prices() {
+    let mut coverage: Coverage = Coverage::new();
+
+    coverage.request("OSMO", "USDC");
+    coverage.request("ATOM", "USDC");
+    coverage.drop_pair(
+        "NTRN",
+        "USDC",
+        DropReason::UnconfiguredCurrency("NTRN".into()),
+    );
+    coverage.drop_pair(
+        "ATOM",
+        "OSMO",
+        DropReason::UnconfiguredCurrency("ATOM".into()),
+    );
+
+    let coverage: Coverage = coverage.with_prices(&[
+        test_price("OSMO", "USDC"),
+        // Derived as a cross rate.
+        test_price("ATOM", "OSMO"),
+    ]);
+
+    assert_eq!(
+        coverage.dropped().collect::<Vec<_>>(),
+        [
+            (&("ATOM".into(), "USDC".into()), &DropReason::NotPriced),
+            (
+                &("NTRN".into(), "USDC".into()),
+                &DropReason::UnconfiguredCurrency("NTRN".into())
+            ),
+        ]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_merge() {
+    let mut first: Coverage = Coverage::new();
+
+    first.request("OSMO", "USDC");
+    first.drop_pair(
+        "ATOM",
+        "USDC",
+        DropReason::UnconfiguredCurrency("ATOM".into()),
+    );
+
+    let mut second: Coverage = Coverage::new();
+
+    second.request("OSMO", "USDC");
+    second.request("ATOM", "USDC");
+
+    let min_sources: NonZeroUsize = NonZeroUsize::new(2).unwrap();
+
+    let merged: Coverage =
+        Coverage::merge([first.clone(), second.clone()], 0, min_sources);
+
+    assert_eq!(merged.requested.len(), 2);
+
+    assert_eq!(
+        merged.dropped().collect::<Vec<_>>(),
+        [(
+            &("ATOM".into(), "USDC".into()),
+            &DropReason::NotEnoughSources {
+                covering: 1,
+                min: min_sources,
+            }
+        )]
+    );
+
+    let mut failed: Coverage = Coverage::new();
+
+    failed.request("OSMO", "USDC");
+    failed.drop_pair(
+        "ATOM",
+        "USDC",
+        DropReason::FetchFailed("Node is unreachable!".into()),
+    );
+
+    assert_eq!(
+        Coverage::merge(
+            [first.clone(), second.clone(), failed],
+            0,
+            min_sources
+        )
+        .dropped()
+        .collect::<Vec<_>>(),
+        [(
+            &("ATOM".into(), "USDC".into()),
+            &DropReason::FetchFailed("Node is unreachable!".into())
+        )]
+    );
+
+    assert_eq!(
+        Coverage::merge([first, second], 1, min_sources)
+            .dropped()
+            .collect::<Vec<_>>(),
+        [(
+            &("ATOM".into(), "USDC".into()),
+            &DropReason::FetchFailed("1 of the sources failed!".into())
+        )]
+    );
+}
@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use thiserror::Error as ThisError;
+use tracing::warn;
+
+use crate::config::{SymbolAndDecimalPlaces, SymbolUnsized, Ticker};
+
+/// Mismatch between a configured currency and the bank module of the chain
+/// its prices are fetched from.
+#[derive(Debug, Clone, PartialEq, Eq, ThisError)]
+pub(crate) enum DenomMismatch {
+    #[error("Currency \"{ticker}\" is configured with denomination \"{denom}\", which doesn't exist on chain!")]
+    Missing {
+        ticker: Ticker,
+        denom: Arc<SymbolUnsized>,
+    },
+    #[error("Currency \"{ticker}\" is configured with {configured} decimal places, while denomination \"{denom}\" has {on_chain} according to its metadata!")]
+    DecimalPlaces {
+        ticker: Ticker,
+        denom: Arc<SymbolUnsized>,
+        configured: u8,
+        on_chain: u32,
+    },
+}
+
+/// Result of looking a denomination up in a chain's bank module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DenomLookup {
+    /// Metadata is registered, along with the exponent of its display unit,
+    /// when the display unit is listed.
+    Metadata { display_exponent: Option<u32> },
+    /// No metadata is registered, but the denomination has supply.
+    NoMetadata,
+    /// Neither metadata is registered, nor the denomination has supply.
+    Missing,
+}
+
+/// Exponent of the unit the metadata designates for display.
+///
+/// When no display unit is designated, the largest exponent is used instead,
+/// as by convention it's the one of the display unit.
+pub(crate) fn display_exponent<'r, I>(display: &str, units: I) -> Option<u32>
+where
+    I: IntoIterator<Item = (&'r str, u32)>,
+{
+    let mut units = units.into_iter();
+
+    if display.is_empty() {
+        units.map(|(_, exponent): (&str, u32)| exponent).max()
+    } else {
+        units
+            .find(|&(denom, _): &(&str, u32)| denom == display)
+            .map(|(_, exponent): (&str, u32)| exponent)
+    }
+}
+
+/// Checks a configured currency against the result of looking its
+/// denomination up.
+///
+/// Denominations without metadata can't have their decimal places checked,
+/// which is only warned about, as not all chains register metadata for all
+/// of their denominations.
+pub(crate) fn check(
+    ticker: &str,
+    currency: &SymbolAndDecimalPlaces,
+    lookup: DenomLookup,
+) -> Option<DenomMismatch> {
+    match lookup {
+        DenomLookup::Metadata {
+            display_exponent: Some(on_chain),
+        } if on_chain != u32::from(currency.decimal_places()) => {
+            Some(DenomMismatch::DecimalPlaces {
+                ticker: ticker.into(),
+                denom: currency.denom().clone(),
+                configured: currency.decimal_places(),
+                on_chain,
+            })
+        },
+        DenomLookup::Metadata {
+            display_exponent: Some(_),
+        } => None,
+        DenomLookup::Metadata {
+            display_exponent: None,
+        }
+        | DenomLookup::NoMetadata => {
+            warn!(
+                %ticker,
+                denom = %currency.denom(),
+                "Denomination has no usable metadata on chain! Its decimal places can't be checked."
+            );
+
+            None
+        },
+        DenomLookup::Missing => Some(DenomMismatch::Missing {
+            ticker: ticker.into(),
+            denom: currency.denom().clone(),
+        }),
+    }
+}
+
+/// Whether a supply amount, as returned by the bank module, is zero.
+pub(crate) fn is_zero_amount(amount: &str) -> bool {
+    amount.trim_start_matches('0').is_empty()
+}
+
+#[cfg(test)]
+#[test]
+fn test_check() {
+    let currency: SymbolAndDecimalPlaces = toml::from_str(
+        r#"
+            denom = "ibc/D189335C6E4A68B513C10AB227BF1C1D38C746766278BA3EEB4FB14124F1D858"
+            decimal_places = 6
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        display_exponent("usdc", [("uusdc", 0), ("usdc", 6)]),
+        Some(6)
+    );
+
+    assert_eq!(display_exponent("", [("uosmo", 0), ("osmo", 6)]), Some(6));
+
+    assert_eq!(display_exponent("atom", [("uatom", 0)]), None);
+
+    assert_eq!(
+        check(
+            "USDC",
+            &currency,
+            DenomLookup::Metadata {
+                display_exponent: Some(6)
+            }
+        ),
+        None
+    );
+
+    assert_eq!(
+        check(
+            "USDC",
+            &currency,
+            DenomLookup::Metadata {
+                display_exponent: Some(18)
+            }
+        ),
+        Some(DenomMismatch::DecimalPlaces {
+            ticker: "USDC".into(),
+            denom: currency.denom().clone(),
+            configured: 6,
+            on_chain: 18,
+        })
+    );
+
+    assert_eq!(check("USDC", &currency, DenomLookup::NoMetadata), None);
+
+    assert_eq!(
+        check("USDC", &currency, DenomLookup::Missing),
+        Some(DenomMismatch::Missing {
+            ticker: "USDC".into(),
+            denom: currency.denom().clone(),
+        })
+    );
+
+    assert!(is_zero_amount("0"));
+    assert!(is_zero_amount("000"));
+    assert!(!is_zero_amount("1000"));
+}
@@ -19,8 +19,20 @@ pub enum Provider {
     #[error(r#"Failed to query WASM contract!{}{}{} Cause: {}"#, if _0.is_empty() { "" } else { " Additional context: " }, _0, if _0.is_empty() { "" } else { ";" }, _1)]
     WasmQuery(String, chain_comms::interact::query::error::Wasm),
 
+    #[error("Failed to query bank module!{}{}{} Cause: {}", if _0.is_empty() { "" } else { " Additional context: " }, _0, if _0.is_empty() { "" } else { ";" }, _1)]
+    BankQuery(String, chain_comms::interact::query::error::Bank),
+
     #[error("Serialization failed! Cause: {0}")]
     Serialization(#[from] serde_json_wasm::ser::Error),
+
+    #[error("Failed to query HTTP endpoint!{}{}{} Cause: {}", if _0.is_empty() { "" } else { " Additional context: " }, _0, if _0.is_empty() { "" } else { ";" }, _1)]
+    HttpQuery(String, reqwest::Error),
+
+    #[error("Failed to deserialize response!{}{}{} Cause: {}", if _0.is_empty() { "" } else { " Additional context: " }, _0, if _0.is_empty() { "" } else { ";" }, _1)]
+    DeserializeResponse(String, serde_json_wasm::de::Error),
+
+    #[error("Failure due to a provider-specific error!{}{}{} Cause: {}", if _0.is_empty() { "" } else { " Additional context: " }, _0, if _0.is_empty() { "" } else { ";" }, _1)]
+    Specific(String, Box<dyn StdError + Send + Sync + 'static>),
 }
 
 impl From<chain_comms::interact::query::error::Wasm> for Provider {
@@ -39,8 +51,51 @@ pub(crate) enum PriceComparisonGuard {
         "Price comparison guard failed due to a missing comparison price! Missing pair: {0}/{1}"
     )]
     MissingComparisonPrice(String, String),
-    #[error("Price deviation too big for \"{0}/{1}\" pair! Deviation equal to {2} percent!")]
-    DeviationTooBig(String, String, crate::deviation::UInt),
+    #[error("Price deviation too big for \"{0}/{1}\" pair! Deviation equal to {2} basis points, while the limit is {3} basis points!")]
+    DeviationTooBig(
+        String,
+        String,
+        crate::price::UInt,
+        crate::deviation::BasisPoints,
+    ),
+    #[error("Price comparison guard failed because the comparison provider supports none of the prices!")]
+    EmptyPricesIntersection,
     #[error("Failure due to an provider-specific error! Cause: {0}")]
     ComparisonProviderSpecific(Box<dyn StdError + Send + 'static>),
+    #[error("Price comparison guard failed due to not reaching quorum! Passed: {0}; Required: {1}")]
+    QuorumNotReached(usize, std::num::NonZeroUsize),
+    #[error(
+        "Price comparison guard failed for multiple pairs! Causes: {}",
+        display_all(_0)
+    )]
+    PairsFailed(Box<[Self]>),
+}
+
+impl PriceComparisonGuard {
+    /// Whether the pair is among the ones which failed the comparison.
+    /// Errors which aren't specific to any pair fail all of them.
+    pub fn fails_pair(&self, base: &str, quote: &str) -> bool {
+        match self {
+            Self::DuplicatePrice(..)
+            | Self::FetchPrices(_)
+            | Self::EmptyPricesIntersection
+            | Self::ComparisonProviderSpecific(_)
+            | Self::QuorumNotReached(..) => true,
+            Self::MissingComparisonPrice(failed_base, failed_quote)
+            | Self::DeviationTooBig(failed_base, failed_quote, ..) => {
+                failed_base == base && failed_quote == quote
+            },
+            Self::PairsFailed(errors) => errors
+                .iter()
+                .any(|error: &Self| error.fails_pair(base, quote)),
+        }
+    }
+}
+
+fn display_all(errors: &[PriceComparisonGuard]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(" ")
 }
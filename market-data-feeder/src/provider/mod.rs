@@ -9,14 +9,19 @@ use chain_comms::client::Client as NodeClient;
 
 use crate::{
     config::{ProviderConfig, ProviderConfigExt},
+    deviation::DeviationLimits,
     price::{CoinWithDecimalPlaces, Price},
 };
 
+pub(crate) use self::coverage::Coverage;
+pub(crate) use self::denoms::DenomMismatch;
 pub(crate) use self::error::{
     PriceComparisonGuard as PriceComparisonGuardError,
     Provider as ProviderError,
 };
 
+pub(crate) mod coverage;
+pub(crate) mod denoms;
 mod error;
 
 #[async_trait]
@@ -27,6 +32,63 @@ pub(crate) trait Provider: Sync + Send + 'static {
         &self,
         fault_tolerant: bool,
     ) -> Result<Box<[Price<CoinWithDecimalPlaces>]>, ProviderError>;
+
+    /// Fetches the prices along with the coverage of the pairs requested by
+    /// the oracle, for providers which price the oracle's pairs.
+    async fn get_prices_with_coverage(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<
+        (Box<[Price<CoinWithDecimalPlaces>]>, Option<Coverage>),
+        ProviderError,
+    > {
+        self.get_prices(fault_tolerant)
+            .await
+            .map(|prices: Box<[Price<CoinWithDecimalPlaces>]>| (prices, None))
+    }
+
+    /// Checks the configured currencies against the bank module of the chain
+    /// the prices are fetched from, returning all of the mismatches found.
+    ///
+    /// Providers which don't fetch prices from a chain have nothing to check.
+    async fn check_denoms(
+        &self,
+    ) -> Result<Box<[DenomMismatch]>, ProviderError> {
+        Ok(Box::default())
+    }
+}
+
+#[async_trait]
+impl<P> Provider for Box<P>
+where
+    P: Provider + ?Sized,
+{
+    fn instance_id(&self) -> &str {
+        (**self).instance_id()
+    }
+
+    async fn get_prices(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<Box<[Price<CoinWithDecimalPlaces>]>, ProviderError> {
+        (**self).get_prices(fault_tolerant).await
+    }
+
+    async fn get_prices_with_coverage(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<
+        (Box<[Price<CoinWithDecimalPlaces>]>, Option<Coverage>),
+        ProviderError,
+    > {
+        (**self).get_prices_with_coverage(fault_tolerant).await
+    }
+
+    async fn check_denoms(
+        &self,
+    ) -> Result<Box<[DenomMismatch]>, ProviderError> {
+        (**self).check_denoms().await
+    }
 }
 
 #[async_trait]
@@ -35,8 +97,15 @@ pub(crate) trait ComparisonProvider: Sync + Send + 'static {
         &self,
         benchmarked_provider_id: &str,
         prices: &[Price<CoinWithDecimalPlaces>],
-        max_deviation_exclusive: u64,
+        deviation_limits: &DeviationLimits,
     ) -> Result<(), PriceComparisonGuardError>;
+
+    /// See [`Provider::check_denoms`].
+    async fn check_denoms(
+        &self,
+    ) -> Result<Box<[DenomMismatch]>, ProviderError> {
+        Ok(Box::default())
+    }
 }
 
 #[async_trait]
@@ -48,7 +117,7 @@ where
         &self,
         benchmarked_provider_id: &str,
         prices: &[Price<CoinWithDecimalPlaces>],
-        max_deviation_exclusive: u64,
+        deviation_limits: &DeviationLimits,
     ) -> Result<(), PriceComparisonGuardError> {
         self.get_prices(false)
             .map(|result: Result<Box<[Price<CoinWithDecimalPlaces>]>, ProviderError>| {
@@ -58,7 +127,7 @@ where
                 let result: Result<(), PriceComparisonGuardError> = block_in_place(|| crate::deviation::compare_prices(
                     prices,
                     &comparison_prices,
-                    max_deviation_exclusive,
+                    deviation_limits,
                 ));
 
                 match &result {
@@ -70,6 +139,12 @@ where
             })
             .await
     }
+
+    async fn check_denoms(
+        &self,
+    ) -> Result<Box<[DenomMismatch]>, ProviderError> {
+        Provider::check_denoms(self).await
+    }
 }
 
 #[async_trait]
@@ -145,10 +220,7 @@ impl<Config: ProviderConfigExt<true>> ProviderConfig
 impl<Config: ProviderConfigExt<true>> ProviderConfigExt<false>
     for ProviderConfigWrapper<Config>
 {
-    fn fetch_from_env(
-        id: &str,
-        name: &str,
-    ) -> Result<String, crate::config::EnvError> {
-        Config::fetch_from_env(id, name)
+    fn env_name(id: &str, name: &str) -> String {
+        Config::env_name(id, name)
     }
 }
@@ -23,49 +23,66 @@ use chain_comms::{
 
 use crate::{
     config::{
-        Currencies, EnvError, ProviderConfigExt, SymbolAndDecimalPlaces,
-        SymbolUnsized,
+        AssetKind, Currencies, FieldError, ProviderConfigExt,
+        SymbolAndDecimalPlaces, Ticker,
     },
     messages::{
         QueryMsg as OracleQueryMsg, SupportedCurrencyPairsResponse, SwapLeg,
     },
     price::{CoinWithDecimalPlaces, Price},
-    provider::{FromConfig, Provider, ProviderError},
+    provider::{
+        coverage::DropReason, Coverage, DenomMismatch, FromConfig, Provider,
+        ProviderError,
+    },
 };
 
+/// Intermediate currencies' tickers through which swaps have to be routed,
+/// indexed by the base currency's ticker and then by the quote one's.
+type Routes = BTreeMap<Ticker, BTreeMap<Ticker, Vec<Ticker>>>;
+
 pub(super) struct Astroport {
     instance_id: String,
     node_wasm_query_client: WasmQueryClient<TonicChannel>,
     oracle_addr: Arc<str>,
     wasm_query_client: WasmQueryClient<TonicChannel>,
+    channel: TonicChannel,
     router_contract: Arc<str>,
     currencies: Currencies,
+    routes: Routes,
 }
 
 impl Astroport {
     fn query_message(
-        base: &SymbolUnsized,
-        quote: &SymbolUnsized,
+        assets: &[AssetInfo],
         decimal_places: u8,
     ) -> Result<Vec<u8>, ProviderError> {
         serde_json_wasm::to_vec(&QueryMsg::SimulateSwapOperations {
             offer_amount: 10_u128.pow(decimal_places.into()).into(),
-            operations: Vec::from([SwapOperation::AstroSwap {
-                offer_asset_info: AssetInfo::NativeToken { denom: base.into() },
-                ask_asset_info: AssetInfo::NativeToken {
-                    denom: quote.into(),
-                },
-            }]),
+            operations: assets
+                .windows(2)
+                .map(|pair: &[AssetInfo]| SwapOperation::AstroSwap {
+                    offer_asset_info: pair[0].clone(),
+                    ask_asset_info: pair[1].clone(),
+                })
+                .collect(),
         })
         .map_err(Into::into)
     }
 
+    fn asset_info(currency: &SymbolAndDecimalPlaces) -> AssetInfo {
+        match currency.kind() {
+            AssetKind::Native => AssetInfo::NativeToken {
+                denom: currency.denom().to_string(),
+            },
+            AssetKind::Cw20 => {
+                AssetInfo::cw20_unchecked(currency.denom().as_ref())
+            },
+        }
+    }
+
     async fn supported_currencies_intersection(
         &self,
-    ) -> Result<
-        impl Iterator<Item = (String, Arc<str>, u8, String, Arc<str>, u8)> + '_,
-        ProviderError,
-    > {
+    ) -> Result<(Vec<SwapPath>, Coverage), ProviderError> {
         query::wasm_smart::<SupportedCurrencyPairsResponse>(
             &mut self.node_wasm_query_client.clone(),
             self.oracle_addr.to_string(),
@@ -73,29 +90,99 @@ impl Astroport {
         )
         .await
         .map(|supported_currencies: SupportedCurrencyPairsResponse| {
-            supported_currencies
+            let mut coverage: Coverage = Coverage::new();
+
+            let swap_paths: Vec<SwapPath> = supported_currencies
                 .into_iter()
                 .filter_map(|swap_leg: SwapLeg| {
-                    self.currencies.get(&swap_leg.from).and_then(
-                        |base: &SymbolAndDecimalPlaces| {
-                            self.currencies.get(&swap_leg.to.target).map(
-                                |quote: &SymbolAndDecimalPlaces| {
-                                    (
-                                        swap_leg.from,
-                                        base.denom().clone(),
-                                        base.decimal_places(),
-                                        swap_leg.to.target,
-                                        quote.denom().clone(),
-                                        quote.decimal_places(),
-                                    )
-                                },
-                            )
+                    match self.swap_path(&swap_leg) {
+                        Ok(swap_path) => {
+                            coverage
+                                .request(&swap_leg.from, &swap_leg.to.target);
+
+                            Some(swap_path)
                         },
-                    )
+                        Err(reason) => {
+                            coverage.drop_pair(
+                                &swap_leg.from,
+                                &swap_leg.to.target,
+                                reason,
+                            );
+
+                            None
+                        },
+                    }
                 })
+                .collect();
+
+            (swap_paths, coverage)
         })
         .map_err(From::from)
     }
+
+    fn swap_path(&self, swap_leg: &SwapLeg) -> Result<SwapPath, DropReason> {
+        let currency = |ticker: &Ticker| {
+            self.currencies
+                .get(ticker)
+                .ok_or_else(|| DropReason::UnconfiguredCurrency(ticker.clone()))
+        };
+
+        let base: &SymbolAndDecimalPlaces = currency(&swap_leg.from)?;
+
+        let quote: &SymbolAndDecimalPlaces = currency(&swap_leg.to.target)?;
+
+        let intermediate: &[Ticker] = self
+            .routes
+            .get(&swap_leg.from)
+            .and_then(|routes: &BTreeMap<Ticker, Vec<Ticker>>| {
+                routes.get(&swap_leg.to.target)
+            })
+            .map_or(&[], Vec::as_slice);
+
+        let mut assets: Vec<AssetInfo> =
+            Vec::with_capacity(intermediate.len() + 2);
+
+        assets.push(Self::asset_info(base));
+
+        for ticker in intermediate {
+            assets.push(Self::asset_info(currency(ticker)?));
+        }
+
+        assets.push(Self::asset_info(quote));
+
+        Ok(SwapPath {
+            base_ticker: swap_leg.from.clone(),
+            base_decimal_places: base.decimal_places(),
+            quote_ticker: swap_leg.to.target.clone(),
+            quote_decimal_places: quote.decimal_places(),
+            assets,
+        })
+    }
+
+    fn validate_routes(
+        routes: &Routes,
+        currencies: &Currencies,
+    ) -> Result<(), ConstructError> {
+        routes
+            .values()
+            .flat_map(BTreeMap::values)
+            .flatten()
+            .find(|ticker: &&Ticker| !currencies.contains_key(*ticker))
+            .map_or(Ok(()), |ticker: &Ticker| {
+                Err(ConstructError::UnknownRouteCurrency(ticker.clone()))
+            })
+    }
+}
+
+/// Swap from the base currency to the quote one, going through zero or more
+/// intermediate currencies. Contains the assets of all currencies in the order
+/// in which the swaps are performed.
+struct SwapPath {
+    base_ticker: Ticker,
+    base_decimal_places: u8,
+    quote_ticker: Ticker,
+    quote_decimal_places: u8,
+    assets: Vec<AssetInfo>,
 }
 
 #[async_trait]
@@ -108,21 +195,30 @@ impl Provider for Astroport {
         &self,
         fault_tolerant: bool,
     ) -> Result<Box<[Price<CoinWithDecimalPlaces>]>, ProviderError> {
-        let mut set: JoinSet<
-            Result<Price<CoinWithDecimalPlaces>, ProviderError>,
-        > = JoinSet::new();
+        self.get_prices_with_coverage(fault_tolerant)
+            .await
+            .map(|(prices, _): (_, Option<Coverage>)| prices)
+    }
+
+    async fn get_prices_with_coverage(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<
+        (Box<[Price<CoinWithDecimalPlaces>]>, Option<Coverage>),
+        ProviderError,
+    > {
+        let mut set: super::PairTaskSet = JoinSet::new();
 
-        let supported_currencies_iter =
+        let (swap_paths, coverage): (Vec<SwapPath>, Coverage) =
             self.supported_currencies_intersection().await?;
 
-        for (
+        for SwapPath {
             base_ticker,
-            base_dex_denom,
             base_decimal_places,
             quote_ticker,
-            quote_dex_denom,
             quote_decimal_places,
-        ) in supported_currencies_iter
+            assets,
+        } in swap_paths
         {
             let mut wasm_query_client: WasmQueryClient<TonicChannel> =
                 self.wasm_query_client.clone();
@@ -132,46 +228,60 @@ impl Provider for Astroport {
             let max_decimal_places: u8 =
                 base_decimal_places.max(quote_decimal_places);
 
-            set.spawn(async move {
-                let query_message =
-                    Self::query_message(&base_dex_denom, &quote_dex_denom, max_decimal_places)?;
-
-                debug!(query_message = %String::from_utf8_lossy(&query_message), "Query message");
-
-                let query_result: Result<
-                    astroport::router::SimulateSwapOperationsResponse,
-                    query::error::Wasm,
-                > = query::wasm_smart(
-                    &mut wasm_query_client,
-                    router_contract.to_string(),
-                    query_message,
-                )
-                .await;
-
-                match query_result {
-                    Ok(astroport::router::SimulateSwapOperationsResponse {
-                        amount: quote_amount,
-                    }) => Ok(Price::new(
-                        CoinWithDecimalPlaces::new(
-                            10_u128.pow(max_decimal_places.into()),
-                            base_ticker,
-                            base_decimal_places,
-                        ),
-                        CoinWithDecimalPlaces::new(
-                            quote_amount.u128(),
-                            quote_ticker,
-                            quote_decimal_places,
-                        ),
-                    )),
-                    Err(error) => Err(ProviderError::WasmQuery(
-                        format!(r#"currency pair = "{base_ticker}/{quote_ticker}""#),
-                        error,
-                    )),
-                }
-            });
+            super::spawn_pair_task(
+                &mut set,
+                (base_ticker.clone(), quote_ticker.clone()),
+                async move {
+                    let query_message =
+                        Self::query_message(&assets, max_decimal_places)?;
+
+                    debug!(query_message = %String::from_utf8_lossy(&query_message), "Query message");
+
+                    let query_result: Result<
+                        astroport::router::SimulateSwapOperationsResponse,
+                        query::error::Wasm,
+                    > = query::wasm_smart(
+                        &mut wasm_query_client,
+                        router_contract.to_string(),
+                        query_message,
+                    )
+                    .await;
+
+                    match query_result {
+                        Ok(
+                            astroport::router::SimulateSwapOperationsResponse {
+                                amount: quote_amount,
+                            },
+                        ) => Ok(Price::new(
+                            CoinWithDecimalPlaces::new(
+                                10_u128.pow(max_decimal_places.into()),
+                                base_ticker,
+                                base_decimal_places,
+                            ),
+                            CoinWithDecimalPlaces::new(
+                                quote_amount.u128(),
+                                quote_ticker,
+                                quote_decimal_places,
+                            ),
+                        )),
+                        Err(error) => Err(ProviderError::WasmQuery(
+                            format!(
+                                r#"currency pair = "{base_ticker}/{quote_ticker}""#
+                            ),
+                            error,
+                        )),
+                    }
+                },
+            );
         }
 
-        super::collect_prices_from_task_set(set, fault_tolerant).await
+        super::collect_prices_with_coverage(set, coverage, fault_tolerant).await
+    }
+
+    async fn check_denoms(
+        &self,
+    ) -> Result<Box<[DenomMismatch]>, ProviderError> {
+        super::check_denoms(self.channel.clone(), &self.currencies).await
     }
 }
 
@@ -183,26 +293,28 @@ impl FromConfig<false> for Astroport {
 
     async fn from_config<Config>(
         id: &str,
-        config: Config,
+        mut config: Config,
         node_client: &NodeClient,
     ) -> Result<Self, Self::ConstructError>
     where
         Config: ProviderConfigExt<false>,
     {
-        const GRPC_URI_ENV_NAME: &str = "grpc_uri";
-        const ROUTER_CONTRACT_ENV_NAME: &str = "router_addr";
+        const GRPC_URI_FIELD: &str = "grpc_uri";
+        const ROUTER_CONTRACT_FIELD: &str = "router_addr";
         const CURRENCIES_FIELD: &str = "currencies";
+        const ROUTES_FIELD: &str = "routes";
 
-        let grpc_uri: Uri = Config::fetch_from_env(id, GRPC_URI_ENV_NAME)
+        let grpc_uri: Uri = config
+            .take_or_fetch_from_env(id, GRPC_URI_FIELD)
             .map_err(ConstructError::FetchGrpcUri)
             .and_then(|value: String| {
                 value.parse().map_err(ConstructError::InvalidGrpcUri)
             })?;
 
-        let router_contract: Arc<str> =
-            Config::fetch_from_env(id, ROUTER_CONTRACT_ENV_NAME)
-                .map_err(ConstructError::FetchRouterContract)
-                .map(Into::into)?;
+        let router_contract: Arc<str> = config
+            .take_or_fetch_from_env(id, ROUTER_CONTRACT_FIELD)
+            .map_err(ConstructError::FetchRouterContract)
+            .map(Into::into)?;
 
         let oracle_addr: Arc<str> = config.oracle_addr().clone();
 
@@ -216,19 +328,32 @@ impl FromConfig<false> for Astroport {
                 ConstructError::DeserializeField(CURRENCIES_FIELD, error)
             })?;
 
+        let routes: Routes = config.remove(ROUTES_FIELD).map_or_else(
+            || Ok(Routes::new()),
+            |value: toml::Value| {
+                value.try_into().map_err(|error: toml::de::Error| {
+                    ConstructError::DeserializeField(ROUTES_FIELD, error)
+                })
+            },
+        )?;
+
+        Self::validate_routes(&routes, &currencies)?;
+
         if let Some(fields) = super::left_over_fields(config) {
             Err(ConstructError::UnknownFields(fields))
         } else {
+            let channel: TonicChannel =
+                TonicChannel::builder(grpc_uri).connect().await?;
+
             Ok(Self {
                 instance_id: id.to_string(),
                 node_wasm_query_client: node_client.wasm_query_client(),
                 oracle_addr,
-                wasm_query_client: TonicChannel::builder(grpc_uri)
-                    .connect()
-                    .await
-                    .map(WasmQueryClient::new)?,
+                wasm_query_client: WasmQueryClient::new(channel.clone()),
+                channel,
                 router_contract,
                 currencies,
+                routes,
             })
         }
     }
@@ -242,14 +367,63 @@ pub(crate) enum ConstructError {
     DeserializeField(&'static str, toml::de::Error),
     #[error("Unknown fields found! Unknown fields: {0}")]
     UnknownFields(Box<str>),
-    #[error(
-        "Failed to fetch gRPC's URI from environment variables! Cause: {0}"
-    )]
-    FetchGrpcUri(EnvError),
+    #[error("Swap route goes through currency \"{0}\" which is not present in the currencies' list!")]
+    UnknownRouteCurrency(Ticker),
+    #[error("Failed to fetch gRPC's URI! Cause: {0}")]
+    FetchGrpcUri(FieldError),
     #[error("Failed to parse gRPC's URI! Cause: {0}")]
     InvalidGrpcUri(#[from] InvalidUri),
-    #[error("Failed to fetch router contract's address from environment variables! Cause: {0}")]
-    FetchRouterContract(EnvError),
+    #[error("Failed to fetch router contract's address! Cause: {0}")]
+    FetchRouterContract(FieldError),
     #[error("Failed to connect RPC's URI! Cause: {0}")]
     ConnectToGrpc(#[from] TonicError),
 }
+
+#[cfg(test)]
+#[test]
+fn test_multi_hop_query_message() {
+    let assets: [AssetInfo; 3] = [
+        AssetInfo::NativeToken {
+            denom: "ibc/DYDX".into(),
+        },
+        AssetInfo::cw20_unchecked("neutron1ntrn"),
+        AssetInfo::NativeToken {
+            denom: "ibc/USDC".into(),
+        },
+    ];
+
+    assert_eq!(
+        String::from_utf8(Astroport::query_message(&assets, 18).unwrap())
+            .unwrap(),
+        concat!(
+            r#"{"simulate_swap_operations":{"offer_amount":"1000000000000000000","operations":["#,
+            r#"{"astro_swap":{"offer_asset_info":{"native_token":{"denom":"ibc/DYDX"}},"ask_asset_info":{"token":{"contract_addr":"neutron1ntrn"}}}},"#,
+            r#"{"astro_swap":{"offer_asset_info":{"token":{"contract_addr":"neutron1ntrn"}},"ask_asset_info":{"native_token":{"denom":"ibc/USDC"}}}}"#,
+            "]}}",
+        ),
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_cw20_asset_info() {
+    let currencies: Currencies = toml::from_str(
+        r#"
+        ASTRO = { cw20_contract = "neutron1astro", decimal_places = 6 }
+        NTRN = { denom = "untrn", decimal_places = 6 }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        Astroport::asset_info(&currencies["ASTRO"]),
+        AssetInfo::cw20_unchecked("neutron1astro")
+    );
+
+    assert_eq!(
+        Astroport::asset_info(&currencies["NTRN"]),
+        AssetInfo::NativeToken {
+            denom: "untrn".into()
+        }
+    );
+}
@@ -1,28 +1,28 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
     convert::identity,
-    string::FromUtf8Error,
-    sync::{Arc, OnceLock},
+    sync::Arc,
 };
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::{FutureExt, TryFutureExt};
-use regex::{Captures, Regex, RegexBuilder};
 use reqwest::{
     header::{HeaderMap, HeaderValue, InvalidHeaderValue},
     Client as ReqwestClient, Error as ReqwestError,
-    Response as ReqwestResponse,
 };
+use serde::Deserialize;
+use serde_json::value::RawValue;
 use thiserror::Error;
-use tokio::task::{block_in_place, JoinSet};
+use tokio::task::JoinSet;
 use toml::Value;
+use url::Url;
 
-use chain_comms::client::Client as NodeClient;
+use chain_comms::{client::Client as NodeClient, config::StrOrValue};
 
 use crate::{
     config::{self, ProviderConfigExt, Ticker, TickerUnsized},
-    deviation,
+    deviation::DeviationLimits,
     price::{
         self, Coin, CoinWithDecimalPlaces, CoinWithoutDecimalPlaces, Price,
         Ratio,
@@ -30,14 +30,42 @@ use crate::{
     provider::{ComparisonProvider, FromConfig, PriceComparisonGuardError},
 };
 
+use super::retry;
+
 pub(crate) struct SanityCheck {
     mandatory: bool,
     http_client: Arc<ReqwestClient>,
+    retry_policy: retry::Policy,
+    api_base_url: Arc<str>,
     ticker_mapping: BTreeMap<Arc<TickerUnsized>, Arc<str>>,
     supported_vs_currencies: BTreeSet<Arc<str>>,
 }
 
 impl SanityCheck {
+    async fn new(
+        mandatory: bool,
+        http_client: Arc<ReqwestClient>,
+        retry_policy: retry::Policy,
+        api_base_url: Arc<str>,
+        ticker_mapping: BTreeMap<Arc<TickerUnsized>, Arc<str>>,
+    ) -> Result<Self, ConstructError> {
+        Self::fetch_supported_vs_currencies(
+            &http_client,
+            &retry_policy,
+            &api_base_url,
+            &ticker_mapping,
+        )
+        .await
+        .map(|supported_vs_currencies: BTreeSet<Arc<str>>| Self {
+            mandatory,
+            http_client,
+            retry_policy,
+            api_base_url,
+            ticker_mapping,
+            supported_vs_currencies,
+        })
+    }
+
     fn extract_mandatory_check_flag<Config>(
         config: &mut Config,
     ) -> Result<bool, ConstructError>
@@ -51,37 +79,73 @@ impl SanityCheck {
             .remove(MANDATORY_CHECK_FIELD)
             .ok_or(ConstructError::MissingField(MANDATORY_CHECK_FIELD))
             .and_then(|value: Value| {
+                value
+                    .try_into()
+                    .map(|StrOrValue(mandatory): StrOrValue<bool>| mandatory)
+                    .map_err(|error: toml::de::Error| {
+                        ConstructError::DeserializeField(
+                            MANDATORY_CHECK_FIELD,
+                            error,
+                        )
+                    })
+            })
+    }
+
+    fn extract_tier<Config>(config: &mut Config) -> Result<Tier, ConstructError>
+    where
+        Config: ProviderConfigExt<true>,
+    {
+        const TIER_FIELD: &str = "tier";
+
+        config.misc_mut().remove(TIER_FIELD).map_or_else(
+            || Ok(Tier::default()),
+            |value: Value| {
                 value.try_into().map_err(|error: toml::de::Error| {
-                    ConstructError::DeserializeField(
-                        MANDATORY_CHECK_FIELD,
-                        error,
-                    )
+                    ConstructError::DeserializeField(TIER_FIELD, error)
                 })
-            })
+            },
+        )
     }
 
-    fn construct_http_client<Config>(
-        id: &str,
-    ) -> Result<Arc<ReqwestClient>, ConstructError>
+    fn extract_api_base_url<Config>(
+        config: &mut Config,
+        tier: Tier,
+    ) -> Result<Arc<str>, ConstructError>
     where
         Config: ProviderConfigExt<true>,
     {
-        const API_KEY_FIELD: &str = "api_key";
-        const API_KEY_HEADER: &str = "x-cg-pro-api-key";
+        const API_BASE_URL_FIELD: &str = "api_base_url";
 
+        let api_base_url: String =
+            config.misc_mut().remove(API_BASE_URL_FIELD).map_or_else(
+                || Ok(tier.default_api_base_url().to_string()),
+                |value: Value| {
+                    value.try_into().map_err(|error: toml::de::Error| {
+                        ConstructError::DeserializeField(
+                            API_BASE_URL_FIELD,
+                            error,
+                        )
+                    })
+                },
+            )?;
+
+        Url::parse(&api_base_url)
+            .map(|_: Url| api_base_url.trim_end_matches('/').into())
+            .map_err(From::from)
+    }
+
+    fn construct_http_client(
+        tier: Tier,
+        api_key: &str,
+    ) -> Result<Arc<ReqwestClient>, ConstructError> {
         ReqwestClient::builder()
             .default_headers({
                 let mut headers: HeaderMap = HeaderMap::new();
 
                 headers.insert(
-                    API_KEY_HEADER,
-                    Config::fetch_from_env(id, API_KEY_FIELD)
-                        .map_err(ConstructError::EnvVariable)
-                        .and_then(|api_key: String| {
-                            HeaderValue::from_str(&api_key).map_err(
-                                ConstructError::ConstructApiKeyHeaderValue,
-                            )
-                        })?,
+                    tier.api_key_header(),
+                    HeaderValue::from_str(api_key)
+                        .map_err(ConstructError::ConstructApiKeyHeaderValue)?,
                 );
 
                 headers
@@ -125,19 +189,18 @@ impl SanityCheck {
 
     async fn fetch_supported_vs_currencies(
         http_client: &ReqwestClient,
+        retry_policy: &retry::Policy,
+        api_base_url: &str,
         ticker_mappings: &BTreeMap<Arc<TickerUnsized>, Arc<str>>,
     ) -> Result<BTreeSet<Arc<str>>, ConstructError> {
-        const SUPPORTED_VS_CURRENCIES_URL: &str =
-            "https://pro-api.coingecko.com/api/v3/simple/supported_vs_currencies";
-
-        http_client
-            .get(SUPPORTED_VS_CURRENCIES_URL)
-            .send()
-            .map_err(ConstructError::SendSupportedVsCurrencies)
-            .and_then(|response: ReqwestResponse| {
-                response
-                    .bytes()
-                    .map_err(ConstructError::FetchSupportedVsCurrencies)
+        retry_policy
+            .send(&retry_policy.new_budget(), || {
+                http_client.get(format!(
+                    "{api_base_url}/simple/supported_vs_currencies"
+                ))
+            })
+            .map(|result: Result<Bytes, retry::Error>| {
+                result.map_err(ConstructError::FetchSupportedVsCurrencies)
             })
             .map(|result: Result<Bytes, ConstructError>| {
                 result.and_then(|body: Bytes| {
@@ -220,82 +283,155 @@ impl SanityCheck {
 
     async fn query(
         http_client: Arc<ReqwestClient>,
-        mappings: Mappings,
-        regex: &'static Regex,
-    ) -> Result<Price<CoinWithoutDecimalPlaces>, BenchmarkError> {
-        const PRICE_URL: &str =
-            "https://pro-api.coingecko.com/api/v3/simple/price";
-
-        http_client
-            .get(PRICE_URL)
-            .query(&[
-                ("ids", mappings.base.mapping.as_ref()),
-                ("vs_currencies", mappings.quote.mapping.as_ref()),
-                ("precision", "full"),
-            ])
-            .send()
-            .map_err(BenchmarkError::SendQuery)
-            .and_then(|response: ReqwestResponse| {
-                response
-                    .bytes()
-                    .map_err(BenchmarkError::ReceiveResponseBody)
-            })
-            .map(|result: Result<Bytes, BenchmarkError>| {
-                result.and_then(|body: Bytes| {
-                    String::from_utf8({ body }.to_vec())
-                        .map_err(BenchmarkError::InvalidUtf8)
-                        .and_then(move |body| {
-                            Self::parse_price_with_regex(&mappings, regex, body)
-                        })
-                })
+        retry_policy: retry::Policy,
+        budget: retry::Budget,
+        api_base_url: Arc<str>,
+        ids: String,
+        vs_currencies: Arc<str>,
+    ) -> Result<QueryResponse, BenchmarkError> {
+        retry_policy
+            .send(&budget, || {
+                http_client
+                    .get(format!("{api_base_url}/simple/price"))
+                    .query(&[
+                        ("ids", ids.as_str()),
+                        ("vs_currencies", &vs_currencies),
+                        ("precision", "full"),
+                    ])
             })
             .await
+            .map_err(BenchmarkError::Query)
+            .and_then(|body: Bytes| {
+                serde_json::from_slice(&body)
+                    .map_err(BenchmarkError::DeserializeResponse)
+            })
     }
 
-    fn regex() -> &'static Regex {
-        static REGEX: OnceLock<Regex> = OnceLock::new();
+    /// Queries the prices of all mappings, batching as many of them as
+    /// possible into each request.
+    ///
+    /// All requests share the same list of "versus currencies", while the
+    /// list of IDs is split into chunks to keep the URL's length bounded.
+    async fn query_batched(
+        &self,
+        mappings: &[Mappings],
+    ) -> Result<QueryResponse, BenchmarkError> {
+        const MAX_IDS_PER_QUERY: usize = 50;
+
+        let ids: Vec<&str> = mappings
+            .iter()
+            .map(|mappings: &Mappings| mappings.base.mapping.as_ref())
+            .collect::<BTreeSet<&str>>()
+            .into_iter()
+            .collect();
+
+        let vs_currencies: Arc<str> = mappings
+            .iter()
+            .map(|mappings: &Mappings| mappings.quote.mapping.as_ref())
+            .collect::<BTreeSet<&str>>()
+            .into_iter()
+            .collect::<Vec<&str>>()
+            .join(",")
+            .into();
+
+        let budget: retry::Budget = self.retry_policy.new_budget();
+
+        let mut set: JoinSet<Result<QueryResponse, BenchmarkError>> =
+            JoinSet::new();
+
+        for ids in ids.chunks(MAX_IDS_PER_QUERY) {
+            set.spawn(Self::query(
+                self.http_client.clone(),
+                self.retry_policy,
+                budget.clone(),
+                self.api_base_url.clone(),
+                ids.join(","),
+                vs_currencies.clone(),
+            ));
+        }
 
-        REGEX.get_or_init(|| {
-            let Ok(regex): Result<Regex, regex::Error> = RegexBuilder::new(
-                r#"^\s*\{\s*"[\w\-]+"\s*:\s*\{\s*"[\w\-]*"\s*:\s*(\d+(?:\.\d+)?)\s*\}\s*\}\s*$"#,
-            )
-            .case_insensitive(true)
-            .ignore_whitespace(false)
-            .multi_line(true)
-            .build() else {
-                unreachable!()
-            };
+        let mut response: QueryResponse = QueryResponse::new();
 
-            regex
-        })
+        while let Some(result) = set.join_next().await {
+            response.extend(
+                result
+                    .map_err(BenchmarkError::JoinQueryTask)
+                    .and_then(identity)?,
+            );
+        }
+
+        Ok(response)
     }
 
-    fn parse_price_with_regex(
+    async fn query_prices(
+        &self,
+        mappings: Vec<Mappings>,
+    ) -> Result<Vec<Price<CoinWithoutDecimalPlaces>>, BenchmarkError> {
+        let response: QueryResponse = self.query_batched(&mappings).await?;
+
+        mappings
+            .iter()
+            .map(|mappings: &Mappings| Self::extract_price(&response, mappings))
+            .collect()
+    }
+
+    fn extract_price(
+        response: &QueryResponse,
         mappings: &Mappings,
-        regex: &Regex,
-        body: String,
     ) -> Result<Price<CoinWithoutDecimalPlaces>, BenchmarkError> {
-        let maybe_price: Option<
-            Result<Price<CoinWithoutDecimalPlaces>, BenchmarkError>,
-        > = regex
-            .captures(&body)
-            .and_then(|captures: Captures<'_>| captures.get(1))
-            .map(|price_decimal| {
-                price_decimal
-                    .as_str()
-                    .parse()
-                    .map(|price_ratio: Ratio| {
-                        price_ratio.to_price(
-                            mappings.base.ticker.to_string(),
-                            mappings.quote.ticker.to_string(),
-                        )
-                    })
-                    .map_err(BenchmarkError::ParsePrice)
-            });
+        response
+            .get(mappings.base.mapping.as_ref())
+            .and_then(|prices: &BTreeMap<String, Box<RawValue>>| {
+                prices
+                    .get(mappings.quote.mapping.as_ref())
+                    .map(AsRef::as_ref)
+            })
+            .ok_or_else(|| {
+                BenchmarkError::PriceNotFoundInResponse(
+                    mappings.base.mapping.to_string(),
+                    mappings.quote.mapping.to_string(),
+                )
+            })
+            .and_then(|price: &RawValue| {
+                Ratio::from_json(price).map_err(BenchmarkError::ParsePrice)
+            })
+            .map(|price_ratio: Ratio| {
+                price_ratio.to_price(
+                    mappings.base.ticker.to_string(),
+                    mappings.quote.ticker.to_string(),
+                )
+            })
+    }
+}
 
-        maybe_price.unwrap_or_else(|| {
-            Err(BenchmarkError::PriceNotFoundInResponse(body))
-        })
+/// Prices as returned by `CoinGecko`, indexed by ID and then by "versus
+/// currency". They are kept unparsed as the response also contains the
+/// combinations which were not requested.
+type QueryResponse = BTreeMap<String, BTreeMap<String, Box<RawValue>>>;
+
+/// `CoinGecko` API plan, which determines the default API host and the header
+/// through which the API key is passed.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Tier {
+    #[default]
+    Pro,
+    Demo,
+}
+
+impl Tier {
+    const fn default_api_base_url(self) -> &'static str {
+        match self {
+            Self::Pro => "https://pro-api.coingecko.com/api/v3",
+            Self::Demo => "https://api.coingecko.com/api/v3",
+        }
+    }
+
+    const fn api_key_header(self) -> &'static str {
+        match self {
+            Self::Pro => "x-cg-pro-api-key",
+            Self::Demo => "x-cg-demo-api-key",
+        }
     }
 }
 
@@ -315,107 +451,30 @@ impl ComparisonProvider for SanityCheck {
         &self,
         benchmarked_provider_id: &str,
         prices: &[Price<CoinWithDecimalPlaces>],
-        max_deviation_exclusive: u64,
+        deviation_limits: &DeviationLimits,
     ) -> Result<(), PriceComparisonGuardError> {
-        let mut prices: Vec<Price<CoinWithDecimalPlaces>> = prices.to_vec();
-
-        let mut comparison_prices: Vec<Price<CoinWithoutDecimalPlaces>> =
-            Vec::new();
-
-        let regex: &'static Regex = Self::regex();
-
-        let mut set: JoinSet<
-            Result<Price<CoinWithoutDecimalPlaces>, BenchmarkError>,
-        > = JoinSet::new();
-
-        for index in (0..prices.len()).rev() {
-            let price: &Price<CoinWithDecimalPlaces> = &prices[index];
-
-            let Some(mappings): Option<Mappings> = self.get_mappings(price)
-            else {
-                let _: Price<CoinWithDecimalPlaces> = prices.remove(index);
-
-                continue;
-            };
-
-            set.spawn(Self::query(self.http_client.clone(), mappings, regex));
-        }
-
-        if prices.is_empty() {
-            debug_assert!(set.is_empty());
-
-            if self.mandatory {
-                tracing::error!(
-                    "Sanity check failed for provider with ID: {id}! No intersection of prices is empty!",
-                    id = benchmarked_provider_id,
-                );
-
-                Err(PriceComparisonGuardError::ComparisonProviderSpecific(
-                    Box::new(BenchmarkError::EmptyPricesIntersection),
-                ))
-            } else {
-                tracing::warn!(
-                    "Sanity check unavailable for provider with ID: {id}! No intersection of prices is empty!",
-                    id = benchmarked_provider_id,
-                );
-
-                Ok(())
-            }
-        } else {
-            while let Some(result) = set.join_next().await {
-                result
-                    .map_err(BenchmarkError::JoinQueryTask)
-                    .and_then(identity)
-                    .map(|price: Price<CoinWithoutDecimalPlaces>| {
-                        comparison_prices.push(price);
-                    })
-                    .map_err(|error: BenchmarkError| {
-                        PriceComparisonGuardError::ComparisonProviderSpecific(
-                            Box::new(error),
-                        )
-                    })?;
-            }
-
-            let result: Result<(), PriceComparisonGuardError> =
-                block_in_place(|| {
-                    deviation::compare_prices(
-                        &prices,
-                        &comparison_prices,
-                        max_deviation_exclusive,
-                    )
-                });
-
-            if result.is_ok() {
-                tracing::info!(
-                    "Sanity check passed for provider with ID: {id}.",
-                    id = benchmarked_provider_id,
-                );
-            } else {
-                tracing::error!(
-                    "Sanity check failed for provider with ID: {id}!",
-                    id = benchmarked_provider_id,
-                );
-            }
-
-            result
-        }
+        super::benchmark_prices(
+            benchmarked_provider_id,
+            self.mandatory,
+            prices,
+            deviation_limits,
+            |price: &Price<CoinWithDecimalPlaces>| self.get_mappings(price),
+            |mappings: Vec<Mappings>| self.query_prices(mappings),
+        )
+        .await
     }
 }
 
 #[derive(Debug, Error)]
 enum BenchmarkError {
-    #[error("Failed sending price query! Cause: {0}")]
-    SendQuery(ReqwestError),
-    #[error("Failed to receive price query response body! Cause: {0}")]
-    ReceiveResponseBody(ReqwestError),
-    #[error("Failed to retrieve price from response! No price found! Raw response: {0}")]
-    PriceNotFoundInResponse(String),
-    #[error("Failed to parse response body as string! Cause: {0}")]
-    InvalidUtf8(FromUtf8Error),
+    #[error("Failed to query prices! Cause: {0}")]
+    Query(retry::Error),
+    #[error("Failed to deserialize price query response! Cause: {0}")]
+    DeserializeResponse(serde_json::Error),
+    #[error("Failed to retrieve price from response! No price found for \"{0}\" versus \"{1}\"!")]
+    PriceNotFoundInResponse(String, String),
     #[error("Failed to parse price! Cause: {0}")]
     ParsePrice(price::Error),
-    #[error("Failed to benchmark prices because intersection is empty!")]
-    EmptyPricesIntersection,
     #[error("Failed to join price query task into main one! Cause: {0}")]
     JoinQueryTask(tokio::task::JoinError),
 }
@@ -434,10 +493,28 @@ impl FromConfig<true> for SanityCheck {
     where
         Config: ProviderConfigExt<true>,
     {
+        const API_KEY_FIELD: &str = "api_key";
+
         let mandatory: bool = Self::extract_mandatory_check_flag(&mut config)?;
 
+        let tier: Tier = Self::extract_tier(&mut config)?;
+
+        let api_base_url: Arc<str> =
+            Self::extract_api_base_url(&mut config, tier)?;
+
         let http_client: Arc<ReqwestClient> =
-            Self::construct_http_client::<Config>(id)?;
+            Config::fetch_from_env(id, API_KEY_FIELD)
+                .map_err(ConstructError::EnvVariable)
+                .and_then(|api_key: String| {
+                    Self::construct_http_client(tier, &api_key)
+                })?;
+
+        let retry_policy: retry::Policy = retry::Policy::extract(&mut config)
+            .map_err(
+            |error: toml::de::Error| {
+                ConstructError::DeserializeField(retry::Policy::FIELD, error)
+            },
+        )?;
 
         let ticker_mapping: BTreeMap<Arc<TickerUnsized>, Arc<str>> =
             Self::extract_ticker_mapping(&mut config)?;
@@ -445,14 +522,14 @@ impl FromConfig<true> for SanityCheck {
         if let Some(fields) = super::left_over_fields(config.into_misc()) {
             Err(ConstructError::UnknownFields(fields))
         } else {
-            Self::fetch_supported_vs_currencies(&http_client, &ticker_mapping)
-                .await
-                .map(|supported_vs_currencies: BTreeSet<Arc<str>>| Self {
-                    mandatory,
-                    http_client,
-                    ticker_mapping,
-                    supported_vs_currencies,
-                })
+            Self::new(
+                mandatory,
+                http_client,
+                retry_policy,
+                api_base_url,
+                ticker_mapping,
+            )
+            .await
         }
     }
 }
@@ -471,14 +548,273 @@ pub(crate) enum ConstructError {
     ConstructHttpClient(ReqwestError),
     #[error("Unknown fields found! Unknown fields: {0}")]
     UnknownFields(Box<str>),
-    #[error("Failed to send \"supported versus currencies\"! Cause: {0}")]
-    SendSupportedVsCurrencies(ReqwestError),
     #[error("Failed to fetch \"supported versus currencies\"! Cause: {0}")]
-    FetchSupportedVsCurrencies(ReqwestError),
+    FetchSupportedVsCurrencies(retry::Error),
     #[error(
         "Failed to deserialize \"supported versus currencies\"! Cause: {0}"
     )]
     DeserializeSupportedVsCurrencies(serde_json_wasm::de::Error),
-    #[error("Failed to parse prices RPC's URL! Cause: {0}")]
-    InvalidPricesRpcUrl(#[from] url::ParseError),
+    #[error("Failed to parse API's base URL! Cause: {0}")]
+    InvalidApiBaseUrl(#[from] url::ParseError),
+}
+
+#[cfg(test)]
+#[test]
+fn test_extract_price_from_batched_response() {
+    fn mappings(base: &str, quote: &str) -> Mappings {
+        Mappings {
+            base: Mapping {
+                ticker: base.to_ascii_uppercase().into(),
+                mapping: base.into(),
+            },
+            quote: Mapping {
+                ticker: quote.to_ascii_uppercase().into(),
+                mapping: quote.into(),
+            },
+        }
+    }
+
+    let response: QueryResponse = serde_json::from_str(
+        r#"{
+            "osmosis": {"usd": 0.7834021, "eur": 0.72},
+            "cosmos": {"usd": 8.93, "eur": 8.2}
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        SanityCheck::extract_price(&response, &mappings("osmosis", "usd"))
+            .unwrap(),
+        "0.7834021"
+            .parse::<Ratio>()
+            .unwrap()
+            .to_price(String::from("OSMOSIS"), String::from("USD"))
+    );
+
+    assert_eq!(
+        SanityCheck::extract_price(&response, &mappings("cosmos", "eur"))
+            .unwrap(),
+        "8.2"
+            .parse::<Ratio>()
+            .unwrap()
+            .to_price(String::from("COSMOS"), String::from("EUR"))
+    );
+
+    assert!(matches!(
+        SanityCheck::extract_price(&response, &mappings("akash", "usd")),
+        Err(BenchmarkError::PriceNotFoundInResponse(..))
+    ));
+}
+
+/// Serves canned `CoinGecko` responses on a local port and forwards the heads
+/// of all received requests through the returned channel.
+#[cfg(test)]
+fn spawn_stand_in(
+    prices: &'static str,
+) -> (Arc<str>, std::sync::mpsc::Receiver<String>) {
+    use std::{
+        io::{BufRead as _, BufReader, Write as _},
+        net::{TcpListener, TcpStream},
+        sync::mpsc,
+        thread,
+    };
+
+    const SUPPORTED_VS_CURRENCIES: &str = r#"["usd","eur"]"#;
+
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+
+    let api_base_url: Arc<str> =
+        format!("http://{}/api/v3", listener.local_addr().unwrap()).into();
+
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream: TcpStream = stream.unwrap();
+
+            let mut head: String = String::new();
+
+            let mut reader: BufReader<&TcpStream> = BufReader::new(&stream);
+
+            while reader.read_line(&mut head).unwrap() > 2
+                && !head.ends_with("\r\n\r\n")
+            {}
+
+            let body: &str = if head
+                .starts_with("GET /api/v3/simple/supported_vs_currencies ")
+            {
+                SUPPORTED_VS_CURRENCIES
+            } else if head.starts_with("GET /api/v3/simple/price?") {
+                prices
+            } else {
+                ""
+            };
+
+            // Recorded before responding, so requests are observable as soon
+            // as the client receives the responses to them.
+            if sender.send(head).is_err() {
+                break;
+            }
+
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {length}\r\nConnection: close\r\n\r\n{body}",
+                status = if body.is_empty() { "404 Not Found" } else { "200 OK" },
+                length = body.len(),
+            )
+            .unwrap();
+        }
+    });
+
+    (api_base_url, receiver)
+}
+
+#[cfg(test)]
+async fn stand_in_sanity_check(
+    tier: Tier,
+    prices: &'static str,
+) -> (SanityCheck, std::sync::mpsc::Receiver<String>) {
+    let (api_base_url, requests) = spawn_stand_in(prices);
+
+    let sanity_check: SanityCheck = SanityCheck::new(
+        true,
+        SanityCheck::construct_http_client(tier, "test-api-key").unwrap(),
+        retry::Policy::default(),
+        api_base_url,
+        [("OSMO", "osmosis"), ("USD", "usd")]
+            .into_iter()
+            .map(|(ticker, mapping): (&str, &str)| {
+                (ticker.into(), mapping.into())
+            })
+            .collect(),
+    )
+    .await
+    .unwrap();
+
+    (sanity_check, requests)
+}
+
+#[cfg(test)]
+fn osmo_usd_price(
+    amount: u128,
+    amount_quote: u128,
+) -> Price<CoinWithDecimalPlaces> {
+    Price::new(
+        CoinWithDecimalPlaces::new(amount, String::from("OSMO"), 6),
+        CoinWithDecimalPlaces::new(amount_quote, String::from("USD"), 6),
+    )
+}
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_benchmark_against_stand_in() {
+    use std::time::Duration;
+
+    let (sanity_check, receiver) =
+        stand_in_sanity_check(Tier::Demo, r#"{"osmosis":{"usd":0.5}}"#).await;
+
+    sanity_check
+        .benchmark_prices(
+            "test",
+            &[osmo_usd_price(2_000_000, 1_010_000)],
+            &DeviationLimits::new(500, BTreeMap::new(), BTreeMap::new()),
+        )
+        .await
+        .unwrap();
+
+    let requests: Vec<String> = (0..2)
+        .map(|_| receiver.recv_timeout(Duration::from_secs(5)).unwrap())
+        .collect();
+
+    assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+
+    for request in &requests {
+        assert!(request
+            .to_ascii_lowercase()
+            .contains("x-cg-demo-api-key: test-api-key\r\n"));
+    }
+
+    assert!(requests[1].starts_with(
+        "GET /api/v3/simple/price?ids=osmosis&vs_currencies=usd&"
+    ));
+}
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_benchmark_against_stand_in_deviation_too_big() {
+    let (sanity_check, _requests) =
+        stand_in_sanity_check(Tier::Pro, r#"{"osmosis":{"usd":0.5}}"#).await;
+
+    assert!(matches!(
+        sanity_check
+            .benchmark_prices(
+                "test",
+                &[osmo_usd_price(1_000_000, 1_000_000)],
+                &DeviationLimits::new(500, BTreeMap::new(), BTreeMap::new()),
+            )
+            .await,
+        Err(PriceComparisonGuardError::DeviationTooBig(..))
+    ));
+}
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_benchmark_against_stand_in_missing_price() {
+    let (sanity_check, _requests) =
+        stand_in_sanity_check(Tier::Pro, r#"{"osmosis":{"eur":0.5}}"#).await;
+
+    assert!(matches!(
+        sanity_check
+            .benchmark_prices(
+                "test",
+                &[osmo_usd_price(2_000_000, 1_000_000)],
+                &DeviationLimits::new(500, BTreeMap::new(), BTreeMap::new()),
+            )
+            .await,
+        Err(PriceComparisonGuardError::ComparisonProviderSpecific(_))
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_extract_price_from_response_corpus() {
+    let response: QueryResponse = serde_json::from_str(include_str!(
+        "../../../tests/coin_gecko_simple_price_resp.json"
+    ))
+    .unwrap();
+
+    for (id, vs_currency, expected) in [
+        ("bitcoin", "usd", "67432"),
+        ("bitcoin", "eur", "62291.47"),
+        ("osmosis", "eur", "0.72368"),
+        ("shiba-inu", "usd", "0.00001734"),
+        ("shiba-inu", "eur", "0.00001602"),
+        ("pepe", "usd", "0.00000789"),
+        ("baby-doge-coin", "usd", "0.00000000123"),
+        ("baby-doge-coin", "eur", "0.00000000114"),
+        ("dymension", "usd", "2.31"),
+        ("dymension", "eur", "2.13"),
+        ("injective-protocol", "usd", "25"),
+        ("injective-protocol", "eur", "23.1"),
+    ] {
+        let mappings: Mappings = Mappings {
+            base: Mapping {
+                ticker: "BASE".into(),
+                mapping: id.into(),
+            },
+            quote: Mapping {
+                ticker: "QUOTE".into(),
+                mapping: vs_currency.into(),
+            },
+        };
+
+        assert_eq!(
+            SanityCheck::extract_price(&response, &mappings).unwrap(),
+            expected
+                .parse::<Ratio>()
+                .unwrap()
+                .to_price(String::from("BASE"), String::from("QUOTE")),
+            "{id}/{vs_currency}",
+        );
+    }
 }
@@ -0,0 +1,499 @@
+use std::{
+    collections::BTreeMap, convert::identity, str::FromStr as _, sync::Arc,
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::{
+    header::{
+        HeaderMap, HeaderName, HeaderValue, InvalidHeaderName,
+        InvalidHeaderValue,
+    },
+    Client as ReqwestClient, Error as ReqwestError,
+};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use thiserror::Error;
+use tokio::task::JoinSet;
+use toml::Value;
+use url::Url;
+
+use chain_comms::{client::Client as NodeClient, config::StrOrValue};
+
+use crate::{
+    config::{self, ProviderConfigExt, Ticker, TickerUnsized},
+    deviation::DeviationLimits,
+    price::{
+        self, Coin, CoinWithDecimalPlaces, CoinWithoutDecimalPlaces, Price,
+        Ratio,
+    },
+    provider::{ComparisonProvider, FromConfig, PriceComparisonGuardError},
+};
+
+use super::retry;
+
+const BASE_PLACEHOLDER: &str = "{base}";
+const QUOTE_PLACEHOLDER: &str = "{quote}";
+
+/// Comparison provider querying an arbitrary HTTP endpoint which returns JSON.
+///
+/// The URL template and the path to the price field may contain the `{base}`
+/// and `{quote}` placeholders, which are substituted with the mapped tickers
+/// of the pair being benchmarked.
+pub(crate) struct HttpJson {
+    mandatory: bool,
+    http_client: Arc<ReqwestClient>,
+    retry_policy: retry::Policy,
+    url_template: Arc<str>,
+    price_path: Arc<[Box<str>]>,
+    ticker_mapping: BTreeMap<Arc<TickerUnsized>, Arc<str>>,
+}
+
+impl HttpJson {
+    fn extract_field<Config, T>(
+        config: &mut Config,
+        field: &'static str,
+    ) -> Result<T, ConstructError>
+    where
+        Config: ProviderConfigExt<true>,
+        T: for<'de> Deserialize<'de>,
+    {
+        config
+            .misc_mut()
+            .remove(field)
+            .ok_or(ConstructError::MissingField(field))
+            .and_then(|value: Value| {
+                value.try_into().map_err(|error: toml::de::Error| {
+                    ConstructError::DeserializeField(field, error)
+                })
+            })
+    }
+
+    fn extract_url_template<Config>(
+        config: &mut Config,
+    ) -> Result<Arc<str>, ConstructError>
+    where
+        Config: ProviderConfigExt<true>,
+    {
+        Self::extract_field(config, "url").and_then(|url_template: String| {
+            Url::parse(&substitute(&url_template, "base", "quote"))
+                .map(|_: Url| url_template.into())
+                .map_err(ConstructError::InvalidUrlTemplate)
+        })
+    }
+
+    fn extract_price_path<Config>(
+        config: &mut Config,
+    ) -> Result<Arc<[Box<str>]>, ConstructError>
+    where
+        Config: ProviderConfigExt<true>,
+    {
+        Self::extract_field(config, "price_path").and_then(
+            |price_path: String| {
+                if price_path.split('.').any(str::is_empty) {
+                    Err(ConstructError::InvalidPricePath(price_path))
+                } else {
+                    Ok(price_path.split('.').map(Box::from).collect())
+                }
+            },
+        )
+    }
+
+    fn extract_ticker_mapping<Config>(
+        config: &mut Config,
+    ) -> Result<BTreeMap<Arc<TickerUnsized>, Arc<str>>, ConstructError>
+    where
+        Config: ProviderConfigExt<true>,
+    {
+        Self::extract_field(config, "ticker_mapping").map(
+            |mappings: BTreeMap<Ticker, String>| {
+                mappings
+                    .into_iter()
+                    .map(|(ticker, mapping): (Ticker, String)| {
+                        (ticker.into(), mapping.into())
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    fn construct_http_client<Config>(
+        id: &str,
+        config: &mut Config,
+    ) -> Result<Arc<ReqwestClient>, ConstructError>
+    where
+        Config: ProviderConfigExt<true>,
+    {
+        const HEADERS_FIELD: &str = "headers";
+
+        let header_sources: BTreeMap<String, HeaderSource> =
+            config.misc_mut().remove(HEADERS_FIELD).map_or_else(
+                || Ok(BTreeMap::new()),
+                |value: Value| {
+                    value.try_into().map_err(|error: toml::de::Error| {
+                        ConstructError::DeserializeField(HEADERS_FIELD, error)
+                    })
+                },
+            )?;
+
+        let mut headers: HeaderMap = HeaderMap::new();
+
+        for (name, source) in header_sources {
+            let value: String = match source {
+                HeaderSource::Value(value) => value,
+                HeaderSource::Env { env } => {
+                    Config::fetch_from_env(id, &env)
+                        .map_err(ConstructError::EnvVariable)?
+                },
+            };
+
+            _ = headers.insert(
+                HeaderName::from_str(&name)?,
+                HeaderValue::from_str(&value)?,
+            );
+        }
+
+        ReqwestClient::builder()
+            .default_headers(headers)
+            .build()
+            .map(Arc::new)
+            .map_err(ConstructError::ConstructHttpClient)
+    }
+
+    fn get_mappings(
+        &self,
+        price: &Price<CoinWithDecimalPlaces>,
+    ) -> Option<Mappings> {
+        self.ticker_mapping
+            .get_key_value(price.amount().ticker())
+            .zip(
+                self.ticker_mapping
+                    .get_key_value(price.amount_quote().ticker()),
+            )
+            .map(
+                |(
+                    (base_ticker, base_mapping),
+                    (quote_ticker, quote_mapping),
+                )| {
+                    Mappings {
+                        base: Mapping {
+                            ticker: base_ticker.clone(),
+                            mapping: base_mapping.clone(),
+                        },
+                        quote: Mapping {
+                            ticker: quote_ticker.clone(),
+                            mapping: quote_mapping.clone(),
+                        },
+                    }
+                },
+            )
+    }
+
+    async fn query(
+        http_client: Arc<ReqwestClient>,
+        retry_policy: retry::Policy,
+        budget: retry::Budget,
+        url_template: Arc<str>,
+        price_path: Arc<[Box<str>]>,
+        mappings: Mappings,
+    ) -> Result<Price<CoinWithoutDecimalPlaces>, BenchmarkError> {
+        let url: String = substitute(
+            &url_template,
+            &mappings.base.mapping,
+            &mappings.quote.mapping,
+        );
+
+        retry_policy
+            .send(&budget, || http_client.get(&url))
+            .await
+            .map_err(BenchmarkError::Query)
+            .and_then(|body: Bytes| {
+                extract_price(&body, &price_path, &mappings).map(
+                    |price_ratio: Ratio| {
+                        price_ratio.to_price(
+                            mappings.base.ticker.to_string(),
+                            mappings.quote.ticker.to_string(),
+                        )
+                    },
+                )
+            })
+    }
+
+    /// Queries the prices of all mappings concurrently, sharing a single
+    /// retry budget.
+    async fn query_all(
+        &self,
+        mappings: Vec<Mappings>,
+    ) -> Result<Vec<Price<CoinWithoutDecimalPlaces>>, BenchmarkError> {
+        let budget: retry::Budget = self.retry_policy.new_budget();
+
+        let mut set: JoinSet<
+            Result<Price<CoinWithoutDecimalPlaces>, BenchmarkError>,
+        > = JoinSet::new();
+
+        for mappings in mappings {
+            set.spawn(Self::query(
+                self.http_client.clone(),
+                self.retry_policy,
+                budget.clone(),
+                self.url_template.clone(),
+                self.price_path.clone(),
+                mappings,
+            ));
+        }
+
+        let mut prices: Vec<Price<CoinWithoutDecimalPlaces>> =
+            Vec::with_capacity(set.len());
+
+        while let Some(result) = set.join_next().await {
+            prices.push(
+                result
+                    .map_err(BenchmarkError::JoinQueryTask)
+                    .and_then(identity)?,
+            );
+        }
+
+        Ok(prices)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HeaderSource {
+    Value(String),
+    Env { env: String },
+}
+
+struct Mapping {
+    ticker: Arc<TickerUnsized>,
+    mapping: Arc<str>,
+}
+
+struct Mappings {
+    base: Mapping,
+    quote: Mapping,
+}
+
+fn substitute(template: &str, base: &str, quote: &str) -> String {
+    template
+        .replace(BASE_PLACEHOLDER, base)
+        .replace(QUOTE_PLACEHOLDER, quote)
+}
+
+/// Walks the response body along the configured path and parses the value at
+/// its end as a price.
+///
+/// Segments are used as keys when the current value is an object and as
+/// indexes when it is an array. The price itself can be either a number or a
+/// string containing one.
+fn extract_price(
+    body: &[u8],
+    price_path: &[Box<str>],
+    mappings: &Mappings,
+) -> Result<Ratio, BenchmarkError> {
+    let mut value: &RawValue =
+        serde_json::from_slice(body).map_err(BenchmarkError::Deserialize)?;
+
+    for segment in price_path {
+        let segment: String = substitute(
+            segment,
+            &mappings.base.mapping,
+            &mappings.quote.mapping,
+        );
+
+        let next: Option<&RawValue> = match value.get().as_bytes().first() {
+            Some(b'{') => {
+                serde_json::from_str::<BTreeMap<String, &RawValue>>(value.get())
+                    .map_err(BenchmarkError::Deserialize)?
+                    .remove(&segment)
+            },
+            Some(b'[') => segment
+                .parse()
+                .ok()
+                .and_then(|index: usize| {
+                    serde_json::from_str::<Vec<&RawValue>>(value.get())
+                        .map(|mut elements: Vec<&RawValue>| {
+                            (index < elements.len())
+                                .then(|| elements.swap_remove(index))
+                        })
+                        .transpose()
+                })
+                .transpose()
+                .map_err(BenchmarkError::Deserialize)?,
+            _ => None,
+        };
+
+        value = next.ok_or_else(|| {
+            BenchmarkError::PriceNotFoundInResponse(
+                String::from_utf8_lossy(body).into_owned(),
+            )
+        })?;
+    }
+
+    Ratio::from_json(value).map_err(BenchmarkError::ParsePrice)
+}
+
+#[async_trait]
+impl ComparisonProvider for HttpJson {
+    async fn benchmark_prices(
+        &self,
+        benchmarked_provider_id: &str,
+        prices: &[Price<CoinWithDecimalPlaces>],
+        deviation_limits: &DeviationLimits,
+    ) -> Result<(), PriceComparisonGuardError> {
+        super::benchmark_prices(
+            benchmarked_provider_id,
+            self.mandatory,
+            prices,
+            deviation_limits,
+            |price: &Price<CoinWithDecimalPlaces>| self.get_mappings(price),
+            |mappings: Vec<Mappings>| self.query_all(mappings),
+        )
+        .await
+    }
+}
+
+#[derive(Debug, Error)]
+enum BenchmarkError {
+    #[error("Failed to query price! Cause: {0}")]
+    Query(retry::Error),
+    #[error("Failed to deserialize response body! Cause: {0}")]
+    Deserialize(serde_json::Error),
+    #[error("Failed to retrieve price from response! No price found! Raw response: {0}")]
+    PriceNotFoundInResponse(String),
+    #[error("Failed to parse price! Cause: {0}")]
+    ParsePrice(price::Error),
+    #[error("Failed to join price query task into main one! Cause: {0}")]
+    JoinQueryTask(tokio::task::JoinError),
+}
+
+#[async_trait]
+impl FromConfig<true> for HttpJson {
+    const ID: &'static str = "http_json";
+
+    type ConstructError = ConstructError;
+
+    async fn from_config<Config>(
+        id: &str,
+        mut config: Config,
+        _: &NodeClient,
+    ) -> Result<Self, Self::ConstructError>
+    where
+        Config: ProviderConfigExt<true>,
+    {
+        let StrOrValue(mandatory): StrOrValue<bool> =
+            Self::extract_field(&mut config, "mandatory")?;
+
+        let url_template: Arc<str> = Self::extract_url_template(&mut config)?;
+
+        let price_path: Arc<[Box<str>]> =
+            Self::extract_price_path(&mut config)?;
+
+        let ticker_mapping: BTreeMap<Arc<TickerUnsized>, Arc<str>> =
+            Self::extract_ticker_mapping(&mut config)?;
+
+        let http_client: Arc<ReqwestClient> =
+            Self::construct_http_client(id, &mut config)?;
+
+        let retry_policy: retry::Policy = retry::Policy::extract(&mut config)
+            .map_err(
+            |error: toml::de::Error| {
+                ConstructError::DeserializeField(retry::Policy::FIELD, error)
+            },
+        )?;
+
+        if let Some(fields) = super::left_over_fields(config.into_misc()) {
+            return Err(ConstructError::UnknownFields(fields));
+        }
+
+        Ok(Self {
+            mandatory,
+            http_client,
+            retry_policy,
+            url_template,
+            price_path,
+            ticker_mapping,
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ConstructError {
+    #[error("Failed to fetch value from environment! Cause: {0}")]
+    EnvVariable(config::EnvError),
+    #[error("Missing \"{0}\" field in configuration file!")]
+    MissingField(&'static str),
+    #[error("Failed to deserialize field \"{0}\"! Cause: {1}")]
+    DeserializeField(&'static str, toml::de::Error),
+    #[error("Invalid URL template! Cause: {0}")]
+    InvalidUrlTemplate(url::ParseError),
+    #[error("Invalid price path \"{0}\"! Path segments can't be empty!")]
+    InvalidPricePath(String),
+    #[error("Failed to construct header name! Cause: {0}")]
+    ConstructHeaderName(#[from] InvalidHeaderName),
+    #[error("Failed to construct header value! Cause: {0}")]
+    ConstructHeaderValue(#[from] InvalidHeaderValue),
+    #[error("Failed to construct HTTP client! Cause: {0}")]
+    ConstructHttpClient(ReqwestError),
+    #[error("Unknown fields found! Unknown fields: {0}")]
+    UnknownFields(Box<str>),
+}
+
+#[cfg(test)]
+fn test_mappings(base: &str, quote: &str) -> Mappings {
+    Mappings {
+        base: Mapping {
+            ticker: "BASE".into(),
+            mapping: base.into(),
+        },
+        quote: Mapping {
+            ticker: "QUOTE".into(),
+            mapping: quote.into(),
+        },
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_extract_price_from_object() {
+    let path: Box<[Box<str>]> = ["{base}", "{quote}"].map(Box::from).into();
+
+    let price: Ratio = extract_price(
+        br#"{"osmosis": {"usd": 1.234567890123456789}, "other": {}}"#,
+        &path,
+        &test_mappings("osmosis", "usd"),
+    )
+    .unwrap();
+
+    assert_eq!(price, "1.234567890123456789".parse().unwrap());
+}
+
+#[cfg(test)]
+#[test]
+fn test_extract_price_from_array_and_string() {
+    let path: Box<[Box<str>]> = ["data", "1", "price"].map(Box::from).into();
+
+    let price: Ratio = extract_price(
+        br#"{"data": [{"price": "1"}, {"price": "0.0025"}]}"#,
+        &path,
+        &test_mappings("OSMO", "USDC"),
+    )
+    .unwrap();
+
+    assert_eq!(price, "0.0025".parse().unwrap());
+}
+
+#[cfg(test)]
+#[test]
+fn test_extract_price_missing() {
+    let path: Box<[Box<str>]> = ["data", "2", "price"].map(Box::from).into();
+
+    assert!(matches!(
+        extract_price(
+            br#"{"data": [{"price": "1"}]}"#,
+            &path,
+            &test_mappings("OSMO", "USDC"),
+        ),
+        Err(BenchmarkError::PriceNotFoundInResponse(_))
+    ));
+}
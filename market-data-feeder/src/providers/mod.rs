@@ -1,20 +1,42 @@
-use std::{collections::BTreeMap, convert::identity};
+use std::{collections::BTreeMap, error::Error, future::Future};
 
-use tokio::task::JoinSet;
+use tokio::task::{block_in_place, JoinSet};
+
+use chain_comms::{
+    interact::query::{self, error::Bank as BankError},
+    reexport::{
+        cosmrs::proto::cosmos::bank::v1beta1::{
+            query_client::QueryClient as BankQueryClient, DenomUnit,
+        },
+        tonic::transport::Channel as TonicChannel,
+    },
+};
 
 use crate::{
-    price::{Coin, Price},
-    provider::{ComparisonProvider, FromConfig, Provider, ProviderError},
+    config::{AssetKind, Currencies, SymbolAndDecimalPlaces, Ticker},
+    deviation::{self, DeviationLimits},
+    price::{CoinWithDecimalPlaces, CoinWithoutDecimalPlaces, Pair, Price},
+    provider::{
+        coverage::DropReason,
+        denoms::{self, DenomLookup},
+        ComparisonProvider, Coverage, DenomMismatch, FromConfig,
+        PriceComparisonGuardError, Provider, ProviderError,
+    },
 };
 
 use self::{
     astroport::Astroport, coin_gecko::SanityCheck as CoinGeckoSanityCheck,
-    osmosis::Osmosis,
+    http_json::HttpJson, osmosis::Osmosis, osmosis_lcd::OsmosisLcd,
+    osmosis_twap::OsmosisTwap,
 };
 
 mod astroport;
 mod coin_gecko;
+mod http_json;
 mod osmosis;
+mod osmosis_lcd;
+mod osmosis_twap;
+mod retry;
 
 pub(crate) struct Providers;
 
@@ -28,6 +50,12 @@ impl Providers {
                 Some(visitor.on::<Astroport>())
             },
             <Osmosis as FromConfig<false>>::ID => Some(visitor.on::<Osmosis>()),
+            <OsmosisLcd as FromConfig<false>>::ID => {
+                Some(visitor.on::<OsmosisLcd>())
+            },
+            <OsmosisTwap as FromConfig<false>>::ID => {
+                Some(visitor.on::<OsmosisTwap>())
+            },
             _ => None,
         }
     }
@@ -43,6 +71,7 @@ impl Providers {
             CoinGeckoSanityCheck::ID => {
                 Some(visitor.on::<CoinGeckoSanityCheck>())
             },
+            HttpJson::ID => Some(visitor.on::<HttpJson>()),
             _ => Self::visit_provider(id, ProviderConversionVisitor(visitor)),
         }
     }
@@ -94,22 +123,59 @@ fn left_over_fields(config: BTreeMap<String, toml::Value>) -> Option<Box<str>> {
         .map(String::into_boxed_str)
 }
 
-async fn collect_prices_from_task_set<C>(
-    mut set: JoinSet<Result<Price<C>, ProviderError>>,
-    fault_tolerant: bool,
-) -> Result<Box<[Price<C>]>, ProviderError>
+/// Tasks fetching the prices of the pairs requested by the oracle, each
+/// returning the pair along with its price.
+type PairTaskSet =
+    JoinSet<(Pair, Result<Price<CoinWithDecimalPlaces>, ProviderError>)>;
+
+fn spawn_pair_task<F>(set: &mut PairTaskSet, pair: Pair, task: F)
 where
-    C: Coin,
+    F: Future<Output = Result<Price<CoinWithDecimalPlaces>, ProviderError>>
+        + Send
+        + 'static,
 {
-    let mut prices: Vec<Price<C>> = Vec::with_capacity(set.len());
+    set.spawn(async move { (pair, task.await) });
+}
+
+/// Collects the prices of the pairs requested by the oracle.
+///
+/// In fault-tolerant mode, pairs whose prices fail to be fetched are dropped
+/// from the coverage as such, so that they are told apart from the ones
+/// which aren't priced because of the configuration.
+async fn collect_prices_with_coverage(
+    mut set: PairTaskSet,
+    mut coverage: Coverage,
+    fault_tolerant: bool,
+) -> Result<
+    (Box<[Price<CoinWithDecimalPlaces>]>, Option<Coverage>),
+    ProviderError,
+> {
+    let mut prices: Vec<Price<CoinWithDecimalPlaces>> =
+        Vec::with_capacity(set.len());
 
     while let Some(result) = set.join_next().await {
-        match result.map_err(From::from).and_then(identity) {
+        let (pair, result): (
+            Option<Pair>,
+            Result<Price<CoinWithDecimalPlaces>, ProviderError>,
+        ) = match result {
+            Ok((pair, result)) => (Some(pair), result),
+            Err(error) => (None, Err(error.into())),
+        };
+
+        match result {
             Ok(price) => {
                 prices.push(price);
             },
             Err(error) if fault_tolerant => {
                 tracing::error!(error = %error, "Couldn't resolve price!");
+
+                if let Some((base, quote)) = pair {
+                    coverage.drop_pair(
+                        &base,
+                        &quote,
+                        DropReason::FetchFailed(error.to_string()),
+                    );
+                }
             },
             Err(error) => {
                 return Err(error);
@@ -117,5 +183,182 @@ where
         }
     }
 
-    Ok(prices.into_boxed_slice())
+    Ok((prices.into_boxed_slice(), Some(coverage)))
+}
+
+/// Benchmarks the prices for which the comparison provider has mappings
+/// against the ones it returns for them.
+///
+/// When it has mappings for none of them, the check fails in case the
+/// comparison provider is mandatory and is skipped otherwise.
+async fn benchmark_prices<
+    Mappings,
+    GetMappings,
+    Query,
+    QueryFuture,
+    QueryError,
+>(
+    benchmarked_provider_id: &str,
+    mandatory: bool,
+    prices: &[Price<CoinWithDecimalPlaces>],
+    deviation_limits: &DeviationLimits,
+    get_mappings: GetMappings,
+    query: Query,
+) -> Result<(), PriceComparisonGuardError>
+where
+    GetMappings: Fn(&Price<CoinWithDecimalPlaces>) -> Option<Mappings>,
+    Query: FnOnce(Vec<Mappings>) -> QueryFuture,
+    QueryFuture: Future<
+        Output = Result<Vec<Price<CoinWithoutDecimalPlaces>>, QueryError>,
+    >,
+    QueryError: Error + Send + 'static,
+{
+    let (prices, mappings): (Vec<Price<CoinWithDecimalPlaces>>, Vec<Mappings>) =
+        prices
+            .iter()
+            .filter_map(|price: &Price<CoinWithDecimalPlaces>| {
+                get_mappings(price)
+                    .map(|mappings: Mappings| (price.clone(), mappings))
+            })
+            .unzip();
+
+    if prices.is_empty() {
+        return if mandatory {
+            tracing::error!(
+                "Sanity check failed for provider with ID: {id}! Intersection of prices is empty!",
+                id = benchmarked_provider_id,
+            );
+
+            Err(PriceComparisonGuardError::EmptyPricesIntersection)
+        } else {
+            tracing::warn!(
+                "Sanity check unavailable for provider with ID: {id}! Intersection of prices is empty!",
+                id = benchmarked_provider_id,
+            );
+
+            Ok(())
+        };
+    }
+
+    let comparison_prices: Vec<Price<CoinWithoutDecimalPlaces>> =
+        query(mappings).await.map_err(|error: QueryError| {
+            PriceComparisonGuardError::ComparisonProviderSpecific(Box::new(
+                error,
+            ))
+        })?;
+
+    let result: Result<(), PriceComparisonGuardError> = block_in_place(|| {
+        deviation::compare_prices(&prices, &comparison_prices, deviation_limits)
+    });
+
+    if result.is_ok() {
+        tracing::info!(
+            "Sanity check passed for provider with ID: {id}.",
+            id = benchmarked_provider_id,
+        );
+    } else {
+        tracing::error!(
+            "Sanity check failed for provider with ID: {id}!",
+            id = benchmarked_provider_id,
+        );
+    }
+
+    result
+}
+
+/// Checks the native currencies against the bank module of the chain the
+/// channel is connected to. CW20 tokens have no bank metadata, so they are
+/// skipped.
+async fn check_denoms(
+    channel: TonicChannel,
+    currencies: &Currencies,
+) -> Result<Box<[DenomMismatch]>, ProviderError> {
+    let mut query_client: BankQueryClient<TonicChannel> =
+        BankQueryClient::new(channel);
+
+    let mut mismatches: Vec<DenomMismatch> = Vec::new();
+
+    for (ticker, currency) in currencies.iter().filter(
+        |(_, currency): &(&Ticker, &SymbolAndDecimalPlaces)| {
+            currency.kind() == AssetKind::Native
+        },
+    ) {
+        let bank_query_error = |error: BankError| {
+            ProviderError::BankQuery(format!("currency: {ticker}"), error)
+        };
+
+        let lookup: DenomLookup = if let Some(metadata) = query::denom_metadata(
+            &mut query_client,
+            currency.denom().to_string(),
+        )
+        .await
+        .map_err(bank_query_error)?
+        {
+            DenomLookup::Metadata {
+                display_exponent: denoms::display_exponent(
+                    &metadata.display,
+                    metadata.denom_units.iter().map(|unit: &DenomUnit| {
+                        (unit.denom.as_str(), unit.exponent)
+                    }),
+                ),
+            }
+        } else if denoms::is_zero_amount(
+            &query::supply_of(&mut query_client, currency.denom().to_string())
+                .await
+                .map_err(bank_query_error)?,
+        ) {
+            DenomLookup::Missing
+        } else {
+            DenomLookup::NoMetadata
+        };
+
+        mismatches.extend(denoms::check(ticker, currency, lookup));
+    }
+
+    Ok(mismatches.into_boxed_slice())
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_collect_prices_with_coverage() {
+    let mut coverage: Coverage = Coverage::new();
+
+    coverage.request("OSMO", "USDC");
+    coverage.request("ATOM", "USDC");
+
+    let mut set: PairTaskSet = JoinSet::new();
+
+    spawn_pair_task(&mut set, ("OSMO".into(), "USDC".into()), async {
+        Ok(Price::new(
+            CoinWithDecimalPlaces::new(1, "OSMO".into(), 6),
+            CoinWithDecimalPlaces::new(1, "USDC".into(), 6),
+        ))
+    });
+
+    spawn_pair_task(&mut set, ("ATOM".into(), "USDC".into()), async {
+        Err(ProviderError::NonAsciiResponse(String::new()))
+    });
+
+    let (prices, coverage): (
+        Box<[Price<CoinWithDecimalPlaces>]>,
+        Option<Coverage>,
+    ) = collect_prices_with_coverage(set, coverage, true)
+        .await
+        .unwrap();
+
+    assert_eq!(prices.len(), 1);
+
+    assert_eq!(
+        coverage
+            .unwrap()
+            .with_prices(&prices)
+            .dropped()
+            .collect::<Vec<_>>(),
+        [(
+            &("ATOM".into(), "USDC".into()),
+            &DropReason::FetchFailed(
+                ProviderError::NonAsciiResponse(String::new()).to_string()
+            )
+        )]
+    );
 }
@@ -20,12 +20,15 @@ use chain_comms::{
 
 use crate::{
     config::{
-        Currencies, EnvError, ProviderConfigExt, SymbolAndDecimalPlaces,
-        SymbolUnsized, Ticker,
+        AssetKind, Currencies, FieldError, ProviderConfigExt,
+        SymbolAndDecimalPlaces, SymbolUnsized, Ticker,
     },
     messages::{PoolId, QueryMsg, SupportedCurrencyPairsResponse, SwapLeg},
-    price::{CoinWithDecimalPlaces, Price, Ratio},
-    provider::{FromConfig, Provider, ProviderError},
+    price::{self, CoinWithDecimalPlaces, Price, Ratio, UInt},
+    provider::{
+        coverage::DropReason, Coverage, DenomMismatch, FromConfig, Provider,
+        ProviderError,
+    },
 };
 
 pub(crate) struct Osmosis {
@@ -36,61 +39,123 @@ pub(crate) struct Osmosis {
     currencies: Currencies,
 }
 
-impl Osmosis {
-    async fn query_supported_currencies(
-        &self,
-        node_rpc: TonicChannel,
-    ) -> Result<impl Iterator<Item = Route> + '_, query::error::Wasm> {
-        query::wasm_smart::<SupportedCurrencyPairsResponse>(
-            &mut WasmQueryClient::new(node_rpc),
-            self.oracle_addr.to_string(),
-            QueryMsg::SUPPORTED_CURRENCY_PAIRS.to_vec(),
-        )
-        .await
-        .map(|swap_legs: Vec<SwapLeg>| {
-            swap_legs
-                .into_iter()
-                .filter_map(|swap: SwapLeg| -> Option<Route> {
-                    let (from_symbol, from_decimal_places): (
-                        Arc<SymbolUnsized>,
-                        u8,
-                    ) = self.currencies.get(&swap.from).map(
-                        |symbol_and_decimal_places: &SymbolAndDecimalPlaces| {
-                            (
-                                symbol_and_decimal_places.denom().clone(),
-                                symbol_and_decimal_places.decimal_places(),
-                            )
-                        },
-                    )?;
-
-                    let (to_symbol, to_decimal_places): (
-                        Arc<SymbolUnsized>,
-                        u8,
-                    ) = self.currencies.get(&swap.to.target).map(
-                        |symbol_and_decimal_places: &SymbolAndDecimalPlaces| {
-                            (
-                                symbol_and_decimal_places.denom().clone(),
-                                symbol_and_decimal_places.decimal_places(),
-                            )
-                        },
-                    )?;
-
-                    Some(Route {
-                        pool_id: swap.to.pool_id,
-                        from: TickerSymbolDecimalPlaces {
-                            ticker: swap.from,
-                            symbol: from_symbol,
-                            decimal_places: from_decimal_places,
-                        },
-                        to: TickerSymbolDecimalPlaces {
-                            ticker: swap.to.target,
-                            symbol: to_symbol,
-                            decimal_places: to_decimal_places,
-                        },
-                    })
-                })
+/// Osmosis pools hold only native tokens, so CW20 currencies can't be priced.
+pub(super) fn ensure_native_currencies(
+    currencies: &Currencies,
+) -> Result<(), Ticker> {
+    currencies
+        .iter()
+        .find(|(_, currency): &(&Ticker, &SymbolAndDecimalPlaces)| {
+            currency.kind() != AssetKind::Native
+        })
+        .map_or(Ok(()), |(ticker, _): (&Ticker, &SymbolAndDecimalPlaces)| {
+            Err(ticker.clone())
         })
+}
+
+pub(super) async fn query_supported_currencies(
+    node_rpc: TonicChannel,
+    oracle_addr: &str,
+    currencies: &Currencies,
+) -> Result<(Vec<Route>, Coverage), query::error::Wasm> {
+    query::wasm_smart::<SupportedCurrencyPairsResponse>(
+        &mut WasmQueryClient::new(node_rpc),
+        oracle_addr.to_string(),
+        QueryMsg::SUPPORTED_CURRENCY_PAIRS.to_vec(),
+    )
+    .await
+    .map(|swap_legs: Vec<SwapLeg>| {
+        let mut coverage: Coverage = Coverage::new();
+
+        let routes: Vec<Route> = swap_legs
+            .into_iter()
+            .filter_map(|swap: SwapLeg| -> Option<Route> {
+                match route(swap, currencies) {
+                    Ok(route) => {
+                        coverage.request(&route.from.ticker, &route.to.ticker);
+
+                        Some(route)
+                    },
+                    Err((swap, reason)) => {
+                        coverage.drop_pair(&swap.from, &swap.to.target, reason);
+
+                        None
+                    },
+                }
+            })
+            .collect();
+
+        (routes, coverage)
+    })
+}
+
+fn route(
+    swap: SwapLeg,
+    currencies: &Currencies,
+) -> Result<Route, (SwapLeg, DropReason)> {
+    let Some(from) = currencies.get(&swap.from) else {
+        let reason: DropReason =
+            DropReason::UnconfiguredCurrency(swap.from.clone());
+
+        return Err((swap, reason));
+    };
+
+    let Some(to) = currencies.get(&swap.to.target) else {
+        let reason: DropReason =
+            DropReason::UnconfiguredCurrency(swap.to.target.clone());
+
+        return Err((swap, reason));
+    };
+
+    Ok(Route {
+        pool_id: swap.to.pool_id,
+        from: TickerSymbolDecimalPlaces {
+            symbol: from.denom().clone(),
+            decimal_places: from.decimal_places(),
+            ticker: swap.from,
+        },
+        to: TickerSymbolDecimalPlaces {
+            symbol: to.denom().clone(),
+            decimal_places: to.decimal_places(),
+            ticker: swap.to.target,
+        },
+    })
+}
+
+/// Parses a decimal number transferred as an integer, scaled up by
+/// `decimal_places` orders of magnitude, as Osmosis does for its decimal types.
+///
+/// All of the digits are kept as long as the reduced ratio fits in 128 bits.
+/// Prices which don't, e.g. the ones of tokens with vastly different decimal
+/// places, are scaled down, keeping as many of their digits as fit.
+pub(super) fn parse_fixed_point_decimal(
+    value: &str,
+    decimal_places: u32,
+    from_ticker: &str,
+    to_ticker: &str,
+) -> Result<Ratio, ProviderError> {
+    if !value.is_ascii() {
+        return Err(ProviderError::NonAsciiResponse(format!(
+            "currency pair: {from_ticker}/{to_ticker}",
+        )));
     }
+
+    UInt::from_str_radix(value, 10)
+        .map_err(price::Error::from)
+        .and_then(|numerator: UInt| {
+            UInt::TEN
+                .checked_pow(decimal_places)
+                .ok_or(price::Error::ExponentTooBig)
+                .and_then(|denominator: UInt| {
+                    Ratio::from_big_uints(numerator, denominator)
+                })
+        })
+        .map_err(|error: price::Error| {
+            ProviderError::ParsePrice(
+                format!("currency pair: {from_ticker}/{to_ticker}"),
+                error,
+            )
+        })
 }
 
 #[async_trait]
@@ -103,15 +168,28 @@ impl Provider for Osmosis {
         &self,
         fault_tolerant: bool,
     ) -> Result<Box<[Price<CoinWithDecimalPlaces>]>, ProviderError> {
-        const DECIMAL_PLACES_IN_RESPONSE: usize = 36;
-        const MAX_U128_DECIMAL_DIGITS: usize = 38;
+        self.get_prices_with_coverage(fault_tolerant)
+            .await
+            .map(|(prices, _): (_, Option<Coverage>)| prices)
+    }
+
+    async fn get_prices_with_coverage(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<
+        (Box<[Price<CoinWithDecimalPlaces>]>, Option<Coverage>),
+        ProviderError,
+    > {
+        const DECIMAL_PLACES_IN_RESPONSE: u32 = 36;
 
-        let mut set: JoinSet<
-            Result<Price<CoinWithDecimalPlaces>, ProviderError>,
-        > = JoinSet::new();
+        let mut set: super::PairTaskSet = JoinSet::new();
 
-        let routes_iter = self
-            .query_supported_currencies(self.node_client.raw_grpc())
+        let (routes, coverage): (Vec<Route>, Coverage) =
+            query_supported_currencies(
+                self.node_client.raw_grpc(),
+                &self.oracle_addr,
+                &self.currencies,
+            )
             .await?;
 
         for Route {
@@ -128,77 +206,61 @@ impl Provider for Osmosis {
                     symbol: to_symbol,
                     decimal_places: to_decimal_places,
                 },
-        } in routes_iter
+        } in routes
         {
             let channel = self.channel.clone();
 
-            set.spawn(async move {
-                query::raw(
-                    channel,
-                    SpotPriceRequest {
-                        pool_id,
-                        base_asset_denom: from_symbol.to_string(),
-                        quote_asset_denom: to_symbol.to_string(),
-                    },
-                    "/osmosis.poolmanager.v2.Query/SpotPriceV2",
-                )
-                .await
-                .map_err(|error| {
-                    ProviderError::WasmQuery(
-                        format!("currency pair: {from_ticker}/{to_ticker}"),
-                        query::error::Wasm::RawQuery(error),
+            super::spawn_pair_task(
+                &mut set,
+                (from_ticker.clone(), to_ticker.clone()),
+                async move {
+                    query::raw(
+                        channel,
+                        SpotPriceRequest {
+                            pool_id,
+                            base_asset_denom: from_symbol.to_string(),
+                            quote_asset_denom: to_symbol.to_string(),
+                        },
+                        "/osmosis.poolmanager.v2.Query/SpotPriceV2",
                     )
-                })
-                .and_then(|SpotPriceResponse { mut spot_price }| {
-                    debug!(
-                        r#"Osmosis returned "{spot_price}" for the pair {from_ticker}/{to_ticker} from pool #{pool_id}."#
-                    );
-
-                    if spot_price.is_ascii() {
-                        Ok(
-                            if let Some(zeroes_needed) =
-                                DECIMAL_PLACES_IN_RESPONSE.checked_sub(spot_price.len())
-                            {
-                                String::from(".")
-                                    + &String::from('0').repeat(zeroes_needed)
-                                    + &spot_price
-                            } else {
-                                spot_price
-                                    .insert(spot_price.len() - DECIMAL_PLACES_IN_RESPONSE, '.');
-
-                                spot_price
-                            },
+                    .await
+                    .map_err(|error| {
+                        ProviderError::WasmQuery(
+                            format!("currency pair: {from_ticker}/{to_ticker}"),
+                            query::error::Wasm::RawQuery(error),
                         )
-                    } else {
-                        Err(ProviderError::NonAsciiResponse(format!(
-                            "currency pair: {from_ticker}/{to_ticker}",
-                        )))
-                    }
-                })
-                .and_then(|spot_price| {
-                    spot_price[..spot_price
-                        .len()
-                        .min(MAX_U128_DECIMAL_DIGITS + 1 /* Added dot */)]
-                        .try_into()
-                        .map_err(|error| {
-                            ProviderError::ParsePrice(
-                                format!("currency pair: {from_ticker}/{to_ticker}"),
-                                error,
-                            )
-                        })
-                        .map(|ratio: Ratio| {
-                            ratio.as_quote_to_price_with_decimal_places(
-                                from_ticker,
-                                from_decimal_places,
-                                to_ticker,
-                                to_decimal_places,
-                            )
-                        })
-                })
-            });
+                    })
+                    .and_then(|SpotPriceResponse { spot_price }| {
+                        debug!(
+                            r#"Osmosis returned "{spot_price}" for the pair {from_ticker}/{to_ticker} from pool #{pool_id}."#
+                        );
+
+                        parse_fixed_point_decimal(
+                            &spot_price,
+                            DECIMAL_PLACES_IN_RESPONSE,
+                            &from_ticker,
+                            &to_ticker,
+                        )
+                    })
+                    .map(|ratio: Ratio| {
+                        ratio.as_quote_to_price_with_decimal_places(
+                            from_ticker,
+                            from_decimal_places,
+                            to_ticker,
+                            to_decimal_places,
+                        )
+                    })
+                },
+            );
         }
 
-        super::collect_prices_from_task_set(set, fault_tolerant).await
+        super::collect_prices_with_coverage(set, coverage, fault_tolerant).await
+    }
+
+    async fn check_denoms(
+        &self,
+    ) -> Result<Box<[DenomMismatch]>, ProviderError> {
+        super::check_denoms(self.channel.clone(), &self.currencies).await
     }
 }
 
@@ -226,14 +288,16 @@ impl FromConfig<false> for Osmosis {
                 })
             })?;
 
+        ensure_native_currencies(&currencies)
+            .map_err(ConstructError::NonNativeCurrency)?;
+
+        let grpc_uri: String = config.take_or_fetch_from_env(id, "grpc_uri")?;
+
         let oracle_addr: Arc<str> = config.oracle_addr().clone();
 
         if let Some(fields) = super::left_over_fields(config.into_misc()) {
             Err(ConstructError::UnknownFields(fields))
         } else {
-            let grpc_uri = Config::fetch_from_env(id, "GRPC_URI")
-                .map_err(ConstructError::FetchGrpcUri)?;
-
             NodeClient::new(&grpc_uri, None)
                 .await
                 .map(|osmosis_client| Self {
@@ -256,20 +320,51 @@ pub(crate) enum ConstructError {
     DeserializeField(&'static str, toml::de::Error),
     #[error("Unknown fields found! Unknown fields: {0}")]
     UnknownFields(Box<str>),
-    #[error("Failed to fetch Osmosis node's gRPC URI from environment variables! Cause: {0}")]
-    FetchGrpcUri(#[from] EnvError),
+    #[error("Currency \"{0}\" is not a native token!")]
+    NonNativeCurrency(Ticker),
+    #[error("Failed to fetch Osmosis node's gRPC URI! Cause: {0}")]
+    FetchGrpcUri(#[from] FieldError),
     #[error("Failed to connect gRPC endpoint! Cause: {0}")]
     ConnectToGrpc(#[from] client::error::Error),
 }
 
-struct Route {
-    pool_id: PoolId,
-    from: TickerSymbolDecimalPlaces,
-    to: TickerSymbolDecimalPlaces,
+pub(super) struct Route {
+    pub pool_id: PoolId,
+    pub from: TickerSymbolDecimalPlaces,
+    pub to: TickerSymbolDecimalPlaces,
+}
+
+pub(super) struct TickerSymbolDecimalPlaces {
+    pub ticker: Ticker,
+    pub symbol: Arc<SymbolUnsized>,
+    pub decimal_places: u8,
 }
 
-struct TickerSymbolDecimalPlaces {
-    ticker: Ticker,
-    symbol: Arc<SymbolUnsized>,
-    decimal_places: u8,
+#[cfg(test)]
+#[test]
+fn test_parse_fixed_point_decimal_with_many_digits() {
+    // Price of WBTC, with 8 decimal places, in USDC, with 6 decimal places,
+    // which doesn't fit in 128 bits.
+    let ratio: Ratio = parse_fixed_point_decimal(
+        "6001234567890123456789012345678901234567",
+        36,
+        "WBTC",
+        "USDC",
+    )
+    .unwrap();
+
+    let decimal: String = ratio
+        .as_quote_to_price_with_decimal_places(
+            "WBTC".into(),
+            0,
+            "USDC".into(),
+            0,
+        )
+        .to_decimal_string()
+        .unwrap();
+
+    assert!(
+        decimal.starts_with("6001.23456789012345678901234567890123"),
+        "{decimal}"
+    );
 }
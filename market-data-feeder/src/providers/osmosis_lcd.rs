@@ -0,0 +1,557 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::{Client as ReqwestClient, Error as ReqwestError, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize};
+use thiserror::Error;
+use tokio::task::JoinSet;
+use toml::Value;
+use tracing::debug;
+use url::Url;
+
+use chain_comms::client::Client as NodeClient;
+
+use crate::{
+    config::{
+        AssetKind, Currencies, FieldError, ProviderConfigExt,
+        SymbolAndDecimalPlaces, SymbolUnsized, Ticker,
+    },
+    price::{self, CoinWithDecimalPlaces, Price, Ratio, UInt},
+    provider::{
+        denoms::{self, DenomLookup},
+        Coverage, DenomMismatch, FromConfig, Provider, ProviderError,
+    },
+};
+
+use super::osmosis::{
+    ensure_native_currencies, query_supported_currencies, Route,
+    TickerSymbolDecimalPlaces,
+};
+
+pub(crate) struct OsmosisLcd {
+    instance_id: String,
+    node_client: NodeClient,
+    oracle_addr: Arc<str>,
+    http_client: ReqwestClient,
+    pools_url: Url,
+    bank_url: Url,
+    currencies: Currencies,
+}
+
+impl OsmosisLcd {
+    async fn query_pool(
+        http_client: ReqwestClient,
+        pool_url: Url,
+        context: &str,
+    ) -> Result<Pool, ProviderError> {
+        let body: Bytes = http_client
+            .get(pool_url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|error: ReqwestError| {
+                ProviderError::HttpQuery(context.to_string(), error)
+            })?
+            .bytes()
+            .await
+            .map_err(|error: ReqwestError| {
+                ProviderError::HttpQuery(context.to_string(), error)
+            })?;
+
+        serde_json_wasm::from_slice(&body)
+            .map(|PoolResponse { pool }: PoolResponse| pool)
+            .map_err(|error: serde_json_wasm::de::Error| {
+                ProviderError::DeserializeResponse(context.to_string(), error)
+            })
+    }
+
+    /// Queries the bank module about the given denomination, returning `None`
+    /// when the node doesn't find what is queried.
+    async fn query_bank<R>(
+        &self,
+        path: &str,
+        denom: &str,
+        context: &str,
+    ) -> Result<Option<R>, ProviderError>
+    where
+        R: DeserializeOwned,
+    {
+        let mut url: Url = self.bank_url.join(path)?;
+
+        url.query_pairs_mut().append_pair("denom", denom);
+
+        let response: reqwest::Response =
+            self.http_client.get(url).send().await.map_err(
+                |error: ReqwestError| {
+                    ProviderError::HttpQuery(context.to_string(), error)
+                },
+            )?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let body: Bytes = response
+            .error_for_status()
+            .map_err(|error: ReqwestError| {
+                ProviderError::HttpQuery(context.to_string(), error)
+            })?
+            .bytes()
+            .await
+            .map_err(|error: ReqwestError| {
+                ProviderError::HttpQuery(context.to_string(), error)
+            })?;
+
+        serde_json_wasm::from_slice(&body).map(Some).map_err(
+            |error: serde_json_wasm::de::Error| {
+                ProviderError::DeserializeResponse(context.to_string(), error)
+            },
+        )
+    }
+}
+
+#[async_trait]
+impl Provider for OsmosisLcd {
+    fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    async fn get_prices(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<Box<[Price<CoinWithDecimalPlaces>]>, ProviderError> {
+        self.get_prices_with_coverage(fault_tolerant)
+            .await
+            .map(|(prices, _): (_, Option<Coverage>)| prices)
+    }
+
+    async fn get_prices_with_coverage(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<
+        (Box<[Price<CoinWithDecimalPlaces>]>, Option<Coverage>),
+        ProviderError,
+    > {
+        let mut set: super::PairTaskSet = JoinSet::new();
+
+        let (routes, coverage): (Vec<Route>, Coverage) =
+            query_supported_currencies(
+                self.node_client.raw_grpc(),
+                &self.oracle_addr,
+                &self.currencies,
+            )
+            .await?;
+
+        for Route {
+            pool_id,
+            from:
+                TickerSymbolDecimalPlaces {
+                    ticker: from_ticker,
+                    symbol: from_symbol,
+                    decimal_places: from_decimal_places,
+                },
+            to:
+                TickerSymbolDecimalPlaces {
+                    ticker: to_ticker,
+                    symbol: to_symbol,
+                    decimal_places: to_decimal_places,
+                },
+        } in routes
+        {
+            let http_client: ReqwestClient = self.http_client.clone();
+
+            let pool_url: Url = self.pools_url.join(&pool_id.to_string())?;
+
+            super::spawn_pair_task(
+                &mut set,
+                (from_ticker.clone(), to_ticker.clone()),
+                async move {
+                    let context: String =
+                        format!("currency pair: {from_ticker}/{to_ticker}");
+
+                    Self::query_pool(http_client, pool_url, &context)
+                        .await?
+                        .spot_price(&from_symbol, &to_symbol)
+                        .map_err(|error: PoolError| {
+                            ProviderError::Specific(context, Box::new(error))
+                        })
+                        .map(|ratio: Ratio| {
+                            debug!(
+                                ?ratio,
+                                "Osmosis LCD pool #{pool_id} resolved to a price for the pair {from_ticker}/{to_ticker}."
+                            );
+
+                            ratio.as_quote_to_price_with_decimal_places(
+                                from_ticker,
+                                from_decimal_places,
+                                to_ticker,
+                                to_decimal_places,
+                            )
+                        })
+                },
+            );
+        }
+
+        super::collect_prices_with_coverage(set, coverage, fault_tolerant).await
+    }
+
+    async fn check_denoms(
+        &self,
+    ) -> Result<Box<[DenomMismatch]>, ProviderError> {
+        let mut mismatches: Vec<DenomMismatch> = Vec::new();
+
+        for (ticker, currency) in self.currencies.iter().filter(
+            |(_, currency): &(&Ticker, &SymbolAndDecimalPlaces)| {
+                currency.kind() == AssetKind::Native
+            },
+        ) {
+            let context: String = format!("currency: {ticker}");
+
+            let lookup: DenomLookup =
+                if let Some(DenomMetadataResponse { metadata }) = self
+                    .query_bank(
+                        "denoms_metadata_by_query_string",
+                        currency.denom(),
+                        &context,
+                    )
+                    .await?
+                {
+                    DenomLookup::Metadata {
+                        display_exponent: denoms::display_exponent(
+                            &metadata.display,
+                            metadata.denom_units.iter().map(
+                                |unit: &DenomUnit| {
+                                    (unit.denom.as_str(), unit.exponent)
+                                },
+                            ),
+                        ),
+                    }
+                } else if self
+                    .query_bank("supply/by_denom", currency.denom(), &context)
+                    .await?
+                    .map_or(
+                        true,
+                        |SupplyOfResponse { amount }: SupplyOfResponse| {
+                            denoms::is_zero_amount(&amount.amount)
+                        },
+                    )
+                {
+                    DenomLookup::Missing
+                } else {
+                    DenomLookup::NoMetadata
+                };
+
+            mismatches.extend(denoms::check(ticker, currency, lookup));
+        }
+
+        Ok(mismatches.into_boxed_slice())
+    }
+}
+
+#[async_trait]
+impl FromConfig<false> for OsmosisLcd {
+    const ID: &'static str = "osmosis_lcd";
+
+    type ConstructError = ConstructError;
+
+    async fn from_config<Config>(
+        id: &str,
+        mut config: Config,
+        node_client: &NodeClient,
+    ) -> Result<Self, Self::ConstructError>
+    where
+        Config: ProviderConfigExt<false>,
+    {
+        const LCD_URL_FIELD: &str = "lcd_url";
+        const CURRENCIES_FIELD: &str = "currencies";
+
+        let currencies: Currencies = config
+            .misc_mut()
+            .remove(CURRENCIES_FIELD)
+            .ok_or(ConstructError::MissingField(CURRENCIES_FIELD))
+            .and_then(|value: Value| {
+                value.try_into().map_err(|error: toml::de::Error| {
+                    ConstructError::DeserializeField(CURRENCIES_FIELD, error)
+                })
+            })?;
+
+        ensure_native_currencies(&currencies)
+            .map_err(ConstructError::NonNativeCurrency)?;
+
+        let lcd_url: String = config
+            .take_or_fetch_from_env(id, LCD_URL_FIELD)
+            .map_err(ConstructError::FetchLcdUrl)?;
+
+        let oracle_addr: Arc<str> = config.oracle_addr().clone();
+
+        if let Some(fields) = super::left_over_fields(config.into_misc()) {
+            Err(ConstructError::UnknownFields(fields))
+        } else {
+            let lcd_url: Url = Url::parse(&format!(
+                "{lcd_url}/",
+                lcd_url = lcd_url.trim_end_matches('/'),
+            ))?;
+
+            let pools_url: Url =
+                lcd_url.join("osmosis/poolmanager/v1beta1/pools/")?;
+
+            let bank_url: Url = lcd_url.join("cosmos/bank/v1beta1/")?;
+
+            Ok(Self {
+                instance_id: id.to_string(),
+                node_client: node_client.clone(),
+                oracle_addr,
+                http_client: ReqwestClient::new(),
+                pools_url,
+                bank_url,
+                currencies,
+            })
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ConstructError {
+    #[error("Missing \"{0}\" field in configuration file!")]
+    MissingField(&'static str),
+    #[error("Failed to deserialize field \"{0}\"! Cause: {1}")]
+    DeserializeField(&'static str, toml::de::Error),
+    #[error("Unknown fields found! Unknown fields: {0}")]
+    UnknownFields(Box<str>),
+    #[error("Currency \"{0}\" is not a native token!")]
+    NonNativeCurrency(Ticker),
+    #[error("Failed to fetch Osmosis node's LCD URL! Cause: {0}")]
+    FetchLcdUrl(FieldError),
+    #[error("Failed to parse Osmosis node's LCD URL! Cause: {0}")]
+    InvalidLcdUrl(#[from] url::ParseError),
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum PoolError {
+    #[error("Pool type \"{0}\" is not supported!")]
+    UnsupportedPoolType(String),
+    #[error("Pool doesn't contain asset with denomination \"{0}\"!")]
+    MissingAsset(Arc<SymbolUnsized>),
+    #[error("Failed to parse pool's {0}! Cause: {1}")]
+    ParseAmount(&'static str, <UInt as std::str::FromStr>::Err),
+    #[error("Failed to compute price from pool's reserves! Cause: {0}")]
+    ComputePrice(#[from] price::Error),
+}
+
+#[derive(Deserialize)]
+struct PoolResponse {
+    pool: Pool,
+}
+
+#[derive(Deserialize)]
+struct Pool {
+    #[serde(rename = "@type")]
+    type_url: String,
+    #[serde(rename = "poolAssets", alias = "pool_assets", default)]
+    pool_assets: Vec<PoolAsset>,
+}
+
+impl Pool {
+    const BALANCER_POOL_TYPE: &'static str = "/osmosis.gamm.v1beta1.Pool";
+
+    /// Spot price of a weighted (balancer) pool, expressed as amount of the
+    /// quote asset per unit of the base asset.
+    ///
+    /// ```text
+    ///         quote_balance / quote_weight
+    /// price = ----------------------------
+    ///          base_balance / base_weight
+    /// ```
+    fn spot_price(
+        &self,
+        base_denom: &Arc<SymbolUnsized>,
+        quote_denom: &Arc<SymbolUnsized>,
+    ) -> Result<Ratio, PoolError> {
+        if self.type_url != Self::BALANCER_POOL_TYPE {
+            return Err(PoolError::UnsupportedPoolType(self.type_url.clone()));
+        }
+
+        let (base_balance, base_weight): (UInt, UInt) =
+            self.balance_and_weight(base_denom)?;
+
+        let (quote_balance, quote_weight): (UInt, UInt) =
+            self.balance_and_weight(quote_denom)?;
+
+        Ratio::from_big_uints(
+            quote_balance * base_weight,
+            base_balance * quote_weight,
+        )
+        .map_err(From::from)
+    }
+
+    fn balance_and_weight(
+        &self,
+        denom: &Arc<SymbolUnsized>,
+    ) -> Result<(UInt, UInt), PoolError> {
+        self.pool_assets
+            .iter()
+            .find(|asset: &&PoolAsset| asset.token.denom == denom.as_ref())
+            .ok_or_else(|| PoolError::MissingAsset(denom.clone()))
+            .and_then(|asset: &PoolAsset| {
+                asset
+                    .token
+                    .amount
+                    .parse()
+                    .map_err(|error| PoolError::ParseAmount("balance", error))
+                    .and_then(|balance: UInt| {
+                        asset
+                            .weight
+                            .parse()
+                            .map(|weight: UInt| (balance, weight))
+                            .map_err(|error| {
+                                PoolError::ParseAmount("weight", error)
+                            })
+                    })
+            })
+    }
+}
+
+#[derive(Deserialize)]
+struct PoolAsset {
+    token: Token,
+    weight: String,
+}
+
+#[derive(Deserialize)]
+struct Token {
+    denom: String,
+    amount: String,
+}
+
+#[derive(Deserialize)]
+struct DenomMetadataResponse {
+    metadata: DenomMetadata,
+}
+
+#[derive(Deserialize)]
+struct DenomMetadata {
+    #[serde(default)]
+    display: String,
+    #[serde(default)]
+    denom_units: Vec<DenomUnit>,
+}
+
+#[derive(Deserialize)]
+struct DenomUnit {
+    denom: String,
+    #[serde(default)]
+    exponent: u32,
+}
+
+#[derive(Deserialize)]
+struct SupplyOfResponse {
+    amount: Token,
+}
+
+#[cfg(test)]
+fn fixture_pool(pool_id: crate::messages::PoolId) -> Pool {
+    #[derive(Deserialize)]
+    struct PoolsResponse {
+        pools: Vec<IdentifiedPool>,
+    }
+
+    #[derive(Deserialize)]
+    struct IdentifiedPool {
+        id: String,
+        #[serde(flatten)]
+        pool: Pool,
+    }
+
+    let PoolsResponse { pools } = serde_json_wasm::from_str(include_str!(
+        "../../../tests/osmosis_pools_resp.json"
+    ))
+    .unwrap();
+
+    pools
+        .into_iter()
+        .find(|pool: &IdentifiedPool| pool.id == pool_id.to_string())
+        .unwrap()
+        .pool
+}
+
+#[cfg(test)]
+#[test]
+fn test_equally_weighted_pool() {
+    const ATOM: &str =
+        "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+
+    let pool: Pool = fixture_pool(1);
+
+    assert_eq!(
+        pool.spot_price(&Arc::from(ATOM), &Arc::from("uosmo"))
+            .unwrap()
+            .to_price("ATOM".into(), "OSMO".into()),
+        Ratio::from_big_uints(
+            UInt::from_digit(33_621_887_847_675),
+            UInt::from_digit(9_348_441_221_868),
+        )
+        .unwrap()
+        .to_price("ATOM".into(), "OSMO".into()),
+    );
+
+    assert_eq!(
+        pool.spot_price(&Arc::from("uosmo"), &Arc::from(ATOM))
+            .unwrap()
+            .to_price("OSMO".into(), "ATOM".into()),
+        Ratio::from_big_uints(
+            UInt::from_digit(9_348_441_221_868),
+            UInt::from_digit(33_621_887_847_675),
+        )
+        .unwrap()
+        .to_price("OSMO".into(), "ATOM".into()),
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_unequally_weighted_pool() {
+    // Pool #2: 1 353 140 076 "uion" with weight 858 993 459 200 000 and
+    // 244 382 010 326 "uosmo" with weight 214 748 364 800 000, i.e. 4:1.
+    assert_eq!(
+        fixture_pool(2)
+            .spot_price(&Arc::from("uion"), &Arc::from("uosmo"))
+            .unwrap()
+            .to_price("ION".into(), "OSMO".into()),
+        Ratio::from_big_uints(
+            UInt::from_digit(244_382_010_326 * 4),
+            UInt::from_digit(1_353_140_076),
+        )
+        .unwrap()
+        .to_price("ION".into(), "OSMO".into()),
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_missing_asset() {
+    assert!(matches!(
+        fixture_pool(1).spot_price(&Arc::from("uion"), &Arc::from("uosmo")),
+        Err(PoolError::MissingAsset(_))
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_all_fixture_pools_deserialize() {
+    #[derive(Deserialize)]
+    struct PoolsResponse {
+        pools: Vec<Pool>,
+    }
+
+    let PoolsResponse { pools } = serde_json_wasm::from_str(include_str!(
+        "../../../tests/osmosis_pools_resp.json"
+    ))
+    .unwrap();
+
+    assert_eq!(pools.len(), 100);
+
+    assert!(pools.iter().all(|pool: &Pool| {
+        pool.type_url == Pool::BALANCER_POOL_TYPE && pool.pool_assets.len() >= 2
+    }));
+}
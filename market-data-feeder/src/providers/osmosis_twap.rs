@@ -0,0 +1,437 @@
+use std::{
+    num::NonZeroU64,
+    sync::Arc,
+    time::{Duration, SystemTime, SystemTimeError, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use osmosis_std::{
+    shim::Timestamp,
+    types::osmosis::twap::v1beta1::{
+        ArithmeticTwapToNowRequest, ArithmeticTwapToNowResponse,
+        GeometricTwapToNowRequest, GeometricTwapToNowResponse,
+    },
+};
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::task::JoinSet;
+use toml::Value;
+use tracing::debug;
+
+use chain_comms::{
+    client::{self, Client as NodeClient},
+    config::StrOrValue,
+    interact::query,
+    reexport::tonic::transport::Channel as TonicChannel,
+};
+
+use crate::{
+    config::{Currencies, FieldError, ProviderConfigExt, Ticker},
+    messages::PoolId,
+    price::{CoinWithDecimalPlaces, Price, Ratio},
+    provider::{Coverage, DenomMismatch, FromConfig, Provider, ProviderError},
+};
+
+use super::osmosis::{
+    ensure_native_currencies, parse_fixed_point_decimal,
+    query_supported_currencies, Route, TickerSymbolDecimalPlaces,
+};
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TwapType {
+    #[default]
+    Arithmetic,
+    Geometric,
+}
+
+pub(crate) struct OsmosisTwap {
+    instance_id: String,
+    node_client: NodeClient,
+    oracle_addr: Arc<str>,
+    channel: TonicChannel,
+    currencies: Currencies,
+    twap_type: TwapType,
+    window: Duration,
+}
+
+impl OsmosisTwap {
+    async fn query_twap(
+        channel: TonicChannel,
+        twap_type: TwapType,
+        pool_id: PoolId,
+        base_asset: String,
+        quote_asset: String,
+        start_time: Timestamp,
+    ) -> Result<String, query::error::Raw> {
+        match twap_type {
+            TwapType::Arithmetic => query::raw(
+                channel,
+                ArithmeticTwapToNowRequest {
+                    pool_id,
+                    base_asset,
+                    quote_asset,
+                    start_time: Some(start_time),
+                },
+                "/osmosis.twap.v1beta1.Query/ArithmeticTwapToNow",
+            )
+            .await
+            .map(
+                |ArithmeticTwapToNowResponse { arithmetic_twap }| {
+                    arithmetic_twap
+                },
+            ),
+            TwapType::Geometric => query::raw(
+                channel,
+                GeometricTwapToNowRequest {
+                    pool_id,
+                    base_asset,
+                    quote_asset,
+                    start_time: Some(start_time),
+                },
+                "/osmosis.twap.v1beta1.Query/GeometricTwapToNow",
+            )
+            .await
+            .map(
+                |GeometricTwapToNowResponse { geometric_twap }| geometric_twap,
+            ),
+        }
+    }
+
+    fn window_start_time(&self) -> Result<Timestamp, ProviderError> {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|error: SystemTimeError| {
+                ProviderError::Specific(String::new(), Box::new(error))
+            })
+            .map(|now: Duration| {
+                let start: Duration = now.saturating_sub(self.window);
+
+                Timestamp {
+                    seconds: start.as_secs().try_into().unwrap_or(i64::MAX),
+                    nanos: start
+                        .subsec_nanos()
+                        .try_into()
+                        .unwrap_or_else(|_| unreachable!()),
+                }
+            })
+    }
+}
+
+#[async_trait]
+impl Provider for OsmosisTwap {
+    fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    async fn get_prices(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<Box<[Price<CoinWithDecimalPlaces>]>, ProviderError> {
+        self.get_prices_with_coverage(fault_tolerant)
+            .await
+            .map(|(prices, _): (_, Option<Coverage>)| prices)
+    }
+
+    async fn get_prices_with_coverage(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<
+        (Box<[Price<CoinWithDecimalPlaces>]>, Option<Coverage>),
+        ProviderError,
+    > {
+        const DECIMAL_PLACES_IN_RESPONSE: u32 = 18;
+
+        let mut set: super::PairTaskSet = JoinSet::new();
+
+        let start_time: Timestamp = self.window_start_time()?;
+
+        let (routes, coverage): (Vec<Route>, Coverage) =
+            query_supported_currencies(
+                self.node_client.raw_grpc(),
+                &self.oracle_addr,
+                &self.currencies,
+            )
+            .await?;
+
+        for Route {
+            pool_id,
+            from:
+                TickerSymbolDecimalPlaces {
+                    ticker: from_ticker,
+                    symbol: from_symbol,
+                    decimal_places: from_decimal_places,
+                },
+            to:
+                TickerSymbolDecimalPlaces {
+                    ticker: to_ticker,
+                    symbol: to_symbol,
+                    decimal_places: to_decimal_places,
+                },
+        } in routes
+        {
+            let channel: TonicChannel = self.channel.clone();
+
+            let twap_type: TwapType = self.twap_type;
+
+            let start_time: Timestamp = start_time.clone();
+
+            super::spawn_pair_task(
+                &mut set,
+                (from_ticker.clone(), to_ticker.clone()),
+                async move {
+                    Self::query_twap(
+                        channel,
+                        twap_type,
+                        pool_id,
+                        from_symbol.to_string(),
+                        to_symbol.to_string(),
+                        start_time,
+                    )
+                    .await
+                    .map_err(|error| {
+                        ProviderError::WasmQuery(
+                            format!("currency pair: {from_ticker}/{to_ticker}"),
+                            query::error::Wasm::RawQuery(error),
+                        )
+                    })
+                    .and_then(|twap: String| {
+                        debug!(
+                            r#"Osmosis returned {twap_type:?} TWAP "{twap}" for the pair {from_ticker}/{to_ticker} from pool #{pool_id}."#
+                        );
+
+                        parse_fixed_point_decimal(
+                            &twap,
+                            DECIMAL_PLACES_IN_RESPONSE,
+                            &from_ticker,
+                            &to_ticker,
+                        )
+                    })
+                    .map(|ratio: Ratio| {
+                        ratio.as_quote_to_price_with_decimal_places(
+                            from_ticker,
+                            from_decimal_places,
+                            to_ticker,
+                            to_decimal_places,
+                        )
+                    })
+                },
+            );
+        }
+
+        super::collect_prices_with_coverage(set, coverage, fault_tolerant).await
+    }
+
+    async fn check_denoms(
+        &self,
+    ) -> Result<Box<[DenomMismatch]>, ProviderError> {
+        super::check_denoms(self.channel.clone(), &self.currencies).await
+    }
+}
+
+#[async_trait]
+impl FromConfig<false> for OsmosisTwap {
+    const ID: &'static str = "osmosis_twap";
+
+    type ConstructError = ConstructError;
+
+    async fn from_config<Config>(
+        id: &str,
+        mut config: Config,
+        node_client: &NodeClient,
+    ) -> Result<Self, Self::ConstructError>
+    where
+        Config: ProviderConfigExt<false>,
+    {
+        let currencies: Currencies = config
+            .misc_mut()
+            .remove("currencies")
+            .ok_or(ConstructError::MissingField("currencies"))
+            .and_then(|value: Value| {
+                value.try_into().map_err(|error: toml::de::Error| {
+                    ConstructError::DeserializeField("currencies", error)
+                })
+            })?;
+
+        ensure_native_currencies(&currencies)
+            .map_err(ConstructError::NonNativeCurrency)?;
+
+        let twap_type: TwapType =
+            config.misc_mut().remove("twap_type").map_or_else(
+                || Ok(TwapType::default()),
+                |value: Value| {
+                    value.try_into().map_err(|error: toml::de::Error| {
+                        ConstructError::DeserializeField("twap_type", error)
+                    })
+                },
+            )?;
+
+        let window: Duration = config
+            .misc_mut()
+            .remove("window_seconds")
+            .ok_or(ConstructError::MissingField("window_seconds"))
+            .and_then(|value: Value| {
+                value.try_into().map_err(|error: toml::de::Error| {
+                    ConstructError::DeserializeField("window_seconds", error)
+                })
+            })
+            .map(|StrOrValue(window): StrOrValue<NonZeroU64>| {
+                Duration::from_secs(window.get())
+            })?;
+
+        let grpc_uri: String = config.take_or_fetch_from_env(id, "grpc_uri")?;
+
+        let oracle_addr: Arc<str> = config.oracle_addr().clone();
+
+        if let Some(fields) = super::left_over_fields(config.into_misc()) {
+            Err(ConstructError::UnknownFields(fields))
+        } else {
+            NodeClient::new(&grpc_uri, None)
+                .await
+                .map(|osmosis_client| Self {
+                    instance_id: id.to_string(),
+                    node_client: node_client.clone(),
+                    oracle_addr,
+                    channel: osmosis_client.raw_grpc(),
+                    currencies,
+                    twap_type,
+                    window,
+                })
+                .map_err(From::from)
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ConstructError {
+    #[error("Missing \"{0}\" field in configuration file!")]
+    MissingField(&'static str),
+    #[error("Failed to deserialize field \"{0}\"! Cause: {1}")]
+    DeserializeField(&'static str, toml::de::Error),
+    #[error("Unknown fields found! Unknown fields: {0}")]
+    UnknownFields(Box<str>),
+    #[error("Currency \"{0}\" is not a native token!")]
+    NonNativeCurrency(Ticker),
+    #[error("Failed to fetch Osmosis node's gRPC URI! Cause: {0}")]
+    FetchGrpcUri(#[from] FieldError),
+    #[error("Failed to connect gRPC endpoint! Cause: {0}")]
+    ConnectToGrpc(#[from] client::error::Error),
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_fixed_point_decimal() {
+    use std::str::FromStr as _;
+
+    use crate::price::{self, UInt};
+
+    let parse =
+        |value: &str| parse_fixed_point_decimal(value, 18, "ATOM", "OSMO");
+
+    for (value, expected) in [
+        ("1000000000000000000", "1"),
+        ("1500000000000000000", "1.5"),
+        ("14823541062750436981", "14.823541062750436981"),
+        ("1", "0.000000000000000001"),
+        ("0", "0"),
+    ] {
+        assert_eq!(
+            parse(value).unwrap(),
+            Ratio::from_str(expected).unwrap(),
+            "{value}",
+        );
+    }
+
+    // Values rounded by Osmosis are kept as sent, instead of being
+    // approximated by simpler ratios.
+    assert_ne!(
+        parse("333333333333333333").unwrap(),
+        Ratio::from_big_uints(UInt::ONE, UInt::from_digit(3)).unwrap(),
+    );
+
+    assert!(matches!(
+        parse(&"9".repeat(100)),
+        Err(ProviderError::ParsePrice(
+            _,
+            price::Error::ParseNumerator(_)
+        ))
+    ));
+
+    assert!(matches!(
+        parse_fixed_point_decimal("1", u32::MAX, "ATOM", "OSMO"),
+        Err(ProviderError::ParsePrice(_, price::Error::ExponentTooBig))
+    ));
+
+    assert!(matches!(
+        parse("-1"),
+        Err(ProviderError::ParsePrice(
+            _,
+            price::Error::ParseNumerator(_)
+        ))
+    ));
+
+    assert!(matches!(
+        parse("１"),
+        Err(ProviderError::NonAsciiResponse(_))
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_twap_response_fixtures() {
+    let ArithmeticTwapToNowResponse { arithmetic_twap } = serde_json::from_str(
+        include_str!("../../../tests/osmosis_arithmetic_twap_resp.json"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        parse_fixed_point_decimal(&arithmetic_twap, 18, "ATOM", "OSMO")
+            .unwrap()
+            .as_quote_to_price_with_decimal_places(
+                "ATOM".into(),
+                6,
+                "OSMO".into(),
+                6,
+            ),
+        Price::new(
+            CoinWithDecimalPlaces::new(
+                1_000_000_000_000_000_000,
+                "ATOM".into(),
+                6,
+            ),
+            CoinWithDecimalPlaces::new(
+                14_823_541_062_750_436_981,
+                "OSMO".into(),
+                6,
+            ),
+        ),
+    );
+
+    let GeometricTwapToNowResponse { geometric_twap } = serde_json::from_str(
+        include_str!("../../../tests/osmosis_geometric_twap_resp.json"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        parse_fixed_point_decimal(&geometric_twap, 18, "OSMO", "ATOM")
+            .unwrap()
+            .as_quote_to_price_with_decimal_places(
+                "OSMO".into(),
+                6,
+                "ATOM".into(),
+                6,
+            ),
+        Price::new(
+            CoinWithDecimalPlaces::new(
+                200_000_000_000_000_000,
+                "OSMO".into(),
+                6
+            ),
+            CoinWithDecimalPlaces::new(
+                13_492_304_187_432_581,
+                "ATOM".into(),
+                6
+            ),
+        ),
+    );
+}
@@ -0,0 +1,374 @@
+use std::{
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use bytes::Bytes;
+use reqwest::{
+    header::RETRY_AFTER, Error as ReqwestError, RequestBuilder, Response,
+    StatusCode,
+};
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::time::sleep;
+use toml::Value;
+
+use chain_comms::config::from_str_or_value;
+
+use crate::config::ProviderConfigExt;
+
+/// Retry policy shared by the HTTP based providers.
+///
+/// Failed requests are retried with an exponentially growing delay when the
+/// failure is transient, i.e. a connection failure, a timeout, a server error
+/// or a rate-limiting response. When the server specifies the delay through
+/// the `Retry-After` header, it takes precedence over the computed one.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct Policy {
+    #[serde(deserialize_with = "from_str_or_value")]
+    max_retries: u8,
+    #[serde(rename = "initial_backoff_millis", with = "millis")]
+    initial_backoff: Duration,
+    #[serde(rename = "max_backoff_millis", with = "millis")]
+    max_backoff: Duration,
+    #[serde(deserialize_with = "from_str_or_value")]
+    request_budget: NonZeroU32,
+}
+
+impl Policy {
+    pub const FIELD: &'static str = "retry";
+
+    /// Extracts the policy from the optional `retry` table of the provider's
+    /// configuration, falling back to the default one when it is missing.
+    pub fn extract<Config, const COMPARISON: bool>(
+        config: &mut Config,
+    ) -> Result<Self, toml::de::Error>
+    where
+        Config: ProviderConfigExt<COMPARISON>,
+    {
+        config
+            .misc_mut()
+            .remove(Self::FIELD)
+            .map_or_else(|| Ok(Self::default()), Value::try_into)
+    }
+
+    /// Creates a budget which is to be shared by all requests, including the
+    /// retried ones, made while serving a single call to the provider.
+    pub fn new_budget(&self) -> Budget {
+        Budget(Arc::new(AtomicU32::new(self.request_budget.get())))
+    }
+
+    /// Sends the request produced by `request`, retrying it as long as the
+    /// failures are transient, there are retries left and the budget is not
+    /// exhausted, and returns the body of the first successful response.
+    pub async fn send<F>(
+        &self,
+        budget: &Budget,
+        request: F,
+    ) -> Result<Bytes, Error>
+    where
+        F: Fn() -> RequestBuilder + Send,
+    {
+        let mut backoff: Duration = self.initial_backoff;
+
+        let mut retries: u8 = 0;
+
+        budget.acquire()?;
+
+        loop {
+            let (error, retry_after): (Error, Option<Duration>) =
+                match send_once(request()).await {
+                    Ok(body) => return Ok(body),
+                    Err(failure) => failure,
+                };
+
+            if retries == self.max_retries || !error.is_transient() {
+                return Err(error);
+            }
+
+            let delay: Duration = retry_after.unwrap_or(backoff);
+
+            if self.max_backoff < delay || budget.acquire().is_err() {
+                return Err(error);
+            }
+
+            tracing::warn!(
+                error = %error,
+                "Request failed! Retrying after {delay} milliseconds.",
+                delay = delay.as_millis(),
+            );
+
+            sleep(delay).await;
+
+            backoff = backoff.saturating_mul(2).min(self.max_backoff);
+
+            retries += 1;
+        }
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            request_budget: NonZeroU32::new(32)
+                .unwrap_or_else(|| unreachable!()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[must_use]
+pub(super) struct Budget(Arc<AtomicU32>);
+
+impl Budget {
+    fn acquire(&self) -> Result<(), Error> {
+        self.0
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |left: u32| {
+                left.checked_sub(1)
+            })
+            .map(drop)
+            .map_err(|_| Error::BudgetExhausted)
+    }
+}
+
+async fn send_once(
+    request: RequestBuilder,
+) -> Result<Bytes, (Error, Option<Duration>)> {
+    let response: Response = request
+        .send()
+        .await
+        .map_err(|error: ReqwestError| (Error::Send(error), None))?;
+
+    let retry_after: Option<Duration> = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value: &str| value.trim().parse().ok())
+        .map(Duration::from_secs);
+
+    response
+        .error_for_status()
+        .map_err(|error: ReqwestError| {
+            (Error::UnsuccessfulStatus(error), retry_after)
+        })?
+        .bytes()
+        .await
+        .map_err(|error: ReqwestError| (Error::ReceiveBody(error), None))
+}
+
+mod millis {
+    use std::time::Duration;
+
+    use serde::Deserializer;
+
+    use chain_comms::config::from_str_or_value;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        from_str_or_value(deserializer).map(Duration::from_millis)
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    #[error("Failed sending request! Cause: {0}")]
+    Send(ReqwestError),
+    #[error("Request returned an unsuccessful status code! Cause: {0}")]
+    UnsuccessfulStatus(ReqwestError),
+    #[error("Failed to receive response body! Cause: {0}")]
+    ReceiveBody(ReqwestError),
+    #[error("Request budget exhausted!")]
+    BudgetExhausted,
+}
+
+impl Error {
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::Send(error) | Self::ReceiveBody(error) => {
+                error.is_connect()
+                    || error.is_timeout()
+                    || error.is_request()
+                    || error.is_body()
+            },
+            Self::UnsuccessfulStatus(error) => {
+                error.status().is_some_and(|status: StatusCode| {
+                    status == StatusCode::TOO_MANY_REQUESTS
+                        || status.is_server_error()
+                })
+            },
+            Self::BudgetExhausted => false,
+        }
+    }
+}
+
+/// Serves the given responses, one per connection, in order and returns the
+/// URL to send requests to along with a counter of the served requests.
+#[cfg(test)]
+fn serve_scripted(
+    responses: &'static [&'static str],
+) -> (String, Arc<AtomicU32>) {
+    use std::{
+        io::{BufRead as _, BufReader, Write as _},
+        net::{TcpListener, TcpStream},
+        thread,
+    };
+
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+
+    let url: String = format!("http://{}/", listener.local_addr().unwrap());
+
+    let served: Arc<AtomicU32> = Arc::new(AtomicU32::new(0));
+
+    thread::spawn({
+        let served: Arc<AtomicU32> = served.clone();
+
+        move || {
+            for (stream, response) in listener.incoming().zip(responses) {
+                let mut stream: TcpStream = stream.unwrap();
+
+                let mut line: String = String::new();
+
+                let mut reader: BufReader<&TcpStream> = BufReader::new(&stream);
+
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+
+                // Counted before responding, so the count is up to date as
+                // soon as the client receives the response.
+                served.fetch_add(1, Ordering::AcqRel);
+
+                write!(
+                    stream,
+                    "{response}\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{{}}",
+                )
+                .unwrap();
+            }
+        }
+    });
+
+    (url, served)
+}
+
+#[cfg(test)]
+const TEST_POLICY: Policy = Policy {
+    max_retries: 2,
+    initial_backoff: Duration::from_millis(1),
+    max_backoff: Duration::from_millis(10),
+    request_budget: match NonZeroU32::new(3) {
+        Some(budget) => budget,
+        None => unreachable!(),
+    },
+};
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_retries_transient_failures() {
+    let (url, served) = serve_scripted(&[
+        "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0",
+        "HTTP/1.1 503 Service Unavailable",
+        "HTTP/1.1 200 OK",
+    ]);
+
+    let client: reqwest::Client = reqwest::Client::new();
+
+    let body: Bytes = TEST_POLICY
+        .send(&TEST_POLICY.new_budget(), || client.get(&url))
+        .await
+        .unwrap();
+
+    assert_eq!(body.as_ref(), b"{}");
+
+    assert_eq!(served.load(Ordering::Acquire), 3);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_does_not_retry_client_errors() {
+    let (url, served) =
+        serve_scripted(&["HTTP/1.1 404 Not Found", "HTTP/1.1 200 OK"]);
+
+    let client: reqwest::Client = reqwest::Client::new();
+
+    assert!(matches!(
+        TEST_POLICY
+            .send(&TEST_POLICY.new_budget(), || client.get(&url))
+            .await,
+        Err(Error::UnsuccessfulStatus(_))
+    ));
+
+    assert_eq!(served.load(Ordering::Acquire), 1);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_gives_up_on_too_long_retry_after() {
+    let (url, served) = serve_scripted(&[
+        "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 60",
+        "HTTP/1.1 200 OK",
+    ]);
+
+    let client: reqwest::Client = reqwest::Client::new();
+
+    assert!(matches!(
+        TEST_POLICY
+            .send(&TEST_POLICY.new_budget(), || client.get(&url))
+            .await,
+        Err(Error::UnsuccessfulStatus(_))
+    ));
+
+    assert_eq!(served.load(Ordering::Acquire), 1);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_shares_budget_between_requests() {
+    let (url, served) = serve_scripted(&[
+        "HTTP/1.1 500 Internal Server Error",
+        "HTTP/1.1 500 Internal Server Error",
+        "HTTP/1.1 200 OK",
+        "HTTP/1.1 200 OK",
+    ]);
+
+    let client: reqwest::Client = reqwest::Client::new();
+
+    let budget: Budget = TEST_POLICY.new_budget();
+
+    TEST_POLICY
+        .send(&budget, || client.get(&url))
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        TEST_POLICY.send(&budget, || client.get(&url)).await,
+        Err(Error::BudgetExhausted)
+    ));
+
+    assert_eq!(served.load(Ordering::Acquire), 3);
+}
+
+#[cfg(test)]
+#[test]
+fn test_deserialize_policy() {
+    let policy: Policy = toml::from_str(
+        "max_retries = 5\ninitial_backoff_millis = 100\nrequest_budget = 10",
+    )
+    .unwrap();
+
+    assert_eq!(policy.max_retries, 5);
+    assert_eq!(policy.initial_backoff, Duration::from_millis(100));
+    assert_eq!(policy.max_backoff, Policy::default().max_backoff);
+    assert_eq!(policy.request_budget.get(), 10);
+
+    assert!(toml::from_str::<Policy>("max_attempts = 5").is_err());
+}
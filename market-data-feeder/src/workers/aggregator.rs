@@ -0,0 +1,349 @@
+use std::{
+    collections::BTreeMap,
+    num::{NonZeroU32, NonZeroUsize},
+};
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use thiserror::Error;
+use tracing::error;
+
+use crate::{
+    config::{AggregationMethod, Ticker},
+    price::{
+        self, pair_of, Coin as _, CoinWithDecimalPlaces, Pair, Price, Ratio,
+        UInt,
+    },
+    provider::{Coverage, DenomMismatch, Provider, ProviderError},
+};
+
+/// Fixed-point scale used when averaging prices.
+const SCALE: u128 = 10_u128.pow(36);
+
+pub(super) struct Source {
+    pub id: Box<str>,
+    pub provider: Box<dyn Provider>,
+    pub weight: NonZeroU32,
+}
+
+/// Provider aggregating the prices returned by several other providers.
+///
+/// Sources which fail are ignored, as long as at least `min_sources` of them
+/// returned a price for a given pair.
+pub(super) struct Aggregator {
+    id: Box<str>,
+    method: AggregationMethod,
+    min_sources: NonZeroUsize,
+    sources: Box<[Source]>,
+}
+
+impl Aggregator {
+    pub const fn new(
+        id: Box<str>,
+        method: AggregationMethod,
+        min_sources: NonZeroUsize,
+        sources: Box<[Source]>,
+    ) -> Self {
+        Self {
+            id,
+            method,
+            min_sources,
+            sources,
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for Aggregator {
+    fn instance_id(&self) -> &str {
+        &self.id
+    }
+
+    async fn get_prices(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<Box<[Price<CoinWithDecimalPlaces>]>, ProviderError> {
+        self.get_prices_with_coverage(fault_tolerant)
+            .await
+            .map(|(prices, _): (_, Option<Coverage>)| prices)
+    }
+
+    /// Coverage of the aggregate is merged from the ones of its sources, so a
+    /// pair is dropped when fewer than `min_sources` of them cover it.
+    async fn get_prices_with_coverage(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<
+        (Box<[Price<CoinWithDecimalPlaces>]>, Option<Coverage>),
+        ProviderError,
+    > {
+        let results: Vec<
+            Result<
+                (Box<[Price<CoinWithDecimalPlaces>]>, Option<Coverage>),
+                ProviderError,
+            >,
+        > = join_all(self.sources.iter().map(|source: &Source| {
+            source.provider.get_prices_with_coverage(fault_tolerant)
+        }))
+        .await;
+
+        let mut samples: BTreeMap<Pair, Vec<Sample>> = BTreeMap::new();
+
+        let mut coverages: Vec<Coverage> = Vec::new();
+
+        let mut failed_sources: usize = 0;
+
+        for (source, result) in self.sources.iter().zip(results) {
+            match result {
+                Ok((prices, coverage)) => {
+                    coverages.extend(coverage);
+
+                    for price in prices.into_vec() {
+                        samples.entry(pair_of(&price)).or_default().push(
+                            Sample {
+                                price,
+                                weight: source.weight,
+                            },
+                        );
+                    }
+                },
+                Err(error) => {
+                    failed_sources += 1;
+
+                    error!(
+                        error = ?error,
+                        "Aggregation source \"{source_id}\" of \"{id}\" failed! Cause: {error}",
+                        source_id = source.id,
+                        id = self.id,
+                    );
+                },
+            }
+        }
+
+        let mut prices: Vec<Price<CoinWithDecimalPlaces>> =
+            Vec::with_capacity(samples.len());
+
+        for ((base, quote), samples) in samples {
+            match aggregate(
+                self.method,
+                self.min_sources,
+                base,
+                quote,
+                &samples,
+            ) {
+                Ok(price) => prices.push(price),
+                Err(error) if fault_tolerant => {
+                    error!(error = %error, "Couldn't aggregate price!");
+                },
+                Err(error) => {
+                    return Err(ProviderError::Specific(
+                        self.id.to_string(),
+                        Box::new(error),
+                    ));
+                },
+            }
+        }
+
+        Ok((
+            prices.into_boxed_slice(),
+            (!coverages.is_empty()).then(|| {
+                Coverage::merge(coverages, failed_sources, self.min_sources)
+            }),
+        ))
+    }
+
+    async fn check_denoms(
+        &self,
+    ) -> Result<Box<[DenomMismatch]>, ProviderError> {
+        let mut mismatches: Vec<DenomMismatch> = Vec::new();
+
+        for source in self.sources.iter() {
+            mismatches.extend(source.provider.check_denoms().await?.into_vec());
+        }
+
+        Ok(mismatches.into_boxed_slice())
+    }
+}
+
+struct Sample {
+    price: Price<CoinWithDecimalPlaces>,
+    weight: NonZeroU32,
+}
+
+fn aggregate(
+    method: AggregationMethod,
+    min_sources: NonZeroUsize,
+    base: Ticker,
+    quote: Ticker,
+    samples: &[Sample],
+) -> Result<Price<CoinWithDecimalPlaces>, Error> {
+    let [first, ..] = samples else {
+        return Err(Error::NotEnoughSources(base, quote, 0, min_sources));
+    };
+
+    if samples.len() < min_sources.get() {
+        return Err(Error::NotEnoughSources(
+            base,
+            quote,
+            samples.len(),
+            min_sources,
+        ));
+    }
+
+    let decimal_places: (u8, u8) = (
+        first.price.amount().decimal_places(),
+        first.price.amount_quote().decimal_places(),
+    );
+
+    let mut values: Vec<(UInt, NonZeroU32)> = Vec::with_capacity(samples.len());
+
+    for Sample { price, weight } in samples {
+        if (
+            price.amount().decimal_places(),
+            price.amount_quote().decimal_places(),
+        ) != decimal_places
+        {
+            return Err(Error::DecimalPlacesMismatch(base, quote));
+        }
+
+        if price.amount().amount() == 0 {
+            return Err(Error::ConstructPrice(
+                base,
+                quote,
+                price::Error::ZeroDenominator,
+            ));
+        }
+
+        values.push((
+            UInt::from(price.amount_quote().amount()) * UInt::from(SCALE)
+                / UInt::from(price.amount().amount()),
+            *weight,
+        ));
+    }
+
+    let (numerator, denominator): (UInt, UInt) = match method {
+        AggregationMethod::Median => {
+            values
+                .sort_unstable_by_key(|&(value, _): &(UInt, NonZeroU32)| value);
+
+            let middle: usize = values.len() / 2;
+
+            if values.len() % 2 == 0 {
+                (
+                    values[middle - 1].0 + values[middle].0,
+                    UInt::from(SCALE) * UInt::TWO,
+                )
+            } else {
+                (values[middle].0, UInt::from(SCALE))
+            }
+        },
+        AggregationMethod::WeightedMean => values.iter().fold(
+            (UInt::ZERO, UInt::ZERO),
+            |(numerator, denominator): (UInt, UInt),
+             &(value, weight): &(UInt, NonZeroU32)| {
+                (
+                    numerator + value * UInt::from(weight.get()),
+                    denominator + UInt::from(SCALE) * UInt::from(weight.get()),
+                )
+            },
+        ),
+    };
+
+    match Ratio::from_big_uints(numerator, denominator) {
+        Ok(ratio) => Ok(ratio.as_quote_to_price_with_decimal_places(
+            base,
+            decimal_places.0,
+            quote,
+            decimal_places.1,
+        )),
+        Err(error) => Err(Error::ConstructPrice(base, quote, error)),
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    #[error("Not enough sources returned a price for \"{0}/{1}\" pair! Returned: {2}; Required: {3}")]
+    NotEnoughSources(Ticker, Ticker, usize, NonZeroUsize),
+    #[error("Sources returned prices with mismatching decimal places for \"{0}/{1}\" pair!")]
+    DecimalPlacesMismatch(Ticker, Ticker),
+    #[error(
+        "Failed to construct aggregated price for \"{0}/{1}\" pair! Cause: {2}"
+    )]
+    ConstructPrice(Ticker, Ticker, price::Error),
+}
+
+#[cfg(test)]
+fn test_samples(prices: &[(u128, u128, u32)]) -> Vec<Sample> {
+    prices
+        .iter()
+        .map(|&(base, quote, weight): &(u128, u128, u32)| Sample {
+            price: Price::new(
+                CoinWithDecimalPlaces::new(base, "OSMO".into(), 6),
+                CoinWithDecimalPlaces::new(quote, "USDC".into(), 6),
+            ),
+            weight: NonZeroU32::new(weight).unwrap(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn test_aggregate(
+    method: AggregationMethod,
+    min_sources: usize,
+    prices: &[(u128, u128, u32)],
+) -> Result<(u128, u128), Error> {
+    aggregate(
+        method,
+        NonZeroUsize::new(min_sources).unwrap(),
+        "OSMO".into(),
+        "USDC".into(),
+        &test_samples(prices),
+    )
+    .map(|price: Price<CoinWithDecimalPlaces>| {
+        (price.amount().amount(), price.amount_quote().amount())
+    })
+}
+
+#[cfg(test)]
+#[test]
+fn test_median() {
+    assert_eq!(
+        test_aggregate(
+            AggregationMethod::Median,
+            2,
+            &[(1, 3, 1), (2, 2, 1), (10, 25, 1)],
+        )
+        .unwrap(),
+        (2, 5)
+    );
+
+    assert_eq!(
+        test_aggregate(AggregationMethod::Median, 2, &[(1, 1, 1), (1, 2, 1)])
+            .unwrap(),
+        (2, 3)
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_weighted_mean() {
+    assert_eq!(
+        test_aggregate(
+            AggregationMethod::WeightedMean,
+            1,
+            &[(1, 1, 3), (1, 2, 1)],
+        )
+        .unwrap(),
+        (4, 5)
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_not_enough_sources() {
+    assert!(matches!(
+        test_aggregate(AggregationMethod::Median, 3, &[(1, 1, 1), (1, 2, 1)]),
+        Err(Error::NotEnoughSources(_, _, 2, min_sources))
+            if min_sources.get() == 3
+    ));
+}
@@ -0,0 +1,599 @@
+use std::{
+    collections::BTreeSet,
+    num::{NonZeroU64, NonZeroUsize},
+    sync::Arc,
+};
+
+use tokio::task::JoinSet;
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{ComparisonFailureAction, ContinuousComparison},
+    deviation::DeviationLimits,
+    history::Event,
+    price::{pair_of, CoinWithDecimalPlaces, Pair, Price},
+    provider::{ComparisonProvider, PriceComparisonGuardError},
+};
+
+pub(super) type ComparisonProviders =
+    Box<[(Arc<str>, Arc<dyn ComparisonProvider>)]>;
+
+/// Benchmarks prices against several comparison providers at once and passes
+/// when at least `quorum` of them agree with the benchmarked prices.
+///
+/// Requiring a quorum, instead of all of them, prevents a single unavailable
+/// comparison provider from blocking the feeding, while requiring more than
+/// one prevents a single compromised one from letting a bad price through.
+pub(super) struct ComparisonGuard {
+    providers: ComparisonProviders,
+    quorum: NonZeroUsize,
+    deviation_limits: Arc<DeviationLimits>,
+    continuous: Option<ContinuousComparison>,
+}
+
+impl ComparisonGuard {
+    pub fn new(
+        providers: ComparisonProviders,
+        quorum: NonZeroUsize,
+        deviation_limits: DeviationLimits,
+        continuous: Option<ContinuousComparison>,
+    ) -> Self {
+        Self {
+            providers,
+            quorum,
+            deviation_limits: Arc::new(deviation_limits),
+            continuous,
+        }
+    }
+
+    /// Converts the guard into one applied while feeding, when configured so.
+    pub fn into_continuous(self) -> Option<ContinuousGuard> {
+        self.continuous.map(
+            |ContinuousComparison {
+                 every_ticks,
+                 on_failure,
+             }: ContinuousComparison| ContinuousGuard {
+                guard: self,
+                every_ticks,
+                on_failure,
+                ticks: 0,
+                verdict: Verdict::Passed,
+                last_check: None,
+            },
+        )
+    }
+
+    pub async fn benchmark_prices(
+        &self,
+        benchmarked_provider_id: &str,
+        prices: &[Price<CoinWithDecimalPlaces>],
+    ) -> Result<(), PriceComparisonGuardError> {
+        let results: BenchmarkResults = self
+            .benchmark_against_each(benchmarked_provider_id, prices)
+            .await;
+
+        self.reach_quorum(benchmarked_provider_id, results)
+    }
+
+    async fn benchmark_against_each(
+        &self,
+        benchmarked_provider_id: &str,
+        prices: &[Price<CoinWithDecimalPlaces>],
+    ) -> BenchmarkResults {
+        if let [(comparison_provider_id, provider)] = &*self.providers {
+            return vec![(
+                comparison_provider_id.clone(),
+                provider
+                    .benchmark_prices(
+                        benchmarked_provider_id,
+                        prices,
+                        &self.deviation_limits,
+                    )
+                    .await,
+            )];
+        }
+
+        let benchmarked_provider_id: Arc<str> = benchmarked_provider_id.into();
+
+        let prices: Arc<[Price<CoinWithDecimalPlaces>]> = prices.into();
+
+        let mut set: JoinSet<(
+            Arc<str>,
+            Result<(), PriceComparisonGuardError>,
+        )> = JoinSet::new();
+
+        for (comparison_provider_id, provider) in self.providers.iter() {
+            let comparison_provider_id: Arc<str> =
+                comparison_provider_id.clone();
+
+            let provider: Arc<dyn ComparisonProvider> = provider.clone();
+
+            let benchmarked_provider_id: Arc<str> =
+                benchmarked_provider_id.clone();
+
+            let prices: Arc<[Price<CoinWithDecimalPlaces>]> = prices.clone();
+
+            let deviation_limits: Arc<DeviationLimits> =
+                self.deviation_limits.clone();
+
+            set.spawn(async move {
+                let result: Result<(), PriceComparisonGuardError> = provider
+                    .benchmark_prices(
+                        &benchmarked_provider_id,
+                        &prices,
+                        &deviation_limits,
+                    )
+                    .await;
+
+                (comparison_provider_id, result)
+            });
+        }
+
+        let mut results: BenchmarkResults =
+            Vec::with_capacity(self.providers.len());
+
+        while let Some(result) = set.join_next().await {
+            match result {
+                Ok(result) => results.push(result),
+                Err(error) => {
+                    error!(
+                        error = ?error,
+                        "Price comparison guard task of \"{benchmarked_provider_id}\" failed! Cause: {error}",
+                    );
+                },
+            }
+        }
+
+        results
+    }
+
+    fn reach_quorum(
+        &self,
+        benchmarked_provider_id: &str,
+        mut results: BenchmarkResults,
+    ) -> Result<(), PriceComparisonGuardError> {
+        if self.providers.len() == 1 {
+            return results
+                .pop()
+                .map_or(Ok(()), |(_, result): BenchmarkResult| result);
+        }
+
+        let mut passed: usize = 0;
+
+        for (comparison_provider_id, result) in results {
+            match result {
+                Ok(()) => passed += 1,
+                Err(error) => {
+                    error!(
+                        error = ?error,
+                        "Price comparison guard check of \"{benchmarked_provider_id}\" failed against \"{comparison_provider_id}\"! Cause: {error}",
+                    );
+                },
+            }
+        }
+
+        if passed < self.quorum.get() {
+            Err(PriceComparisonGuardError::QuorumNotReached(
+                passed,
+                self.quorum,
+            ))
+        } else {
+            info!(
+                "Price comparison guard check of \"{benchmarked_provider_id}\" reached quorum, with {passed} out of {total} comparison providers passing.",
+                total = self.providers.len(),
+            );
+
+            Ok(())
+        }
+    }
+
+    /// Returns the pairs which don't reach quorum on their own, judging by
+    /// the already collected results.
+    fn failed_pairs(
+        &self,
+        prices: &[Price<CoinWithDecimalPlaces>],
+        results: &[BenchmarkResult],
+    ) -> BTreeSet<Pair> {
+        prices
+            .iter()
+            .map(pair_of)
+            .filter(|(base, quote): &Pair| {
+                results
+                    .iter()
+                    .filter(|(_, result): &&BenchmarkResult| {
+                        result.as_ref().map_or_else(
+                            |error: &PriceComparisonGuardError| {
+                                !error.fails_pair(base, quote)
+                            },
+                            |()| true,
+                        )
+                    })
+                    .count()
+                    < self.quorum.get()
+            })
+            .collect()
+    }
+}
+
+type BenchmarkResult = (Arc<str>, Result<(), PriceComparisonGuardError>);
+
+type BenchmarkResults = Vec<BenchmarkResult>;
+
+enum Verdict {
+    Passed,
+    Held,
+    Skipping(BTreeSet<Pair>),
+}
+
+/// Benchmarks the fed prices every configured number of ticks.
+///
+/// The outcome of a check applies until the next one, so held or skipped pairs
+/// are not fed unchecked in between.
+pub(super) struct ContinuousGuard {
+    guard: ComparisonGuard,
+    every_ticks: NonZeroU64,
+    on_failure: ComparisonFailureAction,
+    ticks: u64,
+    verdict: Verdict,
+    last_check: Option<Result<(), String>>,
+}
+
+impl ContinuousGuard {
+    /// Returns the prices which are to be fed on the current tick, or `None`
+    /// when none of them are.
+    pub async fn apply(
+        &mut self,
+        benchmarked_provider_id: &str,
+        prices: Box<[Price<CoinWithDecimalPlaces>]>,
+    ) -> Option<Box<[Price<CoinWithDecimalPlaces>]>> {
+        self.ticks += 1;
+
+        if self.ticks == self.every_ticks.get() {
+            self.ticks = 0;
+
+            let (verdict, result): (Verdict, Result<(), String>) =
+                self.check(benchmarked_provider_id, &prices).await;
+
+            self.verdict = verdict;
+
+            self.last_check = Some(result);
+        }
+
+        match &self.verdict {
+            Verdict::Passed => Some(prices),
+            Verdict::Held => None,
+            Verdict::Skipping(failed) => {
+                let prices: Box<[Price<CoinWithDecimalPlaces>]> =
+                    Vec::from(prices)
+                        .into_iter()
+                        .filter(|price: &Price<CoinWithDecimalPlaces>| {
+                            !failed.contains(&pair_of(price))
+                        })
+                        .collect();
+
+                (!prices.is_empty()).then_some(prices)
+            },
+        }
+    }
+
+    /// Returns the outcome of the check made on the last tick, if one was
+    /// made.
+    pub fn take_check_outcome(&mut self) -> Option<Event> {
+        self.last_check.take().map(|result: Result<(), String>| {
+            Event::Compared {
+                passed: result.is_ok(),
+                skipped_pairs: if let Verdict::Skipping(failed) = &self.verdict
+                {
+                    failed
+                        .iter()
+                        .map(|(base, quote): &Pair| format!("{base}/{quote}"))
+                        .collect()
+                } else {
+                    Vec::new()
+                },
+                error: result.err(),
+            }
+        })
+    }
+
+    /// Benchmarks the prices once, deriving the pairs to skip, if any, out
+    /// of the same results.
+    async fn check(
+        &self,
+        benchmarked_provider_id: &str,
+        prices: &[Price<CoinWithDecimalPlaces>],
+    ) -> (Verdict, Result<(), String>) {
+        let results: BenchmarkResults = self
+            .guard
+            .benchmark_against_each(benchmarked_provider_id, prices)
+            .await;
+
+        let failed: BTreeSet<Pair> = match self.on_failure {
+            ComparisonFailureAction::Hold => BTreeSet::new(),
+            ComparisonFailureAction::Skip => {
+                self.guard.failed_pairs(prices, &results)
+            },
+        };
+
+        let Err(error) =
+            self.guard.reach_quorum(benchmarked_provider_id, results)
+        else {
+            return (Verdict::Passed, Ok(()));
+        };
+
+        let verdict: Verdict = match self.on_failure {
+            ComparisonFailureAction::Hold => {
+                error!(
+                    error = ?error,
+                    "Price comparison guard check of \"{benchmarked_provider_id}\" failed! Holding feed until the next check. Cause: {error}",
+                );
+
+                Verdict::Held
+            },
+            ComparisonFailureAction::Skip => {
+                warn!(
+                    error = ?error,
+                    "Price comparison guard check of \"{benchmarked_provider_id}\" failed! Skipping failing pairs. Cause: {error}",
+                );
+
+                for (base, quote) in &failed {
+                    error!(
+                        "Price comparison guard check of \"{benchmarked_provider_id}\" failed for pair \"{base}/{quote}\"! Skipping pair until the next check.",
+                    );
+                }
+
+                Verdict::Skipping(failed)
+            },
+        };
+
+        (verdict, Err(error.to_string()))
+    }
+}
+
+#[cfg(test)]
+struct StubComparisonProvider(bool);
+
+#[cfg(test)]
+#[async_trait::async_trait]
+impl ComparisonProvider for StubComparisonProvider {
+    async fn benchmark_prices(
+        &self,
+        _: &str,
+        _: &[Price<CoinWithDecimalPlaces>],
+        _: &DeviationLimits,
+    ) -> Result<(), PriceComparisonGuardError> {
+        if self.0 {
+            Ok(())
+        } else {
+            Err(PriceComparisonGuardError::MissingComparisonPrice(
+                String::from("A"),
+                String::from("B"),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+fn stub_guard(outcomes: &[bool], quorum: usize) -> ComparisonGuard {
+    ComparisonGuard::new(
+        outcomes
+            .iter()
+            .enumerate()
+            .map(|(index, &outcome): (usize, &bool)| {
+                (
+                    Arc::from(index.to_string()),
+                    Arc::new(StubComparisonProvider(outcome))
+                        as Arc<dyn ComparisonProvider>,
+                )
+            })
+            .collect(),
+        NonZeroUsize::new(quorum).unwrap(),
+        DeviationLimits::new(
+            500,
+            std::collections::BTreeMap::new(),
+            std::collections::BTreeMap::new(),
+        ),
+        None,
+    )
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_quorum_reached() {
+    stub_guard(&[true, false, true], 2)
+        .benchmark_prices("test", &[])
+        .await
+        .unwrap();
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_quorum_not_reached() {
+    assert!(matches!(
+        stub_guard(&[true, false, false], 2)
+            .benchmark_prices("test", &[])
+            .await,
+        Err(PriceComparisonGuardError::QuorumNotReached(1, quorum))
+            if quorum.get() == 2
+    ));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_single_provider_error_is_forwarded() {
+    assert!(matches!(
+        stub_guard(&[false], 1).benchmark_prices("test", &[]).await,
+        Err(PriceComparisonGuardError::MissingComparisonPrice(..))
+    ));
+}
+
+#[cfg(test)]
+struct RejectingComparisonProvider(
+    &'static str,
+    std::sync::atomic::AtomicUsize,
+);
+
+#[cfg(test)]
+#[async_trait::async_trait]
+impl ComparisonProvider for RejectingComparisonProvider {
+    async fn benchmark_prices(
+        &self,
+        _: &str,
+        prices: &[Price<CoinWithDecimalPlaces>],
+        _: &DeviationLimits,
+    ) -> Result<(), PriceComparisonGuardError> {
+        use crate::price::Coin as _;
+
+        self.1.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+        prices
+            .iter()
+            .find(|price: &&Price<CoinWithDecimalPlaces>| {
+                price.amount().ticker() == self.0
+            })
+            .map_or(Ok(()), |price: &Price<CoinWithDecimalPlaces>| {
+                Err(PriceComparisonGuardError::MissingComparisonPrice(
+                    price.amount().ticker().to_string(),
+                    price.amount_quote().ticker().to_string(),
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+fn rejecting_continuous_guard(
+    rejected: &'static str,
+    every_ticks: u64,
+    on_failure: ComparisonFailureAction,
+) -> ContinuousGuard {
+    ComparisonGuard::new(
+        Box::new([(
+            Arc::from("test"),
+            Arc::new(RejectingComparisonProvider(
+                rejected,
+                std::sync::atomic::AtomicUsize::default(),
+            )) as Arc<dyn ComparisonProvider>,
+        )]),
+        NonZeroUsize::MIN,
+        DeviationLimits::new(
+            500,
+            std::collections::BTreeMap::new(),
+            std::collections::BTreeMap::new(),
+        ),
+        Some(ContinuousComparison {
+            every_ticks: NonZeroU64::new(every_ticks).unwrap(),
+            on_failure,
+        }),
+    )
+    .into_continuous()
+    .unwrap()
+}
+
+#[cfg(test)]
+fn test_prices() -> Box<[Price<CoinWithDecimalPlaces>]> {
+    [("ATOM", "USDC"), ("OSMO", "USDC")]
+        .into_iter()
+        .map(|(base, quote): (&str, &str)| {
+            Price::new(
+                CoinWithDecimalPlaces::new(1, base.into(), 6),
+                CoinWithDecimalPlaces::new(1, quote.into(), 6),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_continuous_guard_skips_failing_pairs() {
+    use crate::price::Coin as _;
+
+    let mut guard: ContinuousGuard =
+        rejecting_continuous_guard("OSMO", 1, ComparisonFailureAction::Skip);
+
+    let prices: Box<[Price<CoinWithDecimalPlaces>]> =
+        guard.apply("test", test_prices()).await.unwrap();
+
+    assert_eq!(prices.len(), 1);
+    assert_eq!(prices[0].amount().ticker(), "ATOM");
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_continuous_guard_holds_feed() {
+    let mut guard: ContinuousGuard =
+        rejecting_continuous_guard("OSMO", 1, ComparisonFailureAction::Hold);
+
+    assert!(guard.apply("test", test_prices()).await.is_none());
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_continuous_guard_verdict_lasts_until_next_check() {
+    let mut guard: ContinuousGuard =
+        rejecting_continuous_guard("OSMO", 2, ComparisonFailureAction::Hold);
+
+    assert_eq!(guard.apply("test", test_prices()).await.unwrap().len(), 2);
+    assert!(guard.apply("test", test_prices()).await.is_none());
+    assert!(guard.apply("test", test_prices()).await.is_none());
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_continuous_guard_benchmarks_once_per_check() {
+    use std::sync::atomic::Ordering;
+
+    let providers: [Arc<RejectingComparisonProvider>; 2] = [
+        Arc::new(RejectingComparisonProvider(
+            "ATOM",
+            std::sync::atomic::AtomicUsize::default(),
+        )),
+        Arc::new(RejectingComparisonProvider(
+            "OSMO",
+            std::sync::atomic::AtomicUsize::default(),
+        )),
+    ];
+
+    let mut guard: ContinuousGuard = ComparisonGuard::new(
+        providers
+            .iter()
+            .enumerate()
+            .map(
+                |(index, provider): (
+                    usize,
+                    &Arc<RejectingComparisonProvider>,
+                )| {
+                    (
+                        Arc::from(index.to_string()),
+                        provider.clone() as Arc<dyn ComparisonProvider>,
+                    )
+                },
+            )
+            .collect(),
+        NonZeroUsize::MIN,
+        DeviationLimits::new(
+            500,
+            std::collections::BTreeMap::new(),
+            std::collections::BTreeMap::new(),
+        ),
+        Some(ContinuousComparison {
+            every_ticks: NonZeroU64::MIN,
+            on_failure: ComparisonFailureAction::Skip,
+        }),
+    )
+    .into_continuous()
+    .unwrap();
+
+    // Neither provider passes all of the pairs, while each pair is passed by
+    // one of them, which is enough to reach the quorum.
+    assert_eq!(guard.apply("test", test_prices()).await.unwrap().len(), 2);
+
+    assert!(matches!(
+        guard.take_check_outcome(),
+        Some(Event::Compared { passed: false, skipped_pairs, .. })
+            if skipped_pairs.is_empty()
+    ));
+
+    for provider in &providers {
+        assert_eq!(provider.1.load(Ordering::Relaxed), 1);
+    }
+}
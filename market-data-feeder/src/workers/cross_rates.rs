@@ -0,0 +1,501 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroUsize,
+};
+
+use async_trait::async_trait;
+use thiserror::Error;
+use tracing::warn;
+
+use crate::{
+    config::{CrossRates as CrossRatesConfig, Ticker, TickerUnsized},
+    price::{
+        self, pair_of, Coin as _, CoinWithDecimalPlaces, Pair, Price, UInt,
+    },
+    provider::{Coverage, DenomMismatch, Provider, ProviderError},
+};
+
+/// Provider deriving the pairs requested by the oracle, which the wrapped one
+/// doesn't return, out of the prices it returned on the same tick.
+///
+/// Prices are chained, inverting them as needed, along the path with the
+/// fewest hops. Every step is computed exactly and reduced by the greatest
+/// common divisor. Paths whose reduced amounts don't fit in 128 bits are
+/// skipped, instead of losing precision.
+pub(super) struct CrossRates<P> {
+    provider: P,
+    max_hops: NonZeroUsize,
+}
+
+impl<P> CrossRates<P> {
+    pub const fn new(
+        provider: P,
+        CrossRatesConfig { max_hops }: CrossRatesConfig,
+    ) -> Self {
+        Self { provider, max_hops }
+    }
+}
+
+#[async_trait]
+impl<P> Provider for CrossRates<P>
+where
+    P: Provider,
+{
+    fn instance_id(&self) -> &str {
+        self.provider.instance_id()
+    }
+
+    async fn get_prices(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<Box<[Price<CoinWithDecimalPlaces>]>, ProviderError> {
+        self.get_prices_with_coverage(fault_tolerant)
+            .await
+            .map(|(prices, _): (_, Option<Coverage>)| prices)
+    }
+
+    /// The pairs to derive are the ones requested by the oracle, as reported
+    /// through the wrapped provider's coverage. Coverage is returned as it
+    /// is, as derived pairs are accounted for once the prices are, see
+    /// [`Coverage::with_prices`].
+    async fn get_prices_with_coverage(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<
+        (Box<[Price<CoinWithDecimalPlaces>]>, Option<Coverage>),
+        ProviderError,
+    > {
+        let (fetched, coverage): (
+            Box<[Price<CoinWithDecimalPlaces>]>,
+            Option<Coverage>,
+        ) = self
+            .provider
+            .get_prices_with_coverage(fault_tolerant)
+            .await?;
+
+        let Some(coverage) = coverage else {
+            return Ok((fetched, None));
+        };
+
+        let fetched_pairs: BTreeSet<Pair> =
+            fetched.iter().map(pair_of).collect();
+
+        let graph: Graph<'_> = Graph::new(&fetched);
+
+        let mut prices: Vec<Price<CoinWithDecimalPlaces>> = Vec::new();
+
+        for (base, quote) in coverage
+            .requested()
+            .filter(|&pair: &&Pair| !fetched_pairs.contains(pair))
+        {
+            match graph.derive(base, quote, self.max_hops) {
+                Ok(price) => prices.push(price),
+                Err(error) if fault_tolerant => {
+                    warn!(
+                        provider_id = self.instance_id(),
+                        "Couldn't derive cross rate! Cause: {error}",
+                    );
+                },
+                Err(error) => {
+                    return Err(ProviderError::Specific(
+                        self.instance_id().to_string(),
+                        Box::new(error),
+                    ));
+                },
+            }
+        }
+
+        let mut fetched: Vec<Price<CoinWithDecimalPlaces>> = fetched.into_vec();
+
+        fetched.append(&mut prices);
+
+        Ok((fetched.into_boxed_slice(), Some(coverage)))
+    }
+
+    async fn check_denoms(
+        &self,
+    ) -> Result<Box<[DenomMismatch]>, ProviderError> {
+        self.provider.check_denoms().await
+    }
+}
+
+/// Fetched prices, indexed by both of their tickers.
+struct Graph<'r> {
+    edges: BTreeMap<&'r TickerUnsized, Vec<Edge<'r>>>,
+}
+
+#[derive(Clone, Copy)]
+struct Edge<'r> {
+    price: &'r Price<CoinWithDecimalPlaces>,
+    inverted: bool,
+}
+
+impl<'r> Edge<'r> {
+    /// Coin of the ticker the edge leads from.
+    const fn from(self) -> &'r CoinWithDecimalPlaces {
+        if self.inverted {
+            self.price.amount_quote()
+        } else {
+            self.price.amount()
+        }
+    }
+
+    /// Coin of the ticker the edge leads to.
+    const fn to(self) -> &'r CoinWithDecimalPlaces {
+        if self.inverted {
+            self.price.amount()
+        } else {
+            self.price.amount_quote()
+        }
+    }
+}
+
+impl<'r> Graph<'r> {
+    fn new(prices: &'r [Price<CoinWithDecimalPlaces>]) -> Self {
+        let mut edges: BTreeMap<&'r TickerUnsized, Vec<Edge<'r>>> =
+            BTreeMap::new();
+
+        for price in prices {
+            if price.amount().amount() == 0
+                || price.amount_quote().amount() == 0
+            {
+                continue;
+            }
+
+            for inverted in [false, true] {
+                let edge: Edge<'r> = Edge { price, inverted };
+
+                edges.entry(edge.from().ticker()).or_default().push(edge);
+            }
+        }
+
+        Self { edges }
+    }
+
+    /// Breadth-first search for the path with the fewest hops, composing the
+    /// price leading to each reached ticker along the way.
+    ///
+    /// Paths whose prices can't be composed are skipped. The first such
+    /// failure is returned only when no other path leads to the quote.
+    fn derive(
+        &self,
+        base: &TickerUnsized,
+        quote: &TickerUnsized,
+        max_hops: NonZeroUsize,
+    ) -> Result<Price<CoinWithDecimalPlaces>, Error> {
+        let mut visited: BTreeSet<&TickerUnsized> = BTreeSet::from([base]);
+
+        let mut failure: Option<Error> = None;
+
+        let mut frontier: Vec<Price<CoinWithDecimalPlaces>> = self
+            .edges(base)
+            .map(|edge: Edge<'_>| {
+                Price::new(edge.from().clone(), edge.to().clone())
+            })
+            .collect();
+
+        for hop in 1..=max_hops.get() {
+            let mut next: Vec<Price<CoinWithDecimalPlaces>> = Vec::new();
+
+            for price in frontier {
+                let reached: &TickerUnsized = price.amount_quote().ticker();
+
+                if reached == quote {
+                    return Ok(price);
+                }
+
+                let Some(reached) = self
+                    .edges
+                    .get_key_value(reached)
+                    .map(|(&reached, _): (&&TickerUnsized, _)| reached)
+                else {
+                    continue;
+                };
+
+                if visited.contains(reached) || hop == max_hops.get() {
+                    continue;
+                }
+
+                let mut composed_all: bool = true;
+
+                for edge in self.edges(reached) {
+                    match compose(&price, edge) {
+                        Ok(price) => next.push(price),
+                        Err(error) => {
+                            composed_all = false;
+
+                            failure.get_or_insert(error);
+                        },
+                    }
+                }
+
+                // Another path reaching the same ticker may still succeed
+                // where this one didn't.
+                if composed_all {
+                    visited.insert(reached);
+                }
+            }
+
+            frontier = next;
+        }
+
+        Err(failure.unwrap_or_else(|| {
+            Error::NoPath(base.into(), quote.into(), max_hops)
+        }))
+    }
+
+    fn edges(
+        &self,
+        ticker: &TickerUnsized,
+    ) -> impl Iterator<Item = Edge<'r>> + '_ {
+        self.edges
+            .get(ticker)
+            .into_iter()
+            .flat_map(|edges: &Vec<Edge<'r>>| edges.iter().copied())
+    }
+}
+
+/// Chains `A/B` with the `B/C` edge into `A/C`, reducing the result by the
+/// greatest common divisor of its amounts.
+fn compose(
+    price: &Price<CoinWithDecimalPlaces>,
+    edge: Edge<'_>,
+) -> Result<Price<CoinWithDecimalPlaces>, Error> {
+    let (base, quote): (&CoinWithDecimalPlaces, &CoinWithDecimalPlaces) =
+        (price.amount(), edge.to());
+
+    let amount: UInt =
+        UInt::from(base.amount()) * UInt::from(edge.from().amount());
+
+    let amount_quote: UInt =
+        UInt::from(price.amount_quote().amount()) * UInt::from(quote.amount());
+
+    let gcd: UInt = price::gcd(amount, amount_quote);
+
+    let construct_error = |error: price::Error| {
+        Error::ConstructPrice(
+            base.ticker().into(),
+            quote.ticker().into(),
+            error,
+        )
+    };
+
+    let amount: u128 = (amount / gcd)
+        .try_into()
+        .map_err(|_| construct_error(price::Error::DenominatorTooBig))?;
+
+    let amount_quote: u128 = (amount_quote / gcd)
+        .try_into()
+        .map_err(|_| construct_error(price::Error::NumeratorTooBig))?;
+
+    Ok(Price::new(
+        CoinWithDecimalPlaces::new(
+            amount,
+            base.ticker().into(),
+            base.decimal_places(),
+        ),
+        CoinWithDecimalPlaces::new(
+            amount_quote,
+            quote.ticker().into(),
+            quote.decimal_places(),
+        ),
+    ))
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    #[error(
+        "No path of at most {2} hops found to derive \"{0}/{1}\" pair from!"
+    )]
+    NoPath(Ticker, Ticker, NonZeroUsize),
+    #[error("Failed to construct cross rate of \"{0}/{1}\" pair! Cause: {2}")]
+    ConstructPrice(Ticker, Ticker, price::Error),
+}
+
+#[cfg(test)]
+fn test_prices() -> [Price<CoinWithDecimalPlaces>; 2] {
+    [
+        Price::new(
+            CoinWithDecimalPlaces::new(1_000_000, "ATOM".into(), 6),
+            CoinWithDecimalPlaces::new(20_000_000, "OSMO".into(), 6),
+        ),
+        Price::new(
+            CoinWithDecimalPlaces::new(1_000_000, "OSMO".into(), 6),
+            CoinWithDecimalPlaces::new(500_000, "USDC".into(), 6),
+        ),
+    ]
+}
+
+#[cfg(test)]
+fn test_derive(
+    base: &str,
+    quote: &str,
+    max_hops: usize,
+) -> Result<(u128, u128), Error> {
+    let prices: [Price<CoinWithDecimalPlaces>; 2] = test_prices();
+
+    Graph::new(&prices)
+        .derive(base, quote, NonZeroUsize::new(max_hops).unwrap())
+        .map(|price: Price<CoinWithDecimalPlaces>| {
+            assert_eq!(pair_of(&price), (base.into(), quote.into()));
+
+            (price.amount().amount(), price.amount_quote().amount())
+        })
+}
+
+#[cfg(test)]
+#[test]
+fn test_derives_cross_rate() {
+    assert_eq!(test_derive("ATOM", "USDC", 2).unwrap(), (1, 10));
+}
+
+#[cfg(test)]
+#[test]
+fn test_derives_inverted_cross_rate() {
+    assert_eq!(test_derive("USDC", "ATOM", 2).unwrap(), (10, 1));
+    assert_eq!(
+        test_derive("OSMO", "ATOM", 1).unwrap(),
+        (20_000_000, 1_000_000)
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_no_path_within_max_hops() {
+    assert!(matches!(
+        test_derive("ATOM", "USDC", 1),
+        Err(Error::NoPath(_, _, _))
+    ));
+
+    assert!(matches!(
+        test_derive("ATOM", "NLS", 3),
+        Err(Error::NoPath(_, _, _))
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_compose_fails_instead_of_truncating() {
+    let price: Price<CoinWithDecimalPlaces> = Price::new(
+        CoinWithDecimalPlaces::new(u128::MAX, "ATOM".into(), 6),
+        CoinWithDecimalPlaces::new(u128::MAX - 1, "OSMO".into(), 6),
+    );
+
+    let edge: Price<CoinWithDecimalPlaces> = Price::new(
+        CoinWithDecimalPlaces::new(3, "OSMO".into(), 6),
+        CoinWithDecimalPlaces::new(7, "USDC".into(), 6),
+    );
+
+    assert!(matches!(
+        compose(
+            &price,
+            Edge {
+                price: &edge,
+                inverted: false,
+            },
+        ),
+        Err(Error::ConstructPrice(_, _, price::Error::DenominatorTooBig))
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_skips_paths_which_fail_to_compose() {
+    let prices: [Price<CoinWithDecimalPlaces>; 4] = [
+        Price::new(
+            CoinWithDecimalPlaces::new(u128::MAX, "ATOM".into(), 6),
+            CoinWithDecimalPlaces::new(u128::MAX - 1, "OSMO".into(), 6),
+        ),
+        Price::new(
+            CoinWithDecimalPlaces::new(3, "OSMO".into(), 6),
+            CoinWithDecimalPlaces::new(7, "USDC".into(), 6),
+        ),
+        Price::new(
+            CoinWithDecimalPlaces::new(1, "ATOM".into(), 6),
+            CoinWithDecimalPlaces::new(2, "NLS".into(), 6),
+        ),
+        Price::new(
+            CoinWithDecimalPlaces::new(1, "NLS".into(), 6),
+            CoinWithDecimalPlaces::new(5, "USDC".into(), 6),
+        ),
+    ];
+
+    let max_hops: NonZeroUsize = NonZeroUsize::new(2).unwrap();
+
+    let price: Price<CoinWithDecimalPlaces> = Graph::new(&prices)
+        .derive("ATOM", "USDC", max_hops)
+        .unwrap();
+
+    assert_eq!(
+        (price.amount().amount(), price.amount_quote().amount()),
+        (1, 10)
+    );
+
+    // Without the valid path, the failure to compose is returned.
+    assert!(matches!(
+        Graph::new(&prices[..2]).derive("ATOM", "USDC", max_hops),
+        Err(Error::ConstructPrice(_, _, price::Error::DenominatorTooBig))
+    ));
+}
+
+#[cfg(test)]
+struct TestProvider;
+
+#[cfg(test)]
+#[async_trait]
+impl Provider for TestProvider {
+    fn instance_id(&self) -> &'static str {
+        "test"
+    }
+
+    async fn get_prices(
+        &self,
+        _: bool,
+    ) -> Result<Box<[Price<CoinWithDecimalPlaces>]>, ProviderError> {
+        Ok(test_prices().into())
+    }
+
+    async fn get_prices_with_coverage(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<
+        (Box<[Price<CoinWithDecimalPlaces>]>, Option<Coverage>),
+        ProviderError,
+    > {
+        let mut coverage: Coverage = Coverage::new();
+
+        coverage.request("ATOM", "OSMO");
+        coverage.request("ATOM", "USDC");
+        coverage.request("USDC", "ATOM");
+
+        self.get_prices(fault_tolerant).await.map(
+            |prices: Box<[Price<CoinWithDecimalPlaces>]>| {
+                (prices, Some(coverage))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_derives_requested_pairs() {
+    let provider: CrossRates<TestProvider> = CrossRates::new(
+        TestProvider,
+        CrossRatesConfig {
+            max_hops: NonZeroUsize::new(2).unwrap(),
+        },
+    );
+
+    let (prices, _): (_, Option<Coverage>) =
+        provider.get_prices_with_coverage(false).await.unwrap();
+
+    assert_eq!(
+        prices.iter().map(pair_of).collect::<Vec<Pair>>(),
+        [
+            ("ATOM".into(), "OSMO".into()),
+            ("OSMO".into(), "USDC".into()),
+            ("ATOM".into(), "USDC".into()),
+            ("USDC".into(), "ATOM".into()),
+        ]
+    );
+}
@@ -0,0 +1,302 @@
+use std::collections::BTreeMap;
+
+use tokio::time::Instant;
+
+use chain_comms::interact::TxHash;
+
+use crate::{
+    config::FeedingPolicy,
+    deviation,
+    price::{pair_of, CoinWithDecimalPlaces, Pair, Price, UInt},
+};
+
+/// Tracks the last fed price of each pair, so that only the pairs which are
+/// due according to the feeding policy, when one is set, are fed.
+///
+/// A feed counts as the last one of its pairs once its transaction is
+/// delivered successfully. Until then, while it is in flight, it is used as
+/// the reference instead, so pairs are not fed repeatedly while waiting.
+pub(super) struct FeedTracker {
+    policy: Option<FeedingPolicy>,
+    confirmed: BTreeMap<Pair, (Price<CoinWithDecimalPlaces>, Instant)>,
+    sent: BTreeMap<u64, SentPrices>,
+    committed: BTreeMap<String, Box<[Price<CoinWithDecimalPlaces>]>>,
+}
+
+/// Prices sent to be fed, along with the expiration of their request.
+type SentPrices = (Box<[Price<CoinWithDecimalPlaces>]>, Instant);
+
+impl FeedTracker {
+    pub const fn new(policy: Option<FeedingPolicy>) -> Self {
+        Self {
+            policy,
+            confirmed: BTreeMap::new(),
+            sent: BTreeMap::new(),
+            committed: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the prices which are due to be fed, or `None` when none of
+    /// them are.
+    pub fn select(
+        &mut self,
+        prices: Box<[Price<CoinWithDecimalPlaces>]>,
+        now: Instant,
+    ) -> Option<Box<[Price<CoinWithDecimalPlaces>]>> {
+        let Some(policy) = self.policy else {
+            return (!prices.is_empty()).then_some(prices);
+        };
+
+        self.sent
+            .retain(|_, &mut (_, expiration): &mut SentPrices| {
+                now < expiration
+            });
+
+        let in_flight: BTreeMap<Pair, &Price<CoinWithDecimalPlaces>> = self
+            .committed
+            .values()
+            .chain(self.sent.values().map(|(prices, _)| prices))
+            .map(AsRef::as_ref)
+            .flat_map(|prices: &[Price<CoinWithDecimalPlaces>]| prices)
+            .map(|price: &Price<CoinWithDecimalPlaces>| (pair_of(price), price))
+            .collect();
+
+        let prices: Box<[Price<CoinWithDecimalPlaces>]> = Vec::from(prices)
+            .into_iter()
+            .filter(|price: &Price<CoinWithDecimalPlaces>| {
+                let pair: Pair = pair_of(price);
+
+                if let Some(&reference) = in_flight.get(&pair) {
+                    moved(policy, price, reference)
+                } else if let Some((reference, fed_at)) =
+                    self.confirmed.get(&pair)
+                {
+                    policy.heartbeat <= now - *fed_at
+                        || moved(policy, price, reference)
+                } else {
+                    true
+                }
+            })
+            .collect();
+
+        (!prices.is_empty()).then_some(prices)
+    }
+
+    pub fn on_sent(
+        &mut self,
+        request_id: u64,
+        prices: Box<[Price<CoinWithDecimalPlaces>]>,
+        expiration: Instant,
+    ) {
+        self.sent.insert(request_id, (prices, expiration));
+    }
+
+    /// Tracks the prices of the request with the given identifier until its
+    /// transaction is delivered, if it was committed.
+    ///
+    /// Outcomes are sent back in the order in which the requests were sent,
+    /// so earlier requests, which are still tracked as sent, were never
+    /// committed and are dropped.
+    pub fn on_commit_result(
+        &mut self,
+        request_id: u64,
+        tx_hash: Option<&TxHash>,
+    ) {
+        self.sent = self.sent.split_off(&request_id);
+
+        if let (Some((prices, _)), Some(tx_hash)) =
+            (self.sent.remove(&request_id), tx_hash)
+        {
+            self.committed.insert(tx_hash.0.clone(), prices);
+        }
+    }
+
+    pub fn on_delivered(
+        &mut self,
+        tx_hash: &TxHash,
+        succeeded: bool,
+        now: Instant,
+    ) {
+        let Some(prices) = self.committed.remove(&tx_hash.0) else {
+            return;
+        };
+
+        if succeeded {
+            self.confirmed.extend(prices.into_vec().into_iter().map(
+                |price: Price<CoinWithDecimalPlaces>| {
+                    (pair_of(&price), (price, now))
+                },
+            ));
+        }
+    }
+
+    /// Returns the price of the pair which was last fed successfully, along
+    /// with the time its delivery was confirmed.
+    pub fn last_confirmed(
+        &self,
+        pair: &Pair,
+    ) -> Option<(&Price<CoinWithDecimalPlaces>, Instant)> {
+        self.confirmed.get(pair).map(
+            |(price, fed_at): &(Price<CoinWithDecimalPlaces>, Instant)| {
+                (price, *fed_at)
+            },
+        )
+    }
+}
+
+fn moved(
+    policy: FeedingPolicy,
+    price: &Price<CoinWithDecimalPlaces>,
+    reference: &Price<CoinWithDecimalPlaces>,
+) -> bool {
+    UInt::from_digit(policy.deviation)
+        <= deviation::deviation_between(price, reference)
+}
+
+#[cfg(test)]
+pub(super) fn test_prices(
+    prices: &[(&str, u128)],
+) -> Box<[Price<CoinWithDecimalPlaces>]> {
+    prices
+        .iter()
+        .map(|&(base, amount_quote): &(&str, u128)| {
+            Price::new(
+                CoinWithDecimalPlaces::new(10_000, base.into(), 6),
+                CoinWithDecimalPlaces::new(amount_quote, "USDC".into(), 6),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(super) fn test_tracker(now: Instant) -> FeedTracker {
+    let mut tracker: FeedTracker = FeedTracker::new(Some(FeedingPolicy {
+        deviation: 50,
+        heartbeat: std::time::Duration::from_secs(60),
+    }));
+
+    let tx_hash: TxHash = TxHash(String::from("hash"));
+
+    tracker.on_sent(
+        0,
+        test_prices(&[("ATOM", 10_000), ("OSMO", 10_000)]),
+        now + std::time::Duration::from_secs(5),
+    );
+
+    tracker.on_commit_result(0, Some(&tx_hash));
+
+    tracker.on_delivered(&tx_hash, true, now);
+
+    tracker
+}
+
+#[cfg(test)]
+#[test]
+fn test_feeds_only_moved_pairs() {
+    use crate::price::Coin as _;
+
+    let now: Instant = Instant::now();
+
+    let mut tracker: FeedTracker = test_tracker(now);
+
+    assert!(tracker
+        .select(test_prices(&[("ATOM", 10_049), ("OSMO", 9_951)]), now)
+        .is_none());
+
+    let prices: Box<[Price<CoinWithDecimalPlaces>]> = tracker
+        .select(
+            test_prices(&[("ATOM", 10_050), ("OSMO", 10_000), ("NLS", 1)]),
+            now,
+        )
+        .unwrap();
+
+    assert_eq!(prices.len(), 2);
+    assert_eq!(prices[0].amount().ticker(), "ATOM");
+    assert_eq!(prices[1].amount().ticker(), "NLS");
+}
+
+#[cfg(test)]
+#[test]
+fn test_feeds_on_heartbeat() {
+    let now: Instant = Instant::now();
+
+    let mut tracker: FeedTracker = test_tracker(now);
+
+    assert_eq!(
+        tracker
+            .select(
+                test_prices(&[("ATOM", 10_000), ("OSMO", 10_000)]),
+                now + std::time::Duration::from_secs(60),
+            )
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_in_flight_feed_is_reference() {
+    let now: Instant = Instant::now();
+
+    let mut tracker: FeedTracker = test_tracker(now);
+
+    tracker.on_sent(
+        1,
+        test_prices(&[("ATOM", 11_000)]),
+        now + std::time::Duration::from_secs(5),
+    );
+
+    assert!(tracker
+        .select(test_prices(&[("ATOM", 11_000)]), now)
+        .is_none());
+
+    assert!(tracker
+        .select(
+            test_prices(&[("ATOM", 11_000)]),
+            now + std::time::Duration::from_secs(5),
+        )
+        .is_some());
+}
+
+#[cfg(test)]
+#[test]
+fn test_late_commit_is_bound_to_its_own_prices() {
+    use crate::price::Coin as _;
+
+    let now: Instant = Instant::now();
+
+    let mut tracker: FeedTracker = test_tracker(now);
+
+    let expiration: Instant = now + std::time::Duration::from_secs(5);
+
+    tracker.on_sent(1, test_prices(&[("ATOM", 11_000)]), expiration);
+
+    tracker.on_sent(2, test_prices(&[("ATOM", 12_000)]), expiration);
+
+    let tx_hash: TxHash = TxHash(String::from("late"));
+
+    tracker.on_commit_result(1, Some(&tx_hash));
+
+    tracker.on_delivered(&tx_hash, true, now);
+
+    assert_eq!(
+        tracker
+            .last_confirmed(&(String::from("ATOM"), String::from("USDC")))
+            .map(|(price, _): (&Price<CoinWithDecimalPlaces>, Instant)| {
+                price.amount_quote().amount()
+            }),
+        Some(11_000)
+    );
+
+    // The second request is still in flight.
+    assert!(tracker
+        .select(test_prices(&[("ATOM", 12_000)]), now)
+        .is_none());
+
+    tracker.on_commit_result(2, None);
+
+    assert!(tracker
+        .select(test_prices(&[("ATOM", 12_000)]), now)
+        .is_some());
+}
@@ -0,0 +1,281 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    path::PathBuf,
+    time::SystemTime,
+};
+
+use tokio::{fs, time::Instant};
+use tracing::{error, warn};
+
+use crate::{
+    config::JumpGuard as JumpGuardConfig,
+    deviation::{self, BasisPoints, DeviationLimits},
+    price::{pair_of, CoinWithDecimalPlaces, Pair, Price, UInt},
+};
+
+use super::feed_tracker::FeedTracker;
+
+/// Refuses prices which changed by more than allowed since their pair was
+/// last fed successfully.
+///
+/// Operators accept the new level of a refused pair by listing it, as
+/// `BASE/QUOTE`, in the override file. Each listing is honoured once per
+/// modification of the file, after which the accepted price is used as the
+/// pair's reference until a newer feed is confirmed.
+pub(super) struct JumpGuard {
+    limits: DeviationLimits,
+    override_file: Option<PathBuf>,
+    applied_overrides: BTreeMap<Pair, SystemTime>,
+    accepted: BTreeMap<Pair, (Price<CoinWithDecimalPlaces>, Instant)>,
+}
+
+impl JumpGuard {
+    pub fn new(
+        JumpGuardConfig {
+            limits,
+            override_file,
+        }: JumpGuardConfig,
+    ) -> Self {
+        Self {
+            limits,
+            override_file,
+            applied_overrides: BTreeMap::new(),
+            accepted: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the prices which are within the limits, along with the ones
+    /// accepted through the override file.
+    pub async fn filter(
+        &mut self,
+        provider_id: &str,
+        prices: Box<[Price<CoinWithDecimalPlaces>]>,
+        feed_tracker: &FeedTracker,
+        now: Instant,
+    ) -> Box<[Price<CoinWithDecimalPlaces>]> {
+        self.accepted.retain(
+            |pair: &Pair,
+             &mut (_, accepted_at): &mut (
+                Price<CoinWithDecimalPlaces>,
+                Instant,
+            )| {
+                feed_tracker.last_confirmed(pair).map_or(
+                    true,
+                    |(_, fed_at): (&Price<CoinWithDecimalPlaces>, Instant)| {
+                        fed_at < accepted_at
+                    },
+                )
+            },
+        );
+
+        let mut overrides: Option<(BTreeSet<Pair>, SystemTime)> = None;
+
+        let mut filtered: Vec<Price<CoinWithDecimalPlaces>> =
+            Vec::with_capacity(prices.len());
+
+        for price in prices.into_vec() {
+            let pair: Pair = pair_of(&price);
+
+            let Some(reference) = self.reference(&pair, feed_tracker) else {
+                filtered.push(price);
+
+                continue;
+            };
+
+            let jump: UInt = deviation::deviation_between(&price, reference);
+
+            let limit: BasisPoints = self.limits.get(&pair.0, &pair.1);
+
+            if jump < UInt::from_digit(limit) {
+                filtered.push(price);
+
+                continue;
+            }
+
+            if overrides.is_none() {
+                overrides = Some(self.read_overrides(provider_id).await);
+            }
+
+            if let Some((listed, modified)) = &overrides {
+                if listed.contains(&pair)
+                    && self.applied_overrides.get(&pair) != Some(modified)
+                {
+                    warn!(
+                        %provider_id,
+                        "Accepting jump of {jump} basis points for \"{base}/{quote}\" pair, as overridden by operator.",
+                        base = pair.0,
+                        quote = pair.1,
+                    );
+
+                    self.applied_overrides.insert(pair.clone(), *modified);
+
+                    self.accepted.insert(pair, (price.clone(), now));
+
+                    filtered.push(price);
+
+                    continue;
+                }
+            }
+
+            error!(
+                %provider_id,
+                "Price of \"{base}/{quote}\" pair jumped by {jump} basis points since last feed, while the limit is {limit}! Refusing to feed it until accepted by operator.",
+                base = pair.0,
+                quote = pair.1,
+            );
+        }
+
+        filtered.into_boxed_slice()
+    }
+
+    fn reference<'r>(
+        &'r self,
+        pair: &Pair,
+        feed_tracker: &'r FeedTracker,
+    ) -> Option<&'r Price<CoinWithDecimalPlaces>> {
+        self.accepted
+            .get(pair)
+            .map(|(price, _): &(Price<CoinWithDecimalPlaces>, Instant)| price)
+            .or_else(|| {
+                feed_tracker.last_confirmed(pair).map(
+                    |(price, _): (&Price<CoinWithDecimalPlaces>, Instant)| {
+                        price
+                    },
+                )
+            })
+    }
+
+    /// Reads the pairs listed in the override file, along with its
+    /// modification time.
+    async fn read_overrides(
+        &self,
+        provider_id: &str,
+    ) -> (BTreeSet<Pair>, SystemTime) {
+        let Some(override_file) = &self.override_file else {
+            return (BTreeSet::new(), SystemTime::UNIX_EPOCH);
+        };
+
+        let result: io::Result<(String, SystemTime)> = async {
+            let modified: SystemTime =
+                fs::metadata(override_file).await?.modified()?;
+
+            fs::read_to_string(override_file)
+                .await
+                .map(|contents: String| (contents, modified))
+        }
+        .await;
+
+        match result {
+            Ok((contents, modified)) => (parse_overrides(&contents), modified),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                (BTreeSet::new(), SystemTime::UNIX_EPOCH)
+            },
+            Err(error) => {
+                error!(
+                    %provider_id,
+                    error = ?error,
+                    "Failed to read jump guard override file \"{path}\"! Cause: {error}",
+                    path = override_file.display(),
+                );
+
+                (BTreeSet::new(), SystemTime::UNIX_EPOCH)
+            },
+        }
+    }
+}
+
+/// Parses `BASE/QUOTE` lines, ignoring empty ones and `#` comments.
+fn parse_overrides(contents: &str) -> BTreeSet<Pair> {
+    contents
+        .lines()
+        .map(|line: &str| {
+            line.split_once('#')
+                .map_or(line, |(line, _): (&str, &str)| line)
+        })
+        .filter_map(|line: &str| line.trim().split_once('/'))
+        .map(|(base, quote): (&str, &str)| {
+            (base.trim().to_string(), quote.trim().to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn test_guard(override_file: Option<PathBuf>) -> JumpGuard {
+    JumpGuard::new(JumpGuardConfig {
+        limits: DeviationLimits::new(1_000, BTreeMap::new(), BTreeMap::new()),
+        override_file,
+    })
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_overrides() {
+    assert_eq!(
+        parse_overrides(
+            "# Accepted levels\nATOM/USDC\n\n OSMO / USDC # drained\nNLS\n"
+        ),
+        BTreeSet::from([
+            (String::from("ATOM"), String::from("USDC")),
+            (String::from("OSMO"), String::from("USDC")),
+        ])
+    );
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_refuses_jumps() {
+    use super::feed_tracker::{test_prices, test_tracker};
+    use crate::price::Coin as _;
+
+    let now: Instant = Instant::now();
+
+    let prices: Box<[Price<CoinWithDecimalPlaces>]> = test_guard(None)
+        .filter(
+            "provider",
+            test_prices(&[("ATOM", 11_000), ("OSMO", 10_999), ("NLS", 1)]),
+            &test_tracker(now),
+            now,
+        )
+        .await;
+
+    assert_eq!(prices.len(), 2);
+    assert_eq!(prices[0].amount().ticker(), "OSMO");
+    assert_eq!(prices[1].amount().ticker(), "NLS");
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_override_is_applied_once() {
+    use super::feed_tracker::{test_prices, test_tracker};
+
+    let override_file: PathBuf =
+        crate::create_unique_temp_file("jump-guard-overrides");
+
+    fs::write(&override_file, "ATOM/USDC\n").await.unwrap();
+
+    let now: Instant = Instant::now();
+
+    let tracker: FeedTracker = test_tracker(now);
+
+    let mut guard: JumpGuard = test_guard(Some(override_file.clone()));
+
+    for (amount_quote, accepted) in
+        [(15_000, true), (15_500, true), (20_000, false)]
+    {
+        assert_eq!(
+            guard
+                .filter(
+                    "provider",
+                    test_prices(&[("ATOM", amount_quote)]),
+                    &tracker,
+                    now + std::time::Duration::from_secs(1),
+                )
+                .await
+                .len(),
+            usize::from(accepted)
+        );
+    }
+
+    fs::remove_file(&override_file).await.unwrap();
+}
@@ -1,21 +1,23 @@
 use std::{
-    collections::BTreeMap, convert::Infallible, num::NonZeroU64, sync::Arc,
-    time::Duration,
+    collections::BTreeMap, convert::Infallible, mem, num::NonZeroU64,
+    path::PathBuf, sync::Arc, time::Duration,
 };
 
 use thiserror::Error;
 use tokio::{
     runtime::Handle,
     select,
-    task::{block_in_place, JoinError, JoinSet},
+    task::{block_in_place, AbortHandle, JoinError, JoinSet},
     time::{error::Elapsed, sleep, timeout_at, Instant},
 };
 use tracing::{error, info, warn};
 
 use broadcast::{
     generators::{
-        CommitError, CommitErrorType, CommitResult, CommitResultReceiver,
-        CommitResultSender, SpawnResult, TxRequest, TxRequestSender,
+        CommitError, CommitErrorType, CommitOutcome, CommitResultReceiver,
+        CommitResultSender, GeneratorsUpdate, GeneratorsUpdateReceiver,
+        GeneratorsUpdateSender, SpawnResult, TxGenerator, TxRequest,
+        TxRequestSender,
     },
     mode::NonBlocking,
     poll_delivered_tx,
@@ -31,81 +33,233 @@ use chain_comms::{
 
 use crate::{
     config::{
+        Aggregate as AggregateConfig, AggregateSource as AggregateSourceConfig,
         ComparisonProvider as ComparisonProviderConfig,
-        ComparisonProviderIdAndMaxDeviation, Provider as ProviderConfig,
-        ProviderConfig as _,
+        ComparisonProviderIdsAndMaxDeviation, CrossRates as CrossRatesConfig,
+        FeedingPolicy, JumpGuard as JumpGuardConfig,
+        Provider as ProviderConfig, ProviderConfig as _,
         ProviderWithComparison as ProviderWithComparisonConfig,
     },
     error as error_mod,
+    history::{Event, History, Recorder},
     messages::ExecuteMsg,
-    price::{CoinWithDecimalPlaces, Price},
+    price::{CoinWithDecimalPlaces, Pair, Price},
     provider::{
-        ComparisonProvider, FromConfig, PriceComparisonGuardError, Provider,
-        ProviderError,
+        coverage::DropReason, ComparisonProvider, Coverage, DenomMismatch,
+        FromConfig, PriceComparisonGuardError, Provider, ProviderError,
     },
     providers::{self, ComparisonProviderVisitor, ProviderVisitor},
     result::Result as AppResult,
 };
 
+use self::{
+    aggregator::Aggregator,
+    comparison_guard::{ComparisonGuard, ComparisonProviders, ContinuousGuard},
+    cross_rates::CrossRates,
+    feed_tracker::FeedTracker,
+    jump_guard::JumpGuard,
+    reload::Reloader,
+};
+
+#[cfg(test)]
+use tokio::sync::mpsc::UnboundedReceiver;
+
+mod aggregator;
+mod comparison_guard;
+mod cross_rates;
+mod feed_tracker;
+mod jump_guard;
 mod print_prices_pretty;
+mod reload;
 
 pub(crate) struct SpawnContext {
     pub(crate) node_client: NodeClient,
-    pub(crate) providers: BTreeMap<Box<str>, ProviderWithComparisonConfig>,
-    pub(crate) price_comparison_providers:
-        BTreeMap<Arc<str>, ComparisonProviderConfig>,
+    pub(crate) providers: CheckedProviders,
     pub(crate) tx_request_sender: TxRequestSender<NonBlocking>,
     pub(crate) signer_address: Arc<str>,
     pub(crate) hard_gas_limit: NonZeroU64,
     pub(crate) time_before_feeding: Duration,
     pub(crate) tick_time: Duration,
     pub(crate) poll_time: Duration,
+    pub(crate) history: Option<History>,
+    /// Configuration file which is re-read on `SIGHUP`, restarting only the
+    /// workers whose configuration has changed.
+    pub(crate) reload_config_path: Option<PathBuf>,
 }
 
 pub fn spawn(
     SpawnContext {
         node_client,
-        providers,
-        price_comparison_providers,
+        providers:
+            CheckedProviders {
+                feeders,
+                price_comparison_providers,
+            },
         tx_request_sender,
         signer_address,
         hard_gas_limit,
         time_before_feeding,
         tick_time,
         poll_time,
+        history,
+        reload_config_path,
     }: SpawnContext,
 ) -> AppResult<SpawnResult> {
-    let mut tx_generators_set: JoinSet<Infallible> = JoinSet::new();
+    let mut generators: SpawnedGenerators = SpawnedGenerators::default();
+
+    let workers_context: WorkersContext = WorkersContext {
+        node_client,
+        signer_address,
+        hard_gas_limit,
+        time_before_feeding,
+        tick_time,
+        poll_time,
+        history,
+    };
+
+    let reloader: Option<Reloader> =
+        reload_config_path.map(|config_path: PathBuf| {
+            Reloader::new(
+                config_path,
+                workers_context.clone(),
+                &tx_request_sender,
+                &feeders,
+                price_comparison_providers.clone(),
+            )
+        });
 
     let price_comparison_providers: BTreeMap<
         Arc<str>,
         Arc<dyn ComparisonProvider>,
-    > = block_in_place(|| {
-        price_comparison_providers
-            .into_iter()
-            .map(construct_comparison_provider_f(&node_client))
-            .collect::<Result<_, _>>()
-    })?;
-
-    let mut tx_result_senders: BTreeMap<usize, CommitResultSender> =
-        BTreeMap::new();
+    > = price_comparison_providers
+        .into_iter()
+        .map(
+            |(provider_id, constructed): (
+                Arc<str>,
+                ConstructedComparisonProvider,
+            )| { (provider_id, constructed.provider) },
+        )
+        .collect();
 
-    providers
+    feeders
         .into_iter()
         .enumerate()
         .try_for_each(try_for_each_provider_f(TryForEachProviderContext {
-            node_client,
-            tx_generators_set: &mut tx_generators_set,
-            tx_result_senders: &mut tx_result_senders,
+            workers_context,
             tx_request_sender,
-            signer_address,
+            generators: &mut generators,
             price_comparison_providers,
-            hard_gas_limit,
-            time_before_feeding,
-            tick_time,
-            poll_time,
-        }))
-        .map(|()| SpawnResult::new(tx_generators_set, tx_result_senders))
+        }))?;
+
+    let SpawnedGenerators {
+        mut tx_generators_set,
+        tx_result_senders,
+        abort_handles,
+    } = generators;
+
+    Ok(if let Some(reloader) = reloader {
+        let (updates_sender, updates_receiver): (
+            GeneratorsUpdateSender,
+            GeneratorsUpdateReceiver,
+        ) = broadcast::generators::new_updates_channel();
+
+        tx_generators_set.spawn(reloader.run(updates_sender));
+
+        SpawnResult::new(tx_generators_set, tx_result_senders)
+            .with_updates(abort_handles, updates_receiver)
+    } else {
+        SpawnResult::new(tx_generators_set, tx_result_senders)
+    })
+}
+
+/// Set of worker tasks, to which the constructed workers are added.
+trait Generators {
+    fn spawn(
+        &mut self,
+        monotonic_id: usize,
+        commit_result_sender: CommitResultSender,
+        generator: TxGenerator,
+    );
+}
+
+/// Worker tasks spawned before broadcasting starts.
+#[derive(Default)]
+struct SpawnedGenerators {
+    tx_generators_set: JoinSet<Infallible>,
+    tx_result_senders: BTreeMap<usize, CommitResultSender>,
+    abort_handles: BTreeMap<usize, AbortHandle>,
+}
+
+impl Generators for SpawnedGenerators {
+    fn spawn(
+        &mut self,
+        monotonic_id: usize,
+        commit_result_sender: CommitResultSender,
+        generator: TxGenerator,
+    ) {
+        self.tx_result_senders
+            .insert(monotonic_id, commit_result_sender);
+
+        self.abort_handles
+            .insert(monotonic_id, self.tx_generators_set.spawn(generator));
+    }
+}
+
+/// Worker tasks spawned while broadcasting, through the broadcast loop.
+impl Generators for GeneratorsUpdateSender {
+    fn spawn(
+        &mut self,
+        monotonic_id: usize,
+        commit_result_sender: CommitResultSender,
+        generator: TxGenerator,
+    ) {
+        if self
+            .send(GeneratorsUpdate::Spawn {
+                sender_id: monotonic_id,
+                tx_result_sender: commit_result_sender,
+                generator,
+            })
+            .is_err()
+        {
+            warn!("Broadcast loop has stopped! Worker task not spawned.");
+        }
+    }
+}
+
+/// Providers, aggregates and comparison providers, constructed and checked
+/// before the workers are spawned, which reuse them.
+pub(crate) struct CheckedProviders {
+    feeders: Vec<ConstructedFeeder>,
+    price_comparison_providers:
+        BTreeMap<Arc<str>, ConstructedComparisonProvider>,
+}
+
+/// Provider or aggregate, along with the configuration it's constructed from.
+struct ConstructedFeeder {
+    provider_id: Box<str>,
+    feeder: Feeder,
+    provider: Box<dyn Provider>,
+}
+
+#[derive(Clone)]
+struct ConstructedComparisonProvider {
+    config: ComparisonProviderConfig,
+    provider: Arc<dyn ComparisonProvider>,
+}
+
+/// Shared by the workers spawned on start and the ones spawned on reload.
+///
+/// The sender of the transaction requests is left out, as the broadcast loop
+/// exits once all of its clones, held by the workers, are dropped.
+#[derive(Clone)]
+struct WorkersContext {
+    node_client: NodeClient,
+    signer_address: Arc<str>,
+    hard_gas_limit: NonZeroU64,
+    time_before_feeding: Duration,
+    tick_time: Duration,
+    poll_time: Duration,
+    history: Option<History>,
 }
 
 fn construct_comparison_provider_f(
@@ -136,88 +290,443 @@ fn construct_comparison_provider_f(
 }
 
 struct TryForEachProviderContext<'r> {
-    node_client: NodeClient,
-    tx_generators_set: &'r mut JoinSet<Infallible>,
-    tx_result_senders: &'r mut BTreeMap<usize, CommitResultSender>,
+    workers_context: WorkersContext,
     tx_request_sender: TxRequestSender<NonBlocking>,
-    signer_address: Arc<str>,
+    generators: &'r mut dyn Generators,
     price_comparison_providers: BTreeMap<Arc<str>, Arc<dyn ComparisonProvider>>,
-    hard_gas_limit: NonZeroU64,
-    time_before_feeding: Duration,
-    tick_time: Duration,
-    poll_time: Duration,
+}
+
+#[derive(Clone, PartialEq)]
+enum Feeder {
+    Provider(ProviderWithComparisonConfig),
+    Aggregate(AggregateConfig),
+}
+
+impl Feeder {
+    fn options(&self) -> FeederOptions<'_> {
+        match self {
+            Self::Provider(config) => FeederOptions {
+                oracle_address: config.provider.oracle_addr(),
+                comparison: config.comparison.clone(),
+                feeding_policy: config.feeding,
+                jump_guard: config.jump_guard.clone(),
+                cross_rates: config.cross_rates,
+            },
+            Self::Aggregate(config) => FeederOptions {
+                oracle_address: &config.oracle_address,
+                comparison: config.comparison.clone(),
+                feeding_policy: config.feeding,
+                jump_guard: config.jump_guard.clone(),
+                cross_rates: config.cross_rates,
+            },
+        }
+    }
+}
+
+/// Options shared by providers and aggregates.
+struct FeederOptions<'r> {
+    oracle_address: &'r str,
+    comparison: Option<ComparisonProviderIdsAndMaxDeviation>,
+    feeding_policy: Option<FeedingPolicy>,
+    jump_guard: Option<JumpGuardConfig>,
+    cross_rates: Option<CrossRatesConfig>,
 }
 
 fn try_for_each_provider_f(
     TryForEachProviderContext {
-        node_client,
-        tx_generators_set,
-        tx_result_senders,
+        workers_context:
+            WorkersContext {
+                node_client,
+                signer_address,
+                hard_gas_limit,
+                time_before_feeding,
+                tick_time,
+                poll_time,
+                history,
+            },
         tx_request_sender,
-        signer_address,
+        generators,
         price_comparison_providers,
-        hard_gas_limit,
-        time_before_feeding,
-        tick_time,
-        poll_time,
     }: TryForEachProviderContext<'_>,
-) -> impl FnMut((usize, (Box<str>, ProviderWithComparisonConfig))) -> AppResult<()>
-       + '_ {
-    move |(monotonic_id, (provider_id, config)): (
-        usize,
-        (Box<str>, ProviderWithComparisonConfig),
-    )| {
-        config
-            .comparison
-            .map(
-                |ComparisonProviderIdAndMaxDeviation {
-                     provider_id: comparison_provider_id,
-                     max_deviation_exclusive,
-                 }: ComparisonProviderIdAndMaxDeviation| {
-                    price_comparison_providers
-                        .get(&comparison_provider_id)
-                        .map_or_else(
-                            || {
-                                Err(error_mod::Application::UnknownPriceComparisonProviderId(
-                                    comparison_provider_id,
-                                ))
-                            },
-                            |provider: &Arc<dyn ComparisonProvider>| {
-                                Ok((provider.clone(), max_deviation_exclusive))
-                            },
+) -> impl FnMut((usize, ConstructedFeeder)) -> AppResult<()> + '_ {
+    move |(
+        monotonic_id,
+        ConstructedFeeder {
+            provider_id,
+            feeder,
+            provider,
+        },
+    ): (usize, ConstructedFeeder)| {
+        let FeederOptions {
+            oracle_address,
+            comparison,
+            feeding_policy,
+            jump_guard,
+            ..
+        }: FeederOptions<'_> = feeder.options();
+
+        let history: Option<Recorder> =
+            history.as_ref().map(|history: &History| {
+                history.recorder(&provider_id, oracle_address)
+            });
+
+        let price_comparison_guard: Option<ComparisonGuard> =
+            construct_comparison_guard(
+                comparison,
+                &price_comparison_providers,
+            )?;
+
+        TaskSpawner {
+            worker_task_context: TaskContext {
+                tx_request_sender: tx_request_sender.clone(),
+                signer_address: signer_address.clone(),
+                hard_gas_limit,
+                monotonic_id,
+                tick_time,
+                poll_time,
+                feed_tracker: (feeding_policy.is_some()
+                    || jump_guard.is_some())
+                .then(|| FeedTracker::new(feeding_policy)),
+                jump_guard: jump_guard.map(JumpGuard::new),
+                history,
+            },
+            node_client: &node_client,
+            generators: &mut *generators,
+            provider_id,
+            price_comparison_guard,
+            time_before_feeding,
+        }
+        .spawn(provider, oracle_address.into());
+
+        Ok(())
+    }
+}
+
+fn construct_comparison_guard(
+    comparison: Option<ComparisonProviderIdsAndMaxDeviation>,
+    price_comparison_providers: &BTreeMap<
+        Arc<str>,
+        Arc<dyn ComparisonProvider>,
+    >,
+) -> AppResult<Option<ComparisonGuard>> {
+    comparison
+        .map(
+            |ComparisonProviderIdsAndMaxDeviation {
+                 provider_ids: comparison_provider_ids,
+                 quorum,
+                 deviation_limits,
+                 continuous,
+             }: ComparisonProviderIdsAndMaxDeviation| {
+                comparison_provider_ids
+                    .into_vec()
+                    .into_iter()
+                    .map(|comparison_provider_id: Arc<str>| {
+                        price_comparison_providers
+                            .get(&comparison_provider_id)
+                            .cloned()
+                            .map(|provider: Arc<dyn ComparisonProvider>| {
+                                (comparison_provider_id.clone(), provider)
+                            })
+                            .ok_or(error_mod::Application::UnknownPriceComparisonProviderId(
+                                comparison_provider_id,
+                            ))
+                    })
+                    .collect::<AppResult<_>>()
+                    .map(|providers: ComparisonProviders| {
+                        ComparisonGuard::new(
+                            providers,
+                            quorum,
+                            deviation_limits,
+                            continuous,
                         )
-                },
-            )
-            .transpose()
-            .and_then(
-                |price_comparison_provider: Option<(Arc<dyn ComparisonProvider>, u64)>| {
-                    let provider_name: Arc<str> = config.provider.name().clone();
-
-                    providers::Providers::visit_provider(
-                        &provider_name,
-                        TaskSpawningProviderVisitor {
-                            worker_task_context: TaskContext {
-                                tx_request_sender: tx_request_sender.clone(),
-                                signer_address: signer_address.clone(),
-                                hard_gas_limit,
-                                monotonic_id,
-                                tick_time,
-                                poll_time,
-                            },
-                            node_client: &node_client,
-                            tx_generators_set,
-                            tx_result_senders,
-                            provider_id,
-                            provider_config: config.provider,
-                            price_comparison_provider,
-                            time_before_feeding,
-                        },
-                    )
-                    .ok_or(error_mod::Application::UnknownProviderId(provider_name))
-                    .and_then(|result: Result<(), error_mod::Worker>| result.map_err(From::from))
-                },
-            )
+                    })
+            },
+        )
+        .transpose()
+}
+
+fn construct_aggregator(
+    provider_id: &str,
+    AggregateConfig {
+        method,
+        min_sources,
+        sources,
+        ..
+    }: AggregateConfig,
+    node_client: &NodeClient,
+) -> AppResult<Aggregator> {
+    sources
+        .into_iter()
+        .map(
+            |(source_id, AggregateSourceConfig { provider, weight }): (
+                Box<str>,
+                AggregateSourceConfig,
+            )| {
+                construct_provider(&source_id, provider, node_client).map(
+                    |provider: Box<dyn Provider>| aggregator::Source {
+                        id: source_id,
+                        provider,
+                        weight,
+                    },
+                )
+            },
+        )
+        .collect::<AppResult<_>>()
+        .map(|sources: Box<[aggregator::Source]>| {
+            Aggregator::new(provider_id.into(), method, min_sources, sources)
+        })
+}
+
+fn construct_provider(
+    provider_id: &str,
+    provider_config: ProviderConfig,
+    node_client: &NodeClient,
+) -> AppResult<Box<dyn Provider>> {
+    let provider_name: Arc<str> = provider_config.name().clone();
+
+    providers::Providers::visit_provider(
+        &provider_name,
+        SourceConstructingProviderVisitor {
+            source_id: provider_id,
+            provider_config,
+            node_client,
+        },
+    )
+    .ok_or(error_mod::Application::UnknownProviderId(provider_name))
+    .and_then(|result: Result<Box<dyn Provider>, error_mod::Worker>| {
+        result.map_err(From::from)
+    })
+}
+
+/// Constructs the provider or aggregate with the given identifier, as its
+/// worker would, and fetches its prices once, without checking them.
+pub(crate) async fn fetch_prices(
+    node_client: &NodeClient,
+    mut providers: BTreeMap<Box<str>, ProviderWithComparisonConfig>,
+    mut aggregates: BTreeMap<Box<str>, AggregateConfig>,
+    provider_id: &str,
+) -> AppResult<Box<[Price<CoinWithDecimalPlaces>]>> {
+    let feeder: Feeder = providers
+        .remove(provider_id)
+        .map(Feeder::Provider)
+        .or_else(|| aggregates.remove(provider_id).map(Feeder::Aggregate))
+        .ok_or_else(|| {
+            error_mod::Application::NoSuchProvider(provider_id.into())
+        })?;
+
+    block_in_place(|| construct_feeder(provider_id, feeder, node_client))?
+        .get_prices(false)
+        .await
+        .map_err(From::from)
+}
+
+/// Constructs the providers, the aggregates and the comparison providers, as
+/// their workers would, and checks their configured currencies against the
+/// bank modules of the chains the prices are fetched from. The constructed
+/// ones are returned, so that the workers reuse them.
+///
+/// Failures to query a bank module are only warned about, as they are not
+/// necessarily caused by the configuration.
+///
+/// In strict coverage mode, the providers and the aggregates also fetch their
+/// prices once and all of the pairs requested by the oracles have to be
+/// priced.
+///
+/// All of the mismatches and the dropped pairs are logged before failing, so
+/// that they can be fixed at once.
+pub(crate) async fn check_providers(
+    node_client: &NodeClient,
+    providers: BTreeMap<Box<str>, ProviderWithComparisonConfig>,
+    aggregates: BTreeMap<Box<str>, AggregateConfig>,
+    price_comparison_providers: BTreeMap<Arc<str>, ComparisonProviderConfig>,
+    strict_coverage: bool,
+) -> AppResult<CheckedProviders> {
+    info!("Checking configured currencies against chains' metadata...");
+
+    let mut mismatches_count: usize = 0;
+
+    let mut dropped_count: usize = 0;
+
+    let mut feeders: Vec<ConstructedFeeder> =
+        Vec::with_capacity(providers.len() + aggregates.len());
+
+    for (provider_id, feeder) in providers
+        .into_iter()
+        .map(|(id, config): (Box<str>, ProviderWithComparisonConfig)| {
+            (id, Feeder::Provider(config))
+        })
+        .chain(aggregates.into_iter().map(
+            |(id, config): (Box<str>, AggregateConfig)| {
+                (id, Feeder::Aggregate(config))
+            },
+        ))
+    {
+        let provider: Box<dyn Provider> = block_in_place(|| {
+            construct_feeder(&provider_id, feeder.clone(), node_client)
+        })?;
+
+        let (mismatches, dropped): (usize, usize) =
+            check_feeder(&provider_id, &provider, strict_coverage).await?;
+
+        mismatches_count += mismatches;
+
+        dropped_count += dropped;
+
+        feeders.push(ConstructedFeeder {
+            provider_id,
+            feeder,
+            provider,
+        });
     }
+
+    let mut constructed_price_comparison_providers: BTreeMap<
+        Arc<str>,
+        ConstructedComparisonProvider,
+    > = BTreeMap::new();
+
+    for (provider_id, config) in price_comparison_providers {
+        let (provider_id, provider): (Arc<str>, Arc<dyn ComparisonProvider>) =
+            block_in_place(|| {
+                construct_comparison_provider_f(node_client)((
+                    provider_id,
+                    config.clone(),
+                ))
+            })?;
+
+        mismatches_count += report_denom_mismatches(
+            &provider_id,
+            provider.check_denoms().await,
+        );
+
+        constructed_price_comparison_providers.insert(
+            provider_id,
+            ConstructedComparisonProvider { config, provider },
+        );
+    }
+
+    ensure_checked(mismatches_count, dropped_count)?;
+
+    info!("Configured currencies match chains' metadata.");
+
+    Ok(CheckedProviders {
+        feeders,
+        price_comparison_providers: constructed_price_comparison_providers,
+    })
+}
+
+/// Checks the configured currencies of the provider or aggregate and, in
+/// strict coverage mode, its coverage. Returns the count of the mismatches
+/// and the one of the dropped pairs.
+async fn check_feeder(
+    provider_id: &str,
+    provider: &dyn Provider,
+    strict_coverage: bool,
+) -> AppResult<(usize, usize)> {
+    let mismatches: usize =
+        report_denom_mismatches(provider_id, provider.check_denoms().await);
+
+    let dropped: usize = if strict_coverage {
+        check_coverage(provider_id, provider).await?
+    } else {
+        0
+    };
+
+    Ok((mismatches, dropped))
+}
+
+const fn ensure_checked(
+    mismatches_count: usize,
+    dropped_count: usize,
+) -> AppResult<()> {
+    if mismatches_count != 0 {
+        Err(error_mod::Application::DenomMismatches(mismatches_count))
+    } else if dropped_count != 0 {
+        Err(error_mod::Application::IncompleteCoverage(dropped_count))
+    } else {
+        Ok(())
+    }
+}
+
+/// Constructs the provider or aggregate, along with the derivation of its
+/// cross rates, when configured.
+fn construct_feeder(
+    provider_id: &str,
+    feeder: Feeder,
+    node_client: &NodeClient,
+) -> AppResult<Box<dyn Provider>> {
+    let cross_rates: Option<CrossRatesConfig> = feeder.options().cross_rates;
+
+    let provider: Box<dyn Provider> = match feeder {
+        Feeder::Provider(config) => {
+            construct_provider(provider_id, config.provider, node_client)?
+        },
+        Feeder::Aggregate(config) => {
+            Box::new(construct_aggregator(provider_id, config, node_client)?)
+        },
+    };
+
+    Ok(if let Some(cross_rates) = cross_rates {
+        Box::new(CrossRates::new(provider, cross_rates))
+    } else {
+        provider
+    })
+}
+
+/// Logs the mismatches and returns their count. Failures to check are warned
+/// about and count as no mismatches.
+fn report_denom_mismatches(
+    provider_id: &str,
+    result: Result<Box<[DenomMismatch]>, ProviderError>,
+) -> usize {
+    match result {
+        Ok(mismatches) => {
+            for mismatch in mismatches.iter() {
+                error!(%provider_id, "{mismatch}");
+            }
+
+            mismatches.len()
+        },
+        Err(error) => {
+            warn!(
+                %provider_id,
+                ?error,
+                "Failed to check configured currencies against chain's metadata! They are left unchecked. Cause: {error}"
+            );
+
+            0
+        },
+    }
+}
+
+/// Fetches the prices once and returns the count of the pairs requested by
+/// the oracle which are dropped. Pairs whose prices failed to be fetched are
+/// only reported, as they are not necessarily dropped because of the
+/// configuration.
+async fn check_coverage(
+    provider_id: &str,
+    provider: &dyn Provider,
+) -> AppResult<usize> {
+    let (prices, coverage): (
+        Box<[Price<CoinWithDecimalPlaces>]>,
+        Option<Coverage>,
+    ) = provider.get_prices_with_coverage(true).await.map_err(
+        |error: ProviderError| {
+            error_mod::Application::CheckProvider(provider_id.into(), error)
+        },
+    )?;
+
+    Ok(coverage.map_or(0, |coverage: Coverage| {
+        let coverage: Coverage = coverage.with_prices(&prices);
+
+        coverage.report(provider_id);
+
+        coverage
+            .dropped()
+            .filter(|(_, reason): &(&Pair, &DropReason)| {
+                !reason.is_fetch_failure()
+            })
+            .count()
+    }))
 }
 
 struct TaskContext {
@@ -227,6 +736,9 @@ struct TaskContext {
     monotonic_id: usize,
     tick_time: Duration,
     poll_time: Duration,
+    feed_tracker: Option<FeedTracker>,
+    jump_guard: Option<JumpGuard>,
+    history: Option<Recorder>,
 }
 
 struct PriceComparisonProviderVisitor<'r> {
@@ -260,63 +772,70 @@ impl<'r> ComparisonProviderVisitor for PriceComparisonProviderVisitor<'r> {
     }
 }
 
-struct TaskSpawningProviderVisitor<'r> {
+struct TaskSpawner<'r> {
     worker_task_context: TaskContext,
     node_client: &'r NodeClient,
-    tx_generators_set: &'r mut JoinSet<Infallible>,
-    tx_result_senders: &'r mut BTreeMap<usize, CommitResultSender>,
+    generators: &'r mut dyn Generators,
     provider_id: Box<str>,
-    provider_config: ProviderConfig,
-    price_comparison_provider: Option<(Arc<dyn ComparisonProvider>, u64)>,
+    price_comparison_guard: Option<ComparisonGuard>,
     time_before_feeding: Duration,
 }
 
-impl<'r> ProviderVisitor for TaskSpawningProviderVisitor<'r> {
-    type Return = Result<(), error_mod::Worker>;
+impl TaskSpawner<'_> {
+    fn spawn<P>(self, provider: P, oracle_address: Arc<str>)
+    where
+        P: Provider,
+    {
+        let (commit_result_sender, commit_result_receiver): (
+            CommitResultSender,
+            CommitResultReceiver,
+        ) = broadcast::generators::new_results_channel();
+
+        self.generators.spawn(
+            self.worker_task_context.monotonic_id,
+            commit_result_sender,
+            Box::pin(perform_check_and_enter_loop(
+                ProviderWithIds {
+                    provider,
+                    provider_id: self.provider_id,
+                },
+                self.worker_task_context,
+                self.price_comparison_guard,
+                self.time_before_feeding,
+                self.node_client.clone(),
+                oracle_address,
+                commit_result_receiver,
+            )),
+        );
+    }
+}
+
+struct SourceConstructingProviderVisitor<'r> {
+    source_id: &'r str,
+    provider_config: ProviderConfig,
+    node_client: &'r NodeClient,
+}
+
+impl ProviderVisitor for SourceConstructingProviderVisitor<'_> {
+    type Return = Result<Box<dyn Provider>, error_mod::Worker>;
 
     fn on<P>(self) -> Self::Return
     where
         P: Provider + FromConfig<false>,
     {
-        let oracle_address: Arc<str> =
-            self.provider_config.oracle_addr().clone();
-
-        match Handle::current().block_on(<P as FromConfig<false>>::from_config(
-            &self.provider_id,
-            self.provider_config,
-            self.node_client,
-        )) {
-            Ok(provider) => {
-                let (commit_result_sender, commit_result_receiver): (
-                    CommitResultSender,
-                    CommitResultReceiver,
-                ) = broadcast::generators::new_results_channel();
-
-                self.tx_result_senders.insert(
-                    self.worker_task_context.monotonic_id,
-                    commit_result_sender,
-                );
-
-                self.tx_generators_set.spawn(perform_check_and_enter_loop(
-                    ProviderWithIds {
-                        provider,
-                        provider_id: self.provider_id,
-                    },
-                    self.worker_task_context,
-                    self.price_comparison_provider,
-                    self.time_before_feeding,
-                    self.node_client.clone(),
-                    oracle_address,
-                    commit_result_receiver,
-                ));
-
-                Ok(())
-            },
-            Err(error) => Err(error_mod::Worker::InstantiateProvider(
-                self.provider_id,
-                Box::new(error),
-            )),
-        }
+        Handle::current()
+            .block_on(<P as FromConfig<false>>::from_config(
+                self.source_id,
+                self.provider_config,
+                self.node_client,
+            ))
+            .map(|provider: P| Box::new(provider) as Box<dyn Provider>)
+            .map_err(|error: P::ConstructError| {
+                error_mod::Worker::InstantiateProvider(
+                    self.source_id.into(),
+                    Box::new(error),
+                )
+            })
     }
 }
 
@@ -331,10 +850,7 @@ async fn perform_check_and_enter_loop<P>(
         provider_id,
     }: ProviderWithIds<P>,
     worker_task_context: TaskContext,
-    comparison_provider_and_deviation: Option<(
-        Arc<dyn ComparisonProvider>,
-        u64,
-    )>,
+    price_comparison_guard: Option<ComparisonGuard>,
     time_before_feeding: Duration,
     node_client: NodeClient,
     oracle_address: Arc<str>,
@@ -345,16 +861,21 @@ where
 {
     let result: Result<ChannelClosed, error_mod::Worker> = 'result: {
         let prices: Box<[Price<CoinWithDecimalPlaces>]> = {
-            let result = provider.get_prices(false).await.map_err(
-                |error: ProviderError| {
+            let result = provider
+                .get_prices_with_coverage(false)
+                .await
+                .map_err(|error: ProviderError| {
                     error_mod::Worker::PriceComparisonGuard(
                         PriceComparisonGuardError::FetchPrices(error),
                     )
-                },
-            );
+                });
 
             match result {
-                Ok(prices) => prices,
+                Ok((prices, coverage)) => {
+                    report_coverage(&provider_id, &prices, coverage);
+
+                    prices
+                },
                 Err(error) => {
                     break 'result Err(error);
                 },
@@ -369,18 +890,27 @@ where
             break 'result Err(error_mod::Worker::EmptyPriceList);
         }
 
-        if let Some((comparison_provider, max_deviation_exclusive)) =
-            { comparison_provider_and_deviation }
-        {
+        if let Some(history) = &worker_task_context.history {
+            history.record(Event::fetched(&prices));
+        }
+
+        if let Some(price_comparison_guard) = &price_comparison_guard {
             let result: Result<(), PriceComparisonGuardError> =
-                comparison_provider
-                    .benchmark_prices(
-                        provider.instance_id(),
-                        &prices,
-                        max_deviation_exclusive,
-                    )
+                price_comparison_guard
+                    .benchmark_prices(provider.instance_id(), &prices)
                     .await;
 
+            if let Some(history) = &worker_task_context.history {
+                history.record(Event::Compared {
+                    passed: result.is_ok(),
+                    skipped_pairs: Vec::new(),
+                    error: result
+                        .as_ref()
+                        .err()
+                        .map(PriceComparisonGuardError::to_string),
+                });
+            }
+
             if let Err(error) = result {
                 break 'result Err(error_mod::Worker::PriceComparisonGuard(
                     error,
@@ -400,6 +930,7 @@ where
             provider,
             &provider_id,
             worker_task_context,
+            price_comparison_guard.and_then(ComparisonGuard::into_continuous),
             node_client,
             oracle_address,
             commit_result_receiver,
@@ -435,7 +966,11 @@ async fn provider_main_loop<P>(
         monotonic_id,
         tick_time,
         poll_time,
+        feed_tracker,
+        mut jump_guard,
+        history,
     }: TaskContext,
+    mut continuous_comparison_guard: Option<ContinuousGuard>,
     node_client: NodeClient,
     oracle_address: Arc<str>,
     mut commit_result_receiver: CommitResultReceiver,
@@ -443,21 +978,34 @@ async fn provider_main_loop<P>(
 where
     P: Provider,
 {
-    let send_tx_request =
-        move |message, fallback_gas_limit, hard_gas_limit, expiration| {
-            tx_request_sender.send(TxRequest::<NonBlocking>::new(
-                monotonic_id,
-                vec![message],
-                fallback_gas_limit,
-                hard_gas_limit,
-                expiration,
-            ))
-        };
+    let mut next_request_id: u64 = 0;
 
-    let mut fallback_gas_limit: NonZeroU64 = hard_gas_limit;
+    let mut send_tx_request = move |message, expiration| {
+        let request_id: u64 = next_request_id;
 
-    let mut poll_delivered_tx_set: JoinSet<Option<(TxHash, TxResponse)>> =
-        JoinSet::new();
+        next_request_id += 1;
+
+        tx_request_sender
+            .send(
+                TxRequest::<NonBlocking>::new(
+                    monotonic_id,
+                    vec![message],
+                    NonZeroU64::MAX,
+                    hard_gas_limit,
+                    expiration,
+                )
+                .with_request_id(request_id),
+            )
+            .map(|()| request_id)
+    };
+
+    let mut tx_tracking: TxTracking = TxTracking {
+        poll_delivered_tx_set: JoinSet::new(),
+        fallback_gas_limit: hard_gas_limit,
+        feed_tracker,
+        history,
+        unrecorded_fed: BTreeMap::new(),
+    };
 
     let mut next_tick: Instant = Instant::now();
 
@@ -468,8 +1016,7 @@ where
                 &node_client,
                 provider_id,
                 &mut commit_result_receiver,
-                &mut poll_delivered_tx_set,
-                &mut fallback_gas_limit,
+                &mut tx_tracking,
                 tick_time,
                 poll_time,
             ),
@@ -484,35 +1031,43 @@ where
             break 'worker_loop channel_closed;
         }
 
-        match provider.get_prices(true).await {
-            Ok(prices) => {
-                let message: Vec<u8> =
-                    serde_json_wasm::to_string(&ExecuteMsg::FeedPrices {
-                        prices,
-                    })?
-                    .into_bytes();
-
-                let message: ProtobufAny =
-                    ProtobufAny::from_msg(&MsgExecuteContract {
-                        sender: signer_address.to_string(),
-                        contract: oracle_address.to_string(),
-                        msg: message,
-                        funds: Vec::new(),
-                    })?;
+        match provider.get_prices_with_coverage(true).await {
+            Ok((prices, coverage)) => {
+                report_coverage(provider_id, &prices, coverage);
+
+                let Some(prices) = select_prices_to_feed(
+                    provider.instance_id(),
+                    prices,
+                    continuous_comparison_guard.as_mut(),
+                    jump_guard.as_mut(),
+                    &mut tx_tracking,
+                )
+                .await
+                else {
+                    next_tick = Instant::now() + tick_time;
+
+                    continue 'worker_loop;
+                };
+
+                let fed_prices: Option<Box<[Price<CoinWithDecimalPlaces>]>> =
+                    tx_tracking.keeps_fed_prices().then(|| prices.clone());
+
+                let message: ProtobufAny = feed_prices_message(
+                    &signer_address,
+                    &oracle_address,
+                    prices,
+                )?;
 
                 next_tick = Instant::now() + tick_time;
 
-                if send_tx_request(
-                    message,
-                    NonZeroU64::MAX,
-                    hard_gas_limit,
-                    next_tick,
-                )
-                .is_err()
-                {
+                let Ok(request_id) = send_tx_request(message, next_tick) else {
                     warn!(%provider_id, "Communication channel has been closed! Exiting worker task...");
 
                     break 'worker_loop ChannelClosed {};
+                };
+
+                if let Some(fed_prices) = fed_prices {
+                    tx_tracking.on_sent(request_id, fed_prices, next_tick);
                 }
             },
             Err(error) => {
@@ -533,32 +1088,190 @@ where
 
     info!(%provider_id, "Joining all child tasks before exiting.");
 
-    while poll_delivered_tx_set.join_next().await.is_some() {}
+    while tx_tracking
+        .poll_delivered_tx_set
+        .join_next()
+        .await
+        .is_some()
+    {}
 
     Ok(ok_output)
 }
 
+fn report_coverage(
+    provider_id: &str,
+    prices: &[Price<CoinWithDecimalPlaces>],
+    coverage: Option<Coverage>,
+) {
+    if let Some(coverage) = coverage {
+        coverage.with_prices(prices).report(provider_id);
+    }
+}
+
+fn feed_prices_message(
+    signer_address: &str,
+    oracle_address: &str,
+    prices: Box<[Price<CoinWithDecimalPlaces>]>,
+) -> Result<ProtobufAny, error_mod::Worker> {
+    let message: Vec<u8> =
+        serde_json_wasm::to_string(&ExecuteMsg::FeedPrices { prices })?
+            .into_bytes();
+
+    ProtobufAny::from_msg(&MsgExecuteContract {
+        sender: signer_address.to_string(),
+        contract: oracle_address.to_string(),
+        msg: message,
+        funds: Vec::new(),
+    })
+    .map_err(From::from)
+}
+
+/// Returns the prices which are to be fed on the current tick, or `None` when
+/// none of them are.
+async fn select_prices_to_feed(
+    provider_id: &str,
+    prices: Box<[Price<CoinWithDecimalPlaces>]>,
+    continuous_comparison_guard: Option<&mut ContinuousGuard>,
+    jump_guard: Option<&mut JumpGuard>,
+    tx_tracking: &mut TxTracking,
+) -> Option<Box<[Price<CoinWithDecimalPlaces>]>> {
+    if let Some(history) = &tx_tracking.history {
+        history.record(Event::fetched(&prices));
+    }
+
+    let prices: Box<[Price<CoinWithDecimalPlaces>]> =
+        if let Some(guard) = continuous_comparison_guard {
+            let prices: Option<Box<[Price<CoinWithDecimalPlaces>]>> =
+                guard.apply(provider_id, prices).await;
+
+            if let (Some(history), Some(outcome)) =
+                (&tx_tracking.history, guard.take_check_outcome())
+            {
+                history.record(outcome);
+            }
+
+            prices?
+        } else {
+            prices
+        };
+
+    let Some(feed_tracker) = &mut tx_tracking.feed_tracker else {
+        return Some(prices);
+    };
+
+    let now: Instant = Instant::now();
+
+    let prices: Box<[Price<CoinWithDecimalPlaces>]> =
+        if let Some(jump_guard) = jump_guard {
+            jump_guard
+                .filter(provider_id, prices, feed_tracker, now)
+                .await
+        } else {
+            prices
+        };
+
+    feed_tracker.select(prices, now)
+}
+
 #[derive(Debug, Error)]
 #[error("Communication channel has been closed!")]
 struct ChannelClosed;
 
+struct TxTracking {
+    poll_delivered_tx_set: JoinSet<(TxHash, Option<TxResponse>)>,
+    fallback_gas_limit: NonZeroU64,
+    feed_tracker: Option<FeedTracker>,
+    history: Option<Recorder>,
+    /// Prices which were sent, keyed by the identifiers of their requests
+    /// and kept until the outcome of committing them is known, so they can
+    /// be recorded along with the transaction's hash.
+    unrecorded_fed: BTreeMap<u64, Box<[Price<CoinWithDecimalPlaces>]>>,
+}
+
+impl TxTracking {
+    /// Whether the fed prices have to be kept after they are sent.
+    const fn keeps_fed_prices(&self) -> bool {
+        self.feed_tracker.is_some() || self.history.is_some()
+    }
+
+    fn on_sent(
+        &mut self,
+        request_id: u64,
+        prices: Box<[Price<CoinWithDecimalPlaces>]>,
+        expiration: Instant,
+    ) {
+        let prices: Box<[Price<CoinWithDecimalPlaces>]> = match &mut self
+            .feed_tracker
+        {
+            Some(feed_tracker) if self.history.is_some() => {
+                feed_tracker.on_sent(request_id, prices.clone(), expiration);
+
+                prices
+            },
+            Some(feed_tracker) => {
+                feed_tracker.on_sent(request_id, prices, expiration);
+
+                return;
+            },
+            None => prices,
+        };
+
+        self.unrecorded_fed.insert(request_id, prices);
+    }
+
+    /// Records the prices of the request with the given identifier along
+    /// with the transaction's hash, if it was committed.
+    ///
+    /// Outcomes are sent back in the order in which the requests were sent,
+    /// so the prices of earlier requests, which are still unrecorded, were
+    /// never committed and are recorded without a hash.
+    fn on_commit_result(&mut self, request_id: u64, tx_hash: Option<&TxHash>) {
+        if let Some(feed_tracker) = &mut self.feed_tracker {
+            feed_tracker.on_commit_result(request_id, tx_hash);
+        }
+
+        let later: BTreeMap<u64, Box<[Price<CoinWithDecimalPlaces>]>> =
+            self.unrecorded_fed.split_off(&request_id);
+
+        let earlier: BTreeMap<u64, Box<[Price<CoinWithDecimalPlaces>]>> =
+            mem::replace(&mut self.unrecorded_fed, later);
+
+        for prices in earlier.values() {
+            self.record_fed(prices, None);
+        }
+
+        if let Some(prices) = self.unrecorded_fed.remove(&request_id) {
+            self.record_fed(&prices, tx_hash);
+        }
+    }
+
+    fn record_fed(
+        &self,
+        prices: &[Price<CoinWithDecimalPlaces>],
+        tx_hash: Option<&TxHash>,
+    ) {
+        if let Some(history) = &self.history {
+            history.record(Event::fed(prices, tx_hash));
+        }
+    }
+}
+
 async fn handle_idle_work(
     node_client: &NodeClient,
     provider_name: &str,
     commit_result_receiver: &mut CommitResultReceiver,
-    poll_delivered_tx_set: &mut JoinSet<Option<(TxHash, TxResponse)>>,
-    fallback_gas_limit: &mut NonZeroU64,
+    tx_tracking: &mut TxTracking,
     tick_time: Duration,
     poll_time: Duration,
 ) -> ChannelClosed {
     loop {
         select! {
             maybe_result = commit_result_receiver.recv() => {
-                if let Some(result) = maybe_result {
+                if let Some(outcome) = maybe_result {
                     handle_commit_result(
                         node_client,
-                        poll_delivered_tx_set,
-                        result,
+                        tx_tracking,
+                        outcome,
                         tick_time,
                         poll_time,
                     );
@@ -566,8 +1279,8 @@ async fn handle_idle_work(
                     break ChannelClosed {};
                 }
             }
-            Some(result) = poll_delivered_tx_set.join_next(), if !poll_delivered_tx_set.is_empty() => {
-                handle_delivered_tx(provider_name, fallback_gas_limit, result);
+            Some(result) = tx_tracking.poll_delivered_tx_set.join_next(), if !tx_tracking.poll_delivered_tx_set.is_empty() => {
+                handle_delivered_tx(provider_name, tx_tracking, result);
             }
         }
     }
@@ -575,30 +1288,48 @@ async fn handle_idle_work(
 
 fn handle_commit_result(
     node_client: &NodeClient,
-    poll_delivered_tx_set: &mut JoinSet<Option<(TxHash, TxResponse)>>,
-    result: CommitResult,
+    tx_tracking: &mut TxTracking,
+    CommitOutcome { request_id, result }: CommitOutcome,
     tick_time: Duration,
     poll_time: Duration,
 ) {
     match result {
         Ok(tx_hash) => {
+            tx_tracking.on_commit_result(request_id, Some(&tx_hash));
+
+            if let Some(history) = &tx_tracking.history {
+                history.record(Event::Committed {
+                    tx_hash: tx_hash.0.clone(),
+                });
+            }
+
             let node_client: NodeClient = node_client.clone();
 
-            poll_delivered_tx_set.spawn(async move {
-                poll_delivered_tx(
+            tx_tracking.poll_delivered_tx_set.spawn(async move {
+                let tx: Option<TxResponse> = poll_delivered_tx(
                     &node_client,
                     tick_time,
                     poll_time,
                     tx_hash.clone(),
                 )
-                .await
-                .map(|tx| (tx_hash, tx))
+                .await;
+
+                (tx_hash, tx)
             });
         },
         Err(CommitError {
             r#type,
             tx_response,
         }) => {
+            tx_tracking.on_commit_result(request_id, None);
+
+            if let Some(history) = &tx_tracking.history {
+                history.record(Event::CommitFailed {
+                    code: tx_response.code.value(),
+                    raw_log: tx_response.raw_log.to_string(),
+                });
+            }
+
             error!(
                 code = tx_response.code.value(),
                 raw_log = tx_response.raw_log,
@@ -615,19 +1346,34 @@ fn handle_commit_result(
 
 fn handle_delivered_tx(
     provider_name: &str,
-    fallback_gas_limit: &mut NonZeroU64,
-    result: Result<Option<(TxHash, TxResponse)>, JoinError>,
+    tx_tracking: &mut TxTracking,
+    result: Result<(TxHash, Option<TxResponse>), JoinError>,
 ) {
     match result {
-        Ok(Some((tx_hash, tx_result))) => {
-            crate::log::tx_response(provider_name, &tx_hash, &tx_result);
+        Ok((tx_hash, tx_result)) => {
+            if let Some(feed_tracker) = &mut tx_tracking.feed_tracker {
+                feed_tracker.on_delivered(
+                    &tx_hash,
+                    tx_result.as_ref().is_some_and(|tx_result: &TxResponse| {
+                        tx_result.code.is_ok()
+                    }),
+                    Instant::now(),
+                );
+            }
 
-            *fallback_gas_limit = update_fallback_gas_limit(
-                *fallback_gas_limit,
-                tx_result.gas_used,
-            );
+            if let Some(history) = &tx_tracking.history {
+                history.record(Event::delivered(&tx_hash, tx_result.as_ref()));
+            }
+
+            if let Some(tx_result) = tx_result {
+                crate::log::tx_response(provider_name, &tx_hash, &tx_result);
+
+                tx_tracking.fallback_gas_limit = update_fallback_gas_limit(
+                    tx_tracking.fallback_gas_limit,
+                    tx_result.gas_used,
+                );
+            }
         },
-        Ok(None) => {},
         Err(error) => {
             error!(
                 "Task polling delivered transaction {}!",
@@ -665,3 +1411,75 @@ fn update_fallback_gas_limit(
         || unreachable!(),
     )
 }
+
+#[cfg(test)]
+#[test]
+fn test_report_denom_mismatches() {
+    let mismatch: DenomMismatch = DenomMismatch::Missing {
+        ticker: "ATOM".into(),
+        denom: Arc::from("uatom"),
+    };
+
+    assert_eq!(
+        report_denom_mismatches(
+            "provider",
+            Ok(Box::new([mismatch.clone(), mismatch]))
+        ),
+        2
+    );
+
+    assert_eq!(
+        report_denom_mismatches(
+            "provider",
+            Err(ProviderError::NonAsciiResponse(String::new()))
+        ),
+        0
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_fed_prices_are_recorded_with_their_commit_results() {
+    let (history, mut records): (
+        Recorder,
+        UnboundedReceiver<crate::history::Record>,
+    ) = crate::history::test_recorder();
+
+    let mut tx_tracking: TxTracking = TxTracking {
+        poll_delivered_tx_set: JoinSet::new(),
+        fallback_gas_limit: NonZeroU64::MIN,
+        feed_tracker: None,
+        history: Some(history),
+        unrecorded_fed: BTreeMap::new(),
+    };
+
+    let now: Instant = Instant::now();
+
+    for (request_id, amount_quote) in [(0, 1), (1, 2), (2, 3)] {
+        tx_tracking.on_sent(
+            request_id,
+            feed_tracker::test_prices(&[("ATOM", amount_quote)]),
+            now,
+        );
+    }
+
+    // The request sent first never got committed, e.g. because it was
+    // replaced by the second one before being broadcast.
+    tx_tracking.on_commit_result(1, Some(&TxHash(String::from("HASH"))));
+
+    tx_tracking.on_commit_result(2, None);
+
+    let fed: Vec<(Option<String>, u128)> =
+        std::iter::from_fn(|| records.try_recv().ok())
+            .map(|record: crate::history::Record| match record.event {
+                Event::Fed { tx_hash, prices } => {
+                    (tx_hash, prices[0].quote.amount.parse().unwrap())
+                },
+                event => panic!("Unexpected event: {event:?}"),
+            })
+            .collect();
+
+    assert_eq!(fed, [(None, 1), (Some(String::from("HASH")), 2), (None, 3)]);
+
+    assert!(tx_tracking.unrecorded_fed.is_empty());
+}
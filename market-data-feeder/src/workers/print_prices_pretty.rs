@@ -31,13 +31,16 @@ fn do_print(prices: &[Price<CoinWithDecimalPlaces>]) {
                     price.amount().ticker().len(),
                 );
 
-                let quote_amount: String = normalize_and_stringify_quote(price);
+                let quote_amount: String = price
+                    .to_decimal_string()
+                    .unwrap_or_else(|| String::from("N/A"));
 
-                let Some((quote_whole, quote_fraction)) =
-                    quote_amount.split_once('.')
-                else {
-                    unreachable!()
-                };
+                // Fraction keeps its leading dot, so whole numbers are
+                // aligned without one.
+                let (quote_whole, quote_fraction): (&str, &str) = quote_amount
+                    .split_at(
+                        quote_amount.find('.').unwrap_or(quote_amount.len()),
+                    );
 
                 assign_max(&mut max_quote_fraction_width, quote_fraction.len());
 
@@ -59,7 +62,7 @@ fn do_print(prices: &[Price<CoinWithDecimalPlaces>]) {
 
     for (price, quote_whole, quote_fraction) in prices {
         info!(
-            "\t1 {base_denom:<base_denom_width$} ~ {quote_whole:>quote_whole_width$}.{quote_fraction:<quote_fraction_width$} {quote_denom}",
+            "\t1 {base_denom:<base_denom_width$} ~ {quote_whole:>quote_whole_width$}{quote_fraction:<quote_fraction_width$} {quote_denom}",
             base_denom = price.amount().ticker(),
             quote_whole = quote_whole,
             quote_fraction = quote_fraction,
@@ -124,33 +127,3 @@ fn cmp_price_tickers(
                 .cmp(right_price.amount().ticker())
         })
 }
-
-fn normalize_and_stringify_quote(
-    price: &Price<CoinWithDecimalPlaces>,
-) -> String {
-    #[allow(clippy::cast_precision_loss)]
-    let base_f64: f64 = (price.amount_quote().amount()
-        * 10_u128.pow(
-            price
-                .amount()
-                .decimal_places()
-                .saturating_sub(price.amount_quote().decimal_places())
-                .into(),
-        )) as f64;
-
-    #[allow(clippy::cast_precision_loss)]
-    let quote_f64: f64 = (price.amount().amount()
-        * 10_u128.pow(
-            price
-                .amount_quote()
-                .decimal_places()
-                .saturating_sub(price.amount().decimal_places())
-                .into(),
-        )) as f64;
-
-    format!(
-        "{quote:.decimal_places$}",
-        quote = base_f64 / quote_f64,
-        decimal_places = price.amount_quote().decimal_places().into()
-    )
-}
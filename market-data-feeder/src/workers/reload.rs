@@ -0,0 +1,618 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+    future::pending,
+    path::PathBuf,
+    sync::Arc,
+};
+
+#[cfg(unix)]
+use tokio::signal::unix::{signal, Signal, SignalKind};
+use tokio::{
+    runtime::Handle, sync::mpsc::WeakUnboundedSender, task::block_in_place,
+};
+#[cfg(not(unix))]
+use tracing::warn;
+use tracing::{error, info};
+
+use broadcast::{
+    generators::{
+        GeneratorsUpdate, GeneratorsUpdateSender, TxRequest, TxRequestSender,
+    },
+    mode::NonBlocking,
+};
+use chain_comms::client::Client as NodeClient;
+
+use crate::{
+    config::{
+        Aggregate as AggregateConfig,
+        ComparisonProvider as ComparisonProviderConfig, Config,
+        ProviderWithComparison as ProviderWithComparisonConfig,
+    },
+    provider::{ComparisonProvider, Provider},
+    result::Result as AppResult,
+};
+
+#[cfg(test)]
+use crate::{
+    config::AggregationMethod,
+    price::{CoinWithDecimalPlaces, Price},
+    provider::{Coverage, DenomMismatch, ProviderError},
+};
+
+use super::{
+    check_feeder, construct_comparison_provider_f, construct_feeder,
+    ensure_checked, report_denom_mismatches, try_for_each_provider_f,
+    ConstructedComparisonProvider, ConstructedFeeder, Feeder,
+    TryForEachProviderContext, WorkersContext,
+};
+
+/// Constructs the providers and the aggregates. Replaced in tests, so that
+/// they don't reach the network.
+type ConstructFeeder =
+    fn(&str, Feeder, &NodeClient) -> AppResult<Box<dyn Provider>>;
+
+/// Workers, as last spawned, against which the reloaded configuration is
+/// diffed.
+pub(super) struct Reloader {
+    config_path: PathBuf,
+    workers_context: WorkersContext,
+    /// Kept weak, so that the broadcast loop still exits once all of the
+    /// workers have stopped.
+    tx_request_sender: WeakUnboundedSender<TxRequest<NonBlocking>>,
+    construct_feeder: ConstructFeeder,
+    feeders: BTreeMap<Box<str>, SpawnedFeeder>,
+    price_comparison_providers:
+        BTreeMap<Arc<str>, ConstructedComparisonProvider>,
+    next_monotonic_id: usize,
+}
+
+struct SpawnedFeeder {
+    monotonic_id: usize,
+    feeder: Feeder,
+}
+
+impl Reloader {
+    /// Expects the feeders in the order in which they are spawned, as their
+    /// monotonic IDs are assigned by it.
+    pub(super) fn new(
+        config_path: PathBuf,
+        workers_context: WorkersContext,
+        tx_request_sender: &TxRequestSender<NonBlocking>,
+        feeders: &[ConstructedFeeder],
+        price_comparison_providers: BTreeMap<
+            Arc<str>,
+            ConstructedComparisonProvider,
+        >,
+    ) -> Self {
+        Self {
+            config_path,
+            workers_context,
+            tx_request_sender: tx_request_sender.downgrade(),
+            construct_feeder,
+            feeders: feeders
+                .iter()
+                .enumerate()
+                .map(
+                    |(monotonic_id, constructed): (
+                        usize,
+                        &ConstructedFeeder,
+                    )| {
+                        (
+                            constructed.provider_id.clone(),
+                            SpawnedFeeder {
+                                monotonic_id,
+                                feeder: constructed.feeder.clone(),
+                            },
+                        )
+                    },
+                )
+                .collect(),
+            price_comparison_providers,
+            next_monotonic_id: feeders.len(),
+        }
+    }
+
+    /// Reloads the configuration each time `SIGHUP` is received.
+    #[cfg(unix)]
+    pub(super) async fn run(
+        mut self,
+        mut updates_sender: GeneratorsUpdateSender,
+    ) -> Infallible {
+        let mut hangup: Signal = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(error) => {
+                error!(
+                    ?error,
+                    "Failed to install SIGHUP signal handler! Configuration won't be reloaded. Cause: {error}"
+                );
+
+                return pending().await;
+            },
+        };
+
+        while hangup.recv().await.is_some() {
+            info!(
+                path = %self.config_path.display(),
+                "Received SIGHUP signal. Reloading configuration..."
+            );
+
+            match crate::read_config(&self.config_path).await {
+                Ok(Config {
+                    providers,
+                    aggregates,
+                    comparison_providers,
+                    strict_coverage,
+                    ..
+                }) => {
+                    let feeders: BTreeMap<Box<str>, Feeder> = providers
+                        .into_iter()
+                        .map(
+                            |(id, config): (
+                                Box<str>,
+                                ProviderWithComparisonConfig,
+                            )| {
+                                (id, Feeder::Provider(config))
+                            },
+                        )
+                        .chain(aggregates.into_iter().map(
+                            |(id, config): (Box<str>, AggregateConfig)| {
+                                (id, Feeder::Aggregate(config))
+                            },
+                        ))
+                        .collect();
+
+                    block_in_place(|| {
+                        self.reload(
+                            feeders,
+                            comparison_providers,
+                            strict_coverage,
+                            &mut updates_sender,
+                        );
+                    });
+                },
+                Err(error) => {
+                    error!(
+                        ?error,
+                        "Failed to reload configuration! Workers are left as they are. Cause: {error}"
+                    );
+                },
+            }
+        }
+
+        pending().await
+    }
+
+    /// Configuration is reloaded only on `SIGHUP`, which is specific to Unix.
+    #[cfg(not(unix))]
+    pub(super) async fn run(self, _: GeneratorsUpdateSender) -> Infallible {
+        warn!("Configuration reloading is supported only on Unix platforms! Changes to the configuration take effect after a restart.");
+
+        pending().await
+    }
+
+    /// Stops the workers which are no longer configured and spawns the ones
+    /// which are new or whose configuration, or the configuration of one of
+    /// their comparison providers, has changed. The rest are left running.
+    ///
+    /// Workers are checked as on start, before being spawned. Workers which
+    /// fail to be constructed or checked are logged and the previously
+    /// spawned ones, if any, are left running.
+    fn reload(
+        &mut self,
+        feeders: BTreeMap<Box<str>, Feeder>,
+        comparison_providers: BTreeMap<Arc<str>, ComparisonProviderConfig>,
+        strict_coverage: bool,
+        updates_sender: &mut GeneratorsUpdateSender,
+    ) {
+        let Some(tx_request_sender) = self.tx_request_sender.upgrade() else {
+            error!("All workers have stopped! Configuration is not reloaded.");
+
+            return;
+        };
+
+        let changed_comparison_providers: BTreeSet<Arc<str>> =
+            self.reload_comparison_providers(comparison_providers);
+
+        self.stop_removed(&feeders, updates_sender);
+
+        let mut spawn_feeder =
+            try_for_each_provider_f(TryForEachProviderContext {
+                workers_context: self.workers_context.clone(),
+                tx_request_sender,
+                generators: &mut *updates_sender,
+                price_comparison_providers: self
+                    .price_comparison_providers
+                    .iter()
+                    .map(
+                        |(provider_id, constructed): (
+                            &Arc<str>,
+                            &ConstructedComparisonProvider,
+                        )| {
+                            (provider_id.clone(), constructed.provider.clone())
+                        },
+                    )
+                    .collect(),
+            });
+
+        let mut stopped: Vec<usize> = Vec::new();
+
+        for (provider_id, feeder) in feeders {
+            if self.feeders.get(&provider_id).is_some_and(
+                |spawned: &SpawnedFeeder| {
+                    spawned.feeder == feeder
+                        && !compares_with_any(
+                            &feeder,
+                            &changed_comparison_providers,
+                        )
+                },
+            ) {
+                continue;
+            }
+
+            let monotonic_id: usize = self.next_monotonic_id;
+
+            self.next_monotonic_id += 1;
+
+            let result: AppResult<()> = (self.construct_feeder)(
+                &provider_id,
+                feeder.clone(),
+                &self.workers_context.node_client,
+            )
+            .and_then(|provider: Box<dyn Provider>| {
+                Handle::current()
+                    .block_on(check_feeder(
+                        &provider_id,
+                        &provider,
+                        strict_coverage,
+                    ))
+                    .and_then(|(mismatches, dropped): (usize, usize)| {
+                        ensure_checked(mismatches, dropped)
+                    })
+                    .map(|()| provider)
+            })
+            .and_then(|provider: Box<dyn Provider>| {
+                spawn_feeder((
+                    monotonic_id,
+                    ConstructedFeeder {
+                        provider_id: provider_id.clone(),
+                        feeder: feeder.clone(),
+                        provider,
+                    },
+                ))
+            });
+
+            match result {
+                Ok(()) => {
+                    if let Some(previous) = self.feeders.insert(
+                        provider_id.clone(),
+                        SpawnedFeeder {
+                            monotonic_id,
+                            feeder,
+                        },
+                    ) {
+                        stopped.push(previous.monotonic_id);
+
+                        info!(%provider_id, "Worker restarted with changed configuration.");
+                    } else {
+                        info!(%provider_id, "Worker started.");
+                    }
+                },
+                Err(error) => {
+                    error!(
+                        %provider_id,
+                        ?error,
+                        "Failed to construct or check worker! Previously spawned one, if any, is left running. Cause: {error}"
+                    );
+                },
+            }
+        }
+
+        drop(spawn_feeder);
+
+        for monotonic_id in stopped {
+            stop(updates_sender, monotonic_id);
+        }
+
+        info!("Configuration reloaded. Changes to anything other than the providers, the aggregates and the comparison providers take effect after a restart.");
+    }
+
+    /// Stops the workers which are no longer configured.
+    fn stop_removed(
+        &mut self,
+        feeders: &BTreeMap<Box<str>, Feeder>,
+        updates_sender: &GeneratorsUpdateSender,
+    ) {
+        self.feeders.retain(
+            |provider_id, spawned: &mut SpawnedFeeder| {
+                feeders.contains_key(provider_id) || {
+                    stop(updates_sender, spawned.monotonic_id);
+
+                    info!(%provider_id, "Worker stopped, as it's no longer configured.");
+
+                    false
+                }
+            },
+        );
+    }
+
+    /// Constructs and checks the comparison providers which are new or whose
+    /// configuration has changed and returns their identifiers, along with
+    /// the ones of the removed ones.
+    fn reload_comparison_providers(
+        &mut self,
+        configs: BTreeMap<Arc<str>, ComparisonProviderConfig>,
+    ) -> BTreeSet<Arc<str>> {
+        let mut changed: BTreeSet<Arc<str>> = BTreeSet::new();
+
+        self.price_comparison_providers.retain(
+            |provider_id: &Arc<str>, _: &mut ConstructedComparisonProvider| {
+                configs.contains_key(provider_id) || {
+                    changed.insert(provider_id.clone());
+
+                    false
+                }
+            },
+        );
+
+        let construct =
+            construct_comparison_provider_f(&self.workers_context.node_client);
+
+        for (provider_id, config) in configs {
+            if self
+                .price_comparison_providers
+                .get(&provider_id)
+                .is_some_and(|constructed: &ConstructedComparisonProvider| {
+                    constructed.config == config
+                })
+            {
+                continue;
+            }
+
+            match construct((provider_id.clone(), config.clone())).and_then(
+                |(provider_id, provider): (
+                    Arc<str>,
+                    Arc<dyn ComparisonProvider>,
+                )| {
+                    ensure_checked(
+                        report_denom_mismatches(
+                            &provider_id,
+                            Handle::current().block_on(provider.check_denoms()),
+                        ),
+                        0,
+                    )
+                    .map(|()| (provider_id, provider))
+                },
+            ) {
+                Ok((provider_id, provider)) => {
+                    self.price_comparison_providers.insert(
+                        provider_id.clone(),
+                        ConstructedComparisonProvider { config, provider },
+                    );
+
+                    changed.insert(provider_id);
+                },
+                Err(error) => {
+                    error!(
+                        %provider_id,
+                        ?error,
+                        "Failed to construct or check comparison provider! Previously constructed one, if any, is kept. Cause: {error}"
+                    );
+                },
+            }
+        }
+
+        changed
+    }
+}
+
+fn compares_with_any(
+    feeder: &Feeder,
+    provider_ids: &BTreeSet<Arc<str>>,
+) -> bool {
+    feeder.options().comparison.is_some_and(|comparison| {
+        comparison
+            .provider_ids
+            .iter()
+            .any(|provider_id: &Arc<str>| provider_ids.contains(provider_id))
+    })
+}
+
+fn stop(updates_sender: &GeneratorsUpdateSender, monotonic_id: usize) {
+    _ = updates_sender.send(GeneratorsUpdate::Stop {
+        sender_id: monotonic_id,
+    });
+}
+
+#[cfg(test)]
+struct TestProvider(Box<str>);
+
+#[cfg(test)]
+#[async_trait::async_trait]
+impl Provider for TestProvider {
+    fn instance_id(&self) -> &str {
+        &self.0
+    }
+
+    async fn get_prices(
+        &self,
+        _: bool,
+    ) -> Result<Box<[Price<CoinWithDecimalPlaces>]>, ProviderError> {
+        Ok(Box::default())
+    }
+
+    async fn get_prices_with_coverage(
+        &self,
+        fault_tolerant: bool,
+    ) -> Result<
+        (Box<[Price<CoinWithDecimalPlaces>]>, Option<Coverage>),
+        ProviderError,
+    > {
+        let mut coverage: Coverage = Coverage::new();
+
+        if &*self.0 == "uncovered" {
+            coverage.request("ATOM", "OSMO");
+        }
+
+        self.get_prices(fault_tolerant)
+            .await
+            .map(|prices| (prices, Some(coverage)))
+    }
+
+    async fn check_denoms(
+        &self,
+    ) -> Result<Box<[DenomMismatch]>, ProviderError> {
+        Ok(if &*self.0 == "mismatched" {
+            Box::new([DenomMismatch::Missing {
+                ticker: "ATOM".into(),
+                denom: Arc::from("uatom"),
+            }])
+        } else {
+            Box::default()
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unnecessary_wraps)]
+fn test_construct_feeder(
+    provider_id: &str,
+    _: Feeder,
+    _: &NodeClient,
+) -> AppResult<Box<dyn Provider>> {
+    Ok(Box::new(TestProvider(provider_id.into())))
+}
+
+#[cfg(test)]
+fn test_feeder(min_sources: usize) -> Feeder {
+    Feeder::Aggregate(AggregateConfig {
+        oracle_address: "oracle".into(),
+        method: AggregationMethod::Median,
+        min_sources: std::num::NonZeroUsize::new(min_sources).unwrap(),
+        sources: BTreeMap::new(),
+        comparison: None,
+        feeding: None,
+        jump_guard: None,
+        cross_rates: None,
+    })
+}
+
+#[cfg(test)]
+fn test_updates(
+    updates_receiver: &mut broadcast::generators::GeneratorsUpdateReceiver,
+) -> Vec<(bool, usize)> {
+    std::iter::from_fn(|| updates_receiver.try_recv().ok())
+        .map(|update: GeneratorsUpdate| match update {
+            GeneratorsUpdate::Spawn { sender_id, .. } => (true, sender_id),
+            GeneratorsUpdate::Stop { sender_id } => (false, sender_id),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reload() {
+    // Connecting only needs the listener to be bound, as nothing is served.
+    let listener: std::net::TcpListener =
+        std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+
+    let node_client: NodeClient = NodeClient::new(
+        &format!("http://{}", listener.local_addr().unwrap()),
+        None,
+    )
+    .await
+    .unwrap();
+
+    let (tx_request_sender, _tx_request_receiver) =
+        tokio::sync::mpsc::unbounded_channel();
+
+    let feeders: Vec<ConstructedFeeder> =
+        ["kept", "removed", "changed", "mismatched", "uncovered"]
+            .into_iter()
+            .map(|provider_id: &str| ConstructedFeeder {
+                provider_id: provider_id.into(),
+                feeder: test_feeder(1),
+                provider: Box::new(TestProvider(provider_id.into())),
+            })
+            .collect();
+
+    let mut reloader: Reloader = Reloader::new(
+        PathBuf::new(),
+        WorkersContext {
+            node_client,
+            signer_address: "signer".into(),
+            hard_gas_limit: std::num::NonZeroU64::new(1).unwrap(),
+            time_before_feeding: std::time::Duration::ZERO,
+            tick_time: std::time::Duration::from_secs(1),
+            poll_time: std::time::Duration::from_secs(1),
+            history: None,
+        },
+        &tx_request_sender,
+        &feeders,
+        BTreeMap::new(),
+    );
+
+    reloader.construct_feeder = test_construct_feeder;
+
+    let (mut updates_sender, mut updates_receiver) =
+        broadcast::generators::new_updates_channel();
+
+    block_in_place(|| {
+        reloader.reload(
+            [
+                ("added", test_feeder(1)),
+                ("changed", test_feeder(2)),
+                ("kept", test_feeder(1)),
+                ("mismatched", test_feeder(2)),
+                ("uncovered", test_feeder(2)),
+            ]
+            .into_iter()
+            .map(|(provider_id, feeder): (&str, Feeder)| {
+                (provider_id.into(), feeder)
+            })
+            .collect(),
+            BTreeMap::new(),
+            true,
+            &mut updates_sender,
+        );
+    });
+
+    // The changed feeders failing the checks are left running, as they are.
+    assert_eq!(
+        test_updates(&mut updates_receiver),
+        [(false, 1), (true, 5), (true, 6), (false, 2)]
+    );
+
+    assert_eq!(
+        reloader
+            .feeders
+            .iter()
+            .map(|(provider_id, spawned)| {
+                (&**provider_id, spawned.monotonic_id)
+            })
+            .collect::<Vec<(&str, usize)>>(),
+        [
+            ("added", 5),
+            ("changed", 6),
+            ("kept", 0),
+            ("mismatched", 3),
+            ("uncovered", 4),
+        ]
+    );
+
+    // Workers hold the only other clones of the sender.
+    drop(tx_request_sender);
+
+    block_in_place(|| {
+        reloader.reload(
+            BTreeMap::new(),
+            BTreeMap::new(),
+            false,
+            &mut updates_sender,
+        );
+    });
+
+    assert!(test_updates(&mut updates_receiver).is_empty());
+
+    assert_eq!(reloader.feeders.len(), 5);
+}